@@ -0,0 +1,176 @@
+//! Account decoding and risk math for off-chain clients (keepers, indexers)
+//!
+//! Everything here runs the same code paths as the on-chain instructions on
+//! copies of the decoded accounts, so a keeper sees exactly the debt and health
+//! factor a liquidation would see at the same timestamp and price.
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use crate::constants::{BPS_PRECISION, EMA_FOR_LIQUIDATIONS};
+use crate::errors::CreditError;
+use crate::oracle::PythPrice;
+use crate::state::{Config, DebitAccount, DebtValuation, UserPosition};
+
+/// Borsh-decode an account's data after checking its 8-byte discriminator
+fn decode<T: AnchorDeserialize + Discriminator>(data: &[u8]) -> Result<T> {
+    require!(data.len() >= 8, ErrorCode::AccountDiscriminatorNotFound);
+    require!(data[..8] == T::DISCRIMINATOR, ErrorCode::AccountDiscriminatorMismatch);
+
+    let mut body = &data[8..];
+    T::deserialize(&mut body).map_err(|_| error!(ErrorCode::AccountDidNotDeserialize))
+}
+
+/// Decode raw `Config` account data
+pub fn decode_config(data: &[u8]) -> Result<Config> {
+    decode(data)
+}
+
+/// Decode raw `UserPosition` account data
+pub fn decode_user_position(data: &[u8]) -> Result<UserPosition> {
+    decode(data)
+}
+
+/// Decode raw `DebitAccount` account data
+pub fn decode_debit_account(data: &[u8]) -> Result<DebitAccount> {
+    decode(data)
+}
+
+/// The config with interest accrued up to `now`, as an instruction would see it
+pub fn accrued_config(config: &Config, now: i64) -> Result<Config> {
+    let mut accrued = config.clone();
+    accrued.accrue_interest(now)?;
+    Ok(accrued)
+}
+
+/// Position debt with interest settled up to `now` (including any subsidy)
+pub fn current_debt(position: &UserPosition, config: &Config, now: i64) -> Result<u64> {
+    let mut accrued = accrued_config(config, now)?;
+    let mut settled = position.clone();
+    settled.settle_interest(&mut accrued, now)
+}
+
+/// Health factor (bps) a liquidation at `now` would compute for the position
+///
+/// `oracle_price` is the feed as read off-chain; the EMA flag and liquidation
+/// valuation mode from the config are applied the same way `liquidate` does.
+/// `debt_valuation` prices the position's debt asset (`DebtValuation::USDC`
+/// for USDC debt).
+pub fn liquidation_health_factor(
+    position: &UserPosition,
+    config: &Config,
+    oracle_price: PythPrice,
+    debt_valuation: DebtValuation,
+    now: i64,
+) -> Result<u64> {
+    let price = oracle_price
+        .with_ema(config.uses_ema_for(EMA_FOR_LIQUIDATIONS))?
+        .valued_at(config.liquidation_valuation_mode);
+    let debt_value = debt_valuation.value(current_debt(position, config, now)?)?;
+
+    position.calculate_health_factor(price, config.liquidation_threshold_bps, debt_value)
+}
+
+/// Whether `liquidate` would accept the position at `now` and this price
+pub fn is_liquidatable(
+    position: &UserPosition,
+    config: &Config,
+    oracle_price: PythPrice,
+    debt_valuation: DebtValuation,
+    now: i64,
+) -> Result<bool> {
+    require!(position.is_initialized, CreditError::PositionNotInitialized);
+
+    let health_factor = liquidation_health_factor(position, config, oracle_price, debt_valuation, now)?;
+    let liquidation_cutoff = BPS_PRECISION.saturating_sub(config.liquidation_health_epsilon_bps as u64);
+
+    Ok(health_factor < liquidation_cutoff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::RAY_PRECISION;
+
+    const START: i64 = 1_700_000_000;
+    const YEAR: i64 = 365 * 24 * 60 * 60;
+
+    /// Account data as the program would write it: discriminator plus Borsh body
+    fn account_data<T: AccountSerialize>(account: &T) -> Vec<u8> {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    /// A zeroed account of `len` bytes, decoded so tests only set what they use
+    fn zeroed<T: AnchorDeserialize + Discriminator>(len: usize) -> T {
+        let mut data = vec![0u8; len];
+        data[..8].copy_from_slice(&T::DISCRIMINATOR);
+        decode(&data).unwrap()
+    }
+
+    fn config() -> Config {
+        let mut config: Config = zeroed(Config::LEN);
+        config.liquidation_threshold_bps = 6000;
+        config.interest_rate_bps = 1200;
+        config.global_borrow_index = RAY_PRECISION;
+        config.last_update_timestamp = START;
+        config.liquidation_health_epsilon_bps = 0;
+        config
+    }
+
+    // 10 SOL against $400 of USDC debt, taken at the current index
+    fn position() -> UserPosition {
+        let mut position: UserPosition = zeroed(UserPosition::LEN);
+        position.is_initialized = true;
+        position.owner = Pubkey::new_unique();
+        position.collateral_mint = Pubkey::new_unique();
+        position.collateral_amount = 10_000_000_000;
+        position.debt_usdc = 400_000_000;
+        position.borrow_index_snapshot = RAY_PRECISION;
+        position.last_interest_timestamp = START;
+        position
+    }
+
+    fn price(price: u64) -> PythPrice {
+        PythPrice { price, confidence: 0, publish_slot: 0, ema_price: price, ema_confidence: 0 }
+    }
+
+    #[test]
+    fn round_trips_accounts_and_computes_health() {
+        let config = decode_config(&account_data(&config())).unwrap();
+        let position = decode_user_position(&account_data(&position())).unwrap();
+        assert_eq!(config.liquidation_threshold_bps, 6000);
+        assert_eq!(position.collateral_amount, 10_000_000_000);
+        assert_eq!(position.debt_usdc, 400_000_000);
+
+        // $1000 of collateral against $400: 1000 * 60% / 400 = 1.5
+        let health = liquidation_health_factor(&position, &config, price(100_000_000), DebtValuation::USDC, START).unwrap();
+        assert_eq!(health, 15_000);
+        assert!(!is_liquidatable(&position, &config, price(100_000_000), DebtValuation::USDC, START).unwrap());
+
+        // At $60 the same debt is 600 * 60% / 400 = 0.9
+        let health = liquidation_health_factor(&position, &config, price(60_000_000), DebtValuation::USDC, START).unwrap();
+        assert_eq!(health, 9_000);
+        assert!(is_liquidatable(&position, &config, price(60_000_000), DebtValuation::USDC, START).unwrap());
+    }
+
+    #[test]
+    fn accrues_decoded_debt_to_the_given_time() {
+        let config = decode_config(&account_data(&config())).unwrap();
+        let position = decode_user_position(&account_data(&position())).unwrap();
+
+        // A year at 12% grows $400 by $48
+        let debt = current_debt(&position, &config, START + YEAR).unwrap();
+        assert_eq!(debt, 448_000_000);
+
+        let health = liquidation_health_factor(&position, &config, price(100_000_000), DebtValuation::USDC, START + YEAR).unwrap();
+        assert!(health < 15_000);
+    }
+
+    #[test]
+    fn rejects_data_of_another_account_type() {
+        let data = account_data(&config());
+        assert!(decode_user_position(&data).is_err());
+        assert!(decode_config(&data[..4]).is_err());
+    }
+}
@@ -3,21 +3,105 @@ pub const CONFIG_SEED: &[u8] = b"config";
 pub const USER_POSITION_SEED: &[u8] = b"user_position";
 pub const VAULT_SEED: &[u8] = b"vault";
 pub const VAULT_AUTHORITY_SEED: &[u8] = b"vault_authority";
+pub const SUPPLIER_POSITION_SEED: &[u8] = b"supplier_position";
+pub const DEBIT_ACCOUNT_SEED: &[u8] = b"debit";
+pub const UNWRAP_SEED: &[u8] = b"unwrap";
+pub const PENDING_PARAMS_SEED: &[u8] = b"pending_params";
 
 // Protocol Parameters (basis points)
 pub const DEFAULT_LTV_MAX_BPS: u16 = 5000; // 50%
 pub const DEFAULT_LIQUIDATION_THRESHOLD_BPS: u16 = 6000; // 60%
 pub const DEFAULT_LIQUIDATION_BONUS_BPS: u16 = 600; // 6%
 pub const DEFAULT_INTEREST_RATE_BPS: u16 = 1200; // 12% APR
+pub const DEFAULT_MAX_INTEREST_RATE_BPS: u16 = 3600; // 36% APR ceiling for admin rate updates
+pub const MAX_INTEREST_RATE_BPS: u16 = 10000; // 100% APR; rates are never negative
 
 // Safety Parameters
-pub const HEALTH_FACTOR_BUFFER_BPS: u16 = 1100; // 1.10 health factor required for borrows
+// Health factors are fixed-point with HEALTH_FACTOR_SCALE = 1.0 (bps); divide by it for a ratio
+pub const HEALTH_FACTOR_SCALE: u64 = 10_000;
+pub const HEALTH_FACTOR_BUFFER_BPS: u16 = 11_000; // 1.10 default health factor required for borrows and withdrawals
+pub const MAX_AUTO_DELEVERAGE_THRESHOLD_BPS: u16 = 20_000; // 2.0 health factor, upper bound for auto-deleverage
+pub const MAX_STOP_LOSS_HEALTH_BPS: u16 = 20_000; // 2.0 health factor, upper bound for a position's stop-loss
+pub const DEFAULT_WARNING_THRESHOLD_BPS: u16 = 11_500; // 1.15 health factor, below which keepers may warn owners
+pub const DEFAULT_DEBIT_DUST_THRESHOLD: u64 = 10_000; // $0.01 of reserved debit funds left after a settle is released
+pub const DEFAULT_MAX_ACTIVE_RESERVATIONS: u16 = 16; // Pending debit authorizations per account
 pub const MAX_CONFIDENCE_DEVIATION_BPS: u16 = 200; // 2% max price confidence deviation
-pub const MAX_ORACLE_STALENESS_SLOTS: u64 = 30; // ~15 seconds at 2 slots/sec
+pub const DEFAULT_LIQUIDATION_HEALTH_EPSILON_BPS: u16 = 1; // Rounding allowance below 1.0 health factor
+pub const MAX_LIQUIDATION_HEALTH_EPSILON_BPS: u16 = 100; // 0.01 health factor, upper bound for admin updates
+pub const DEFAULT_BORROW_MAX_STALENESS_SLOTS: u64 = 60; // ~30 seconds at 2 slots/sec
+pub const DEFAULT_LIQUIDATION_MAX_STALENESS_SLOTS: u64 = 30; // ~15 seconds at 2 slots/sec
+pub const MAX_ORACLE_STALENESS_SLOTS_LIMIT: u64 = 300; // ~2.5 minutes, upper bound for admin updates
+pub const MAX_ORACLE_EXPONENT_ADJUSTMENT: i32 = 12; // Powers of ten a feed is rescaled by to USDC decimals (expo -18..=6)
+pub const MAX_INTEREST_RATE_TIMELOCK_SECS: i64 = 172_800; // 48 hours before a raised rate ceiling applies
+pub const MAX_PARAMS_TIMELOCK_SLOTS: u64 = 432_000; // About two days of slots for queued param changes
+pub const DEFAULT_MIN_ACCRUAL_INTERVAL_SECS: i64 = 60; // Interest compounds at most once a minute
+pub const MAX_ACCRUAL_INTERVAL_SECS: i64 = 86_400; // 1 day, upper bound for admin updates
+pub const MAX_MIN_POSITION_AGE_SECS: i64 = 86_400; // 1 day, upper bound for the first-borrow delay
+pub const DEFAULT_MIN_RESERVE_RATIO_BPS: u16 = 1000; // Treasury keeps 10% of outstanding debt through supply withdrawals
+pub const MAX_PREPAYMENT_FEE_BPS: u16 = 500; // 5% of the repayment, upper bound for the early repayment fee
+pub const MAX_MIN_LOAN_TERM_SECS: i64 = 31_536_000; // 1 year, upper bound for the prepayment fee window
+
+// Collateral registry
+pub const MAX_COLLATERAL_TYPES: usize = 8; // Slots in Config::collateral_types
+pub const MAX_POSITION_COLLATERALS: usize = 4; // Slots in UserPosition::collaterals
+pub const MAX_DEBT_ASSETS: usize = 4; // Slots in Config::debt_assets (USDC is implicit)
+pub const MAX_PROTOCOL_LIQUIDATION_SHARE_BPS: u16 = 5000; // Protocol keeps at most half the bonus
+pub const MAX_FULL_LIQUIDATION_REBATE_BPS: u16 = 500; // Full-liquidation rebate capped at 5% of the repayment
+pub const MIN_LIQUIDATOR_BONUS_BPS: u16 = 200; // 2% bonus left for liquidators after the protocol share
+pub const MAX_LIQUIDATION_BONUS_BPS: u16 = 2000; // 20%, global or per collateral
+pub const DEFAULT_MAX_LIQUIDATION_COLLATERAL_FRACTION_BPS: u16 = 10_000; // No collateral-based cap on a single liquidation
+
+// Debit tiers (index into Config::tier_config)
+pub const MAX_DEBIT_TIERS: usize = 4; // Slots in Config::tier_config
+pub const MAX_ORACLE_DECIMALS: usize = 4; // Slots in Config::oracle_decimals
+pub const MAX_PROTOCOL_OWNERS: usize = 4; // Slots in Config::protocol_owners
+pub const MAX_QUOTE_DECIMALS: u8 = 18; // Largest accepted expected_quote_decimals
+pub const DEBIT_TIER_BASIC: u8 = 0;
+pub const DEBIT_TIER_PREMIUM: u8 = 1;
+pub const BASIC_DAILY_LIMIT: u64 = 1_000_000_000; // $1,000
+pub const BASIC_MONTHLY_LIMIT: u64 = 10_000_000_000; // $10,000
+pub const BASIC_PER_TX_LIMIT: u64 = 500_000_000; // $500
+pub const PREMIUM_DAILY_LIMIT: u64 = 5_000_000_000; // $5,000
+pub const PREMIUM_MONTHLY_LIMIT: u64 = 50_000_000_000; // $50,000
+pub const PREMIUM_PER_TX_LIMIT: u64 = 2_500_000_000; // $2,500
+
+// Pause flags (bitmask in Config::pause_flags)
+pub const PAUSE_DEPOSITS: u8 = 1 << 0;
+pub const PAUSE_BORROWS: u8 = 1 << 1;
+pub const PAUSE_REPAYS: u8 = 1 << 2;
+pub const PAUSE_WITHDRAWALS: u8 = 1 << 3;
+pub const PAUSE_LIQUIDATIONS: u8 = 1 << 4;
+pub const PAUSE_ALL_FLAGS: u8 = PAUSE_DEPOSITS | PAUSE_BORROWS | PAUSE_REPAYS | PAUSE_WITHDRAWALS | PAUSE_LIQUIDATIONS;
+pub const SHUTDOWN_BLOCKED_FLAGS: u8 = PAUSE_DEPOSITS | PAUSE_BORROWS | PAUSE_LIQUIDATIONS; // Only exits stay open after shutdown
+pub const SHUTDOWN_CONFIRMATION: [u8; 8] = *b"SHUTDOWN"; // Must be passed verbatim to admin_shutdown
+
+// Operations valued at the oracle EMA instead of the spot price (bits of Config::ema_price_flags)
+pub const EMA_FOR_DEPOSITS: u8 = 1 << 0;
+pub const EMA_FOR_BORROWS: u8 = 1 << 1;
+pub const EMA_FOR_WITHDRAWALS: u8 = 1 << 2;
+pub const EMA_FOR_LIQUIDATIONS: u8 = 1 << 3;
+pub const EMA_FOR_ALL_FLAGS: u8 = EMA_FOR_DEPOSITS | EMA_FOR_BORROWS | EMA_FOR_WITHDRAWALS | EMA_FOR_LIQUIDATIONS;
+
+// Point of the oracle confidence band collateral is valued at (Config::*_valuation_mode)
+pub const VALUATION_MID: u8 = 0; // Aggregate price
+pub const VALUATION_BID: u8 = 1; // Price minus confidence
+pub const VALUATION_ASK: u8 = 2; // Price plus confidence
+
+// Why a position's credit limit changed (CreditLimitChanged.reason)
+pub const CREDIT_LIMIT_DEPOSIT: u8 = 0;
+pub const CREDIT_LIMIT_WITHDRAWAL: u8 = 1;
+pub const CREDIT_LIMIT_BORROW: u8 = 2;
+pub const CREDIT_LIMIT_REFRESH: u8 = 3;
+pub const CREDIT_LIMIT_MERGE: u8 = 4;
+
+// Client views
+pub const CONFIG_VIEW_VERSION: u8 = 2; // Bump when ConfigView's layout changes
+pub const PROTOCOL_VERSION: u8 = 1; // Bump when instruction arguments change incompatibly
 
 // Precision Constants
 pub const BPS_PRECISION: u64 = 10_000; // Basis points precision
 pub const RAY_PRECISION: u128 = 1_000_000_000_000_000_000_000_000_000; // 27 decimals for interest calculations
+pub const WAD_PRECISION: u128 = 1_000_000_000_000_000_000; // 18 decimals for compounding factors
 pub const SECONDS_PER_YEAR: u64 = 31_536_000; // 365 days
 
 // Token Decimals
@@ -28,6 +112,9 @@ pub const JITO_SOL_DECIMALS: u8 = 9;
 // Limits
 pub const MIN_DEPOSIT_AMOUNT: u64 = 100_000_000; // 0.1 SOL minimum deposit
 pub const MIN_REPAY_AMOUNT: u64 = 1_000_000; // 1 USDC minimum repayment
+pub const DEFAULT_MIN_RESIDUAL_COLLATERAL: u64 = 100_000_000; // 0.1 SOL left after a partial liquidation
+pub const MAX_BATCH_DEPOSITS: usize = 8; // Source accounts per batch deposit
+pub const MAX_BATCH_REPAYS: usize = 8; // Positions per batch repayment
 
 // Supported Collateral (for MVP, only jitoSOL)
 pub const JITO_SOL_MINT: &str = "J1toso1uCk3RLmjorhTtrVwY9HJ7X8V9yYac6Y7kGCPn"; 
@@ -67,4 +67,127 @@ pub enum CreditError {
     
     #[msg("Position unhealthy")]
     UnhealthyPosition,
+    
+    #[msg("Invalid protocol parameter")]
+    InvalidParameter,
+    
+    #[msg("Interest rate exceeds the configured ceiling")]
+    InterestRateAboveCeiling,
+    
+    #[msg("Timelock has not elapsed")]
+    TimelockNotElapsed,
+    
+    #[msg("Position is not an empty bad debt position")]
+    PositionNotBadDebt,
+    
+    #[msg("Collateral type registry is full")]
+    CollateralTypesFull,
+    
+    #[msg("Position still holds collateral")]
+    PositionHasCollateral,
+    
+    #[msg("Position is too new to borrow against")]
+    PositionTooNew,
+    
+    #[msg("Treasury does not hold enough USDC for this borrow")]
+    InsufficientProtocolLiquidity,
+    
+    #[msg("Health factor is above the auto-deleverage threshold")]
+    AutoDeleverageNotTriggered,
+    
+    #[msg("Position collateral slots are full")]
+    PositionCollateralsFull,
+    
+    #[msg("Withdrawal would breach the treasury reserve ratio")]
+    ReserveRatioBreached,
+    
+    #[msg("Token mint is accounted for by the protocol")]
+    ProtocolTokenNotRecoverable,
+    
+    #[msg("Deposit exceeds the protocol collateral value ceiling")]
+    CollateralCapExceeded,
+    
+    #[msg("Insufficient debit balance")]
+    InsufficientBalance,
+    
+    #[msg("Debit account is not active")]
+    AccountNotActive,
+    
+    #[msg("Spend exceeds the debit balance or limits")]
+    SpendingNotAllowed,
+    
+    #[msg("Debt mint must differ from every collateral mint")]
+    DuplicateMint,
+    
+    #[msg("Debit tier is not configured")]
+    InvalidDebitTier,
+    
+    #[msg("Position health factor is not in the liquidation warning band")]
+    PositionNotInWarningBand,
+    
+    #[msg("Protocol reserves cannot cover the repayment")]
+    InsufficientReserves,
+    
+    #[msg("Client version is older than the protocol minimum")]
+    ClientVersionTooOld,
+    
+    #[msg("Emergency action requires explicit confirmation")]
+    ConfirmationRequired,
+    
+    #[msg("Oracle exponent does not match the expected quote decimals")]
+    OracleDecimalsMismatch,
+    
+    #[msg("Collateral oracle has not been healthy recently")]
+    OracleUnhealthy,
+    
+    #[msg("Protocol-owned positions are only liquidatable by the protocol liquidator")]
+    ProtocolPositionRestricted,
+    
+    #[msg("Debit account has too many pending reservations")]
+    TooManyReservations,
+    
+    #[msg("Borrow index is stale, accrue interest earlier in the transaction")]
+    StaleBorrowIndex,
+    
+    #[msg("Liquidation cannot raise the health factor to the target")]
+    TargetHealthUnreachable,
+    
+    #[msg("Seized collateral cannot be sent back to the program vault")]
+    ReceivingAccountIsVault,
+    
+    #[msg("Debt asset registry is full")]
+    DebtAssetsFull,
+    
+    #[msg("Position owes a different debt asset")]
+    DebtMintMismatch,
+    
+    #[msg("Position health factor is not below its stop-loss")]
+    StopLossNotTriggered,
+    
+    #[msg("Position is below the borrow buffer; restore its health and call enforce_buffer")]
+    BufferBreached,
+    
+    #[msg("Borrow would take the position past the per-position debt cap")]
+    PositionDebtCapExceeded,
+    
+    #[msg("No parameter changes are queued")]
+    NoPendingParams,
+    
+    #[msg("Interest accrual would grow the borrow index past the configured maximum jump")]
+    IndexJumpTooLarge,
+    
+    #[msg("Vault token account has a delegate set or is not owned by the vault authority")]
+    VaultDelegateSet,
+    
+    #[msg("Debit account still has spends held against its linked credit line")]
+    CreditHoldsOutstanding,
+    
+    #[msg("Oracle exponent is outside the range the price scaling supports")]
+    OracleExponentOutOfRange,
+    
+    #[msg("Position holds multi-collateral slots, which this instruction cannot price")]
+    SlotCollateralUnsupported,
+    
+    #[msg("Position cannot hold legacy and multi-collateral slot collateral at once")]
+    MixedCollateralFormats,
 }
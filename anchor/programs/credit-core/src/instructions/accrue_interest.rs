@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::Config;
+use crate::utils::get_price_with_fallback;
+
+#[derive(Accounts)]
+pub struct AccrueInterest<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Accrue the global index; any collateral oracles passed in `remaining_accounts`
+/// that are fresh and within the confidence bound are recorded as healthy
+pub fn handler(ctx: Context<AccrueInterest>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+    
+    config.accrue_interest(clock.unix_timestamp)?;
+    
+    for oracle in ctx.remaining_accounts.iter() {
+        require!(
+            config.collateral_types.iter().any(|c| c.is_active() && c.oracle == oracle.key()),
+            CreditError::InvalidOracle
+        );
+        
+        // A degraded feed is simply not marked, so the accrual itself still lands
+        let healthy = match get_price_with_fallback(
+            oracle,
+            &clock,
+            config.borrow_max_staleness_slots,
+            config.expected_quote_decimals(&oracle.key())
+        ) {
+            Ok(price) => price.confidence_bps() <= config.max_confidence_bps as u64,
+            Err(_) => false,
+        };
+        
+        if healthy {
+            for collateral in config.collateral_types.iter_mut() {
+                if collateral.is_active() && collateral.oracle == oracle.key() {
+                    collateral.last_healthy_oracle_slot = clock.slot;
+                }
+            }
+        }
+        msg!("Oracle {} healthy: {}", oracle.key(), healthy);
+    }
+    
+    msg!(
+        "Borrow index: {}, accrued through {}",
+        config.global_borrow_index,
+        config.last_update_timestamp
+    );
+    
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::{Config, UserPosition};
+
+#[derive(Accounts)]
+pub struct AdminCloseBadDebtPosition<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, owner.key().as_ref()],
+        bump,
+        constraint = user_position.owner == owner.key() @ CreditError::Unauthorized,
+        close = owner
+    )]
+    pub user_position: Account<'info, UserPosition>,
+    
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = config.admin == admin.key() @ CreditError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// Position owner, receives the account rent
+    /// CHECK: Validated against position owner
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<AdminCloseBadDebtPosition>) -> Result<()> {
+    let user_position = &mut ctx.accounts.user_position;
+    
+    // Empty position whose whole debt has already been written off
+    require!(
+        user_position.collateral_amount == 0
+            && user_position.bad_debt_usdc > 0
+            && user_position.debt_usdc <= user_position.bad_debt_usdc,
+        CreditError::PositionNotBadDebt
+    );
+    
+    user_position.debt_usdc = 0;
+    
+    msg!(
+        "Closed bad debt position of user: {} ({} USDC written off)",
+        user_position.owner,
+        user_position.bad_debt_usdc
+    );
+    
+    emit!(BadDebtPositionClosed {
+        user: user_position.owner,
+        bad_debt: user_position.bad_debt_usdc,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct BadDebtPositionClosed {
+    pub user: Pubkey,
+    pub bad_debt: u64,
+    pub timestamp: i64,
+}
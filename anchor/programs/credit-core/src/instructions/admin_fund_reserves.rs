@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::Config;
+
+#[derive(Accounts)]
+pub struct AdminFundReserves<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = config.admin == admin.key() @ CreditError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// Admin's USDC token account
+    #[account(
+        mut,
+        constraint = admin_usdc_account.owner == admin.key() @ CreditError::Unauthorized,
+        constraint = admin_usdc_account.mint == config.usdc_mint @ CreditError::InvalidMint
+    )]
+    pub admin_usdc_account: Account<'info, TokenAccount>,
+    
+    /// Program's USDC treasury account
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, config.usdc_mint.as_ref()],
+        bump,
+        token::mint = config.usdc_mint
+    )]
+    pub treasury_usdc_account: Account<'info, TokenAccount>,
+    
+    pub admin: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+/// Move USDC into the treasury as protocol-owned reserves
+pub fn handler(ctx: Context<AdminFundReserves>, amount: u64) -> Result<()> {
+    require!(amount > 0, CreditError::AmountTooSmall);
+    
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.admin_usdc_account.to_account_info(),
+        to: ctx.accounts.treasury_usdc_account.to_account_info(),
+        authority: ctx.accounts.admin.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+    
+    let config = &mut ctx.accounts.config;
+    config.total_reserves_usdc = config.total_reserves_usdc
+        .checked_add(amount)
+        .ok_or(CreditError::MathOverflow)?;
+    
+    msg!("Funded reserves with {} USDC, total: {}", amount, config.total_reserves_usdc);
+    
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::Config;
+
+#[derive(Accounts)]
+pub struct AdminRecoverToken<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = config.admin == admin.key() @ CreditError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// Token account held by the vault authority PDA with the stray tokens
+    #[account(
+        mut,
+        token::authority = vault_authority,
+        constraint = !config.is_protocol_mint(&source_token_account.mint) @ CreditError::ProtocolTokenNotRecoverable
+    )]
+    pub source_token_account: Account<'info, TokenAccount>,
+    
+    /// Recipient of the recovered tokens
+    #[account(
+        mut,
+        token::mint = source_token_account.mint
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+    
+    /// PDA authority for the vaults
+    /// CHECK: This is the PDA that has authority over the vaults
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    pub admin: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+/// Return tokens sent to a program-owned account by mistake
+///
+/// Only mints the protocol does not account for can be moved, so collateral
+/// and treasury USDC are never reachable through this path.
+pub fn handler(ctx: Context<AdminRecoverToken>, amount: u64) -> Result<()> {
+    require!(amount > 0, CreditError::AmountTooSmall);
+    
+    let vault_authority_bump = ctx.bumps.vault_authority;
+    let vault_authority_seeds = &[VAULT_AUTHORITY_SEED, &[vault_authority_bump]];
+    let signer_seeds = &[&vault_authority_seeds[..]];
+    
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.source_token_account.to_account_info(),
+        to: ctx.accounts.recipient_token_account.to_account_info(),
+        authority: ctx.accounts.vault_authority.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds), amount)?;
+    
+    msg!(
+        "Recovered {} of mint {} to {}",
+        amount,
+        ctx.accounts.source_token_account.mint,
+        ctx.accounts.recipient_token_account.key()
+    );
+    
+    Ok(())
+}
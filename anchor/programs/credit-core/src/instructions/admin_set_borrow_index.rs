@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::state::Config;
+
+#[derive(Accounts)]
+pub struct AdminSetBorrowIndex<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = config.admin == admin.key() @ crate::errors::CreditError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub admin: Signer<'info>,
+}
+
+/// Emergency: overwrite a corrupted global borrow index
+///
+/// Every position's debt scales by the index ratio, so this requires `confirm`
+/// and is always emitted for monitoring.
+pub fn handler(ctx: Context<AdminSetBorrowIndex>, new_index: u128, confirm: bool) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let now = Clock::get()?.unix_timestamp;
+    
+    require!(confirm, crate::errors::CreditError::ConfirmationRequired);
+    
+    // The index starts at 1.0 and only grows
+    require!(new_index >= RAY_PRECISION, crate::errors::CreditError::InvalidParameter);
+    
+    // Bring the accrual clock to now so interest resumes from the corrected value
+    config.accrue_interest(now)?;
+    
+    let old_index = config.global_borrow_index;
+    config.global_borrow_index = new_index;
+    
+    msg!("Global borrow index corrected from {} to {}", old_index, new_index);
+    
+    emit!(BorrowIndexCorrected {
+        admin: ctx.accounts.admin.key(),
+        old_index,
+        new_index,
+        timestamp: now,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct BorrowIndexCorrected {
+    pub admin: Pubkey,
+    pub old_index: u128,
+    pub new_index: u128,
+    pub timestamp: i64,
+}
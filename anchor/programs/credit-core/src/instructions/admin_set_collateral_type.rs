@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::constants::*;
+use crate::state::Config;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct CollateralTypeParams {
+    pub protocol_liquidation_share_bps: Option<u16>,
+    pub oracle: Option<Pubkey>,
+    pub liquidation_priority: Option<u8>,
+    pub interest_rate_bps: Option<u16>,
+    /// Exchange-rate feed for appreciating collateral (default pubkey clears it)
+    pub exchange_rate_oracle: Option<Pubkey>,
+    /// Liquidation bonus override (0 = global bonus)
+    pub liquidation_bonus_bps: Option<u16>,
+    /// Borrow-power LTV override (0 = global LTV)
+    pub ltv_bps: Option<u16>,
+}
+
+#[derive(Accounts)]
+#[instruction(mint: Pubkey)]
+pub struct AdminSetCollateralType<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = config.admin == admin.key() @ crate::errors::CreditError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// The collateral mint, read for its decimals
+    #[account(address = mint @ crate::errors::CreditError::InvalidMint)]
+    pub collateral_mint: Account<'info, Mint>,
+    
+    pub admin: Signer<'info>,
+}
+
+/// Register a collateral mint if needed and update its parameters
+pub fn handler(ctx: Context<AdminSetCollateralType>, mint: Pubkey, params: CollateralTypeParams) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    require!(mint != Pubkey::default(), crate::errors::CreditError::InvalidMint);
+    
+    let liquidation_bonus_bps = config.liquidation_bonus_bps;
+    let max_interest_rate_bps = config.max_interest_rate_bps;
+    let liquidation_threshold_bps = config.liquidation_threshold_bps;
+    
+    // Bring every borrow index to now so a rate change only applies going forward
+    if params.interest_rate_bps.is_some() {
+        config.accrue_interest(Clock::get()?.unix_timestamp)?;
+    }
+    
+    let collateral = config.register_collateral_type(mint)?;
+    collateral.decimals = ctx.accounts.collateral_mint.decimals;
+    
+    // Update protocol share of the liquidation bonus if provided
+    if let Some(protocol_liquidation_share_bps) = params.protocol_liquidation_share_bps {
+        require!(
+            protocol_liquidation_share_bps <= MAX_PROTOCOL_LIQUIDATION_SHARE_BPS,
+            crate::errors::CreditError::InvalidPercentage
+        );
+        collateral.protocol_liquidation_share_bps = protocol_liquidation_share_bps;
+        msg!(
+            "Updated protocol liquidation share for {} to {} bps",
+            mint,
+            protocol_liquidation_share_bps
+        );
+    }
+    
+    // Update price feed if provided
+    if let Some(oracle) = params.oracle {
+        require!(oracle != Pubkey::default(), crate::errors::CreditError::InvalidOracle);
+        collateral.oracle = oracle;
+        // A new feed has to be seen healthy before it gates deposits
+        collateral.last_healthy_oracle_slot = 0;
+        msg!("Updated oracle for {} to {}", mint, oracle);
+    }
+    
+    // Update multi-collateral seizure order if provided
+    if let Some(liquidation_priority) = params.liquidation_priority {
+        collateral.liquidation_priority = liquidation_priority;
+        msg!("Updated liquidation priority for {} to {}", mint, liquidation_priority);
+    }
+    
+    // Update collateral-specific borrow rate if provided (0 = global rate)
+    if let Some(interest_rate_bps) = params.interest_rate_bps {
+        require!(
+            interest_rate_bps <= max_interest_rate_bps,
+            crate::errors::CreditError::InterestRateAboveCeiling
+        );
+        collateral.interest_rate_bps = interest_rate_bps;
+        msg!("Updated interest rate for {} to {} bps", mint, interest_rate_bps);
+    }
+    
+    // Update exchange-rate feed if provided (default pubkey = priced by the oracle alone)
+    if let Some(exchange_rate_oracle) = params.exchange_rate_oracle {
+        collateral.exchange_rate_oracle = exchange_rate_oracle;
+        msg!("Updated exchange-rate oracle for {} to {}", mint, exchange_rate_oracle);
+    }
+    
+    // Update collateral-specific liquidation bonus if provided (0 = global bonus)
+    if let Some(collateral_bonus_bps) = params.liquidation_bonus_bps {
+        require!(
+            collateral_bonus_bps <= MAX_LIQUIDATION_BONUS_BPS,
+            crate::errors::CreditError::InvalidPercentage
+        );
+        collateral.liquidation_bonus_bps = collateral_bonus_bps;
+        msg!("Updated liquidation bonus for {} to {} bps", mint, collateral_bonus_bps);
+    }
+    
+    // Update collateral-specific LTV if provided (0 = global LTV)
+    if let Some(ltv_bps) = params.ltv_bps {
+        require!(
+            ltv_bps < liquidation_threshold_bps,
+            crate::errors::CreditError::InvalidPercentage
+        );
+        collateral.ltv_bps = ltv_bps;
+        msg!("Updated LTV for {} to {} bps", mint, ltv_bps);
+    }
+    
+    // The protocol share must still leave liquidators enough of this collateral's bonus
+    require!(
+        Config::liquidator_bonus_sufficient(
+            collateral.effective_liquidation_bonus_bps(liquidation_bonus_bps),
+            collateral.protocol_liquidation_share_bps
+        ),
+        crate::errors::CreditError::InvalidParameter
+    );
+    
+    Ok(())
+}
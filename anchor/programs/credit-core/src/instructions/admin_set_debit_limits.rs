@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::state::{Config, DebitAccount, DebitTierLimits};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct DebitLimitsParams {
+    pub daily_limit: Option<u64>,
+    pub monthly_limit: Option<u64>,
+    pub per_tx_limit: Option<u64>,
+    /// Zero the daily and monthly spend so the new limits start with a fresh budget
+    pub reset_spent: bool,
+}
+
+#[derive(Accounts)]
+pub struct AdminSetDebitLimits<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = config.admin == admin.key() @ crate::errors::CreditError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        seeds = [DEBIT_ACCOUNT_SEED, debit_account.owner.as_ref()],
+        bump,
+        constraint = debit_account.is_initialized @ crate::errors::CreditError::PositionNotInitialized
+    )]
+    pub debit_account: Account<'info, DebitAccount>,
+    
+    pub admin: Signer<'info>,
+}
+
+/// Override a debit account's limits, optionally resetting its spend counters
+///
+/// Without `reset_spent` the daily and monthly spend carry over against the new limits.
+pub fn handler(ctx: Context<AdminSetDebitLimits>, params: DebitLimitsParams) -> Result<()> {
+    let debit_account = &mut ctx.accounts.debit_account;
+    
+    let limits = DebitTierLimits {
+        daily_limit: params.daily_limit.unwrap_or(debit_account.daily_limit),
+        monthly_limit: params.monthly_limit.unwrap_or(debit_account.monthly_limit),
+        per_tx_limit: params.per_tx_limit.unwrap_or(debit_account.per_tx_limit),
+    };
+    require!(limits.is_valid(), crate::errors::CreditError::InvalidParameter);
+    
+    debit_account.daily_limit = limits.daily_limit;
+    debit_account.monthly_limit = limits.monthly_limit;
+    debit_account.per_tx_limit = limits.per_tx_limit;
+    
+    if params.reset_spent {
+        let now = Clock::get()?.unix_timestamp;
+        debit_account.daily_spent = 0;
+        debit_account.monthly_spent = 0;
+        debit_account.last_daily_reset = now;
+        debit_account.last_monthly_reset = now;
+        msg!("Reset debit spend counters for {}", debit_account.owner);
+    }
+    
+    msg!(
+        "Updated debit limits for {} - Daily: {}, Monthly: {}, Per tx: {}",
+        debit_account.owner,
+        debit_account.daily_limit,
+        debit_account.monthly_limit,
+        debit_account.per_tx_limit
+    );
+    
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::state::{Config, DebitAccount};
+
+#[derive(Accounts)]
+pub struct AdminSetDebitTier<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = config.admin == admin.key() @ crate::errors::CreditError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        seeds = [DEBIT_ACCOUNT_SEED, debit_account.owner.as_ref()],
+        bump,
+        constraint = debit_account.is_initialized @ crate::errors::CreditError::PositionNotInitialized
+    )]
+    pub debit_account: Account<'info, DebitAccount>,
+    
+    pub admin: Signer<'info>,
+}
+
+/// Assign a debit tier, replacing the account's limits with the tier defaults
+pub fn handler(ctx: Context<AdminSetDebitTier>, tier: u8) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let debit_account = &mut ctx.accounts.debit_account;
+    
+    let limits = config
+        .tier_limits(tier)
+        .ok_or(crate::errors::CreditError::InvalidDebitTier)?;
+    debit_account.apply_tier(tier, limits);
+    
+    msg!(
+        "Assigned tier {} to debit account of {} - Daily: {}, Monthly: {}, Per tx: {}",
+        tier,
+        debit_account.owner,
+        debit_account.daily_limit,
+        debit_account.monthly_limit,
+        debit_account.per_tx_limit
+    );
+    
+    emit!(DebitTierAssigned {
+        owner: debit_account.owner,
+        tier,
+        daily_limit: debit_account.daily_limit,
+        monthly_limit: debit_account.monthly_limit,
+        per_tx_limit: debit_account.per_tx_limit,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct DebitTierAssigned {
+    pub owner: Pubkey,
+    pub tier: u8,
+    pub daily_limit: u64,
+    pub monthly_limit: u64,
+    pub per_tx_limit: u64,
+    pub timestamp: i64,
+}
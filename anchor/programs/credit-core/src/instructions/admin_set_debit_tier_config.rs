@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::state::{Config, DebitTierLimits};
+
+#[derive(Accounts)]
+pub struct AdminSetDebitTierConfig<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = config.admin == admin.key() @ crate::errors::CreditError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub admin: Signer<'info>,
+}
+
+/// Set a tier's default limits; accounts pick them up when the tier is next assigned
+pub fn handler(ctx: Context<AdminSetDebitTierConfig>, tier: u8, limits: DebitTierLimits) -> Result<()> {
+    require!((tier as usize) < MAX_DEBIT_TIERS, crate::errors::CreditError::InvalidDebitTier);
+    require!(limits.is_valid(), crate::errors::CreditError::InvalidParameter);
+    
+    ctx.accounts.config.tier_config[tier as usize] = limits;
+    
+    msg!(
+        "Updated debit tier {} - Daily: {}, Monthly: {}, Per tx: {}",
+        tier,
+        limits.daily_limit,
+        limits.monthly_limit,
+        limits.per_tx_limit
+    );
+    
+    Ok(())
+}
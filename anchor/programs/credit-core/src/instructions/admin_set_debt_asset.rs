@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::Config;
+
+#[derive(Accounts)]
+pub struct AdminSetDebtAsset<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = config.admin == admin.key() @ CreditError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// Mint positions may borrow instead of USDC
+    #[account(constraint = debt_mint.key() != config.usdc_mint @ CreditError::InvalidMint)]
+    pub debt_mint: Account<'info, Mint>,
+    
+    pub admin: Signer<'info>,
+}
+
+/// Register a debt asset if needed and set its price feed
+///
+/// `oracle` is the asset's USD feed; the default pubkey values it at $1.
+pub fn handler(ctx: Context<AdminSetDebtAsset>, oracle: Pubkey) -> Result<()> {
+    let decimals = ctx.accounts.debt_mint.decimals;
+    let mint = ctx.accounts.debt_mint.key();
+    
+    let asset = ctx.accounts.config.register_debt_asset(mint)?;
+    asset.oracle = oracle;
+    asset.decimals = decimals;
+    
+    if asset.is_stable() {
+        msg!("Debt asset {} valued at $1", mint);
+    } else {
+        msg!("Debt asset {} priced by {}", mint, oracle);
+    }
+    
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::Config;
+
+#[derive(Accounts)]
+pub struct AdminSetMaxInterestRate<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = config.admin == admin.key() @ CreditError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub admin: Signer<'info>,
+}
+
+/// Lowering the ceiling applies immediately. Raising it is queued on the first
+/// call and applied by calling again with the same value once the timelock ends.
+pub fn handler(ctx: Context<AdminSetMaxInterestRate>, max_interest_rate_bps: u16) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let now = Clock::get()?.unix_timestamp;
+    
+    require!(
+        max_interest_rate_bps > 0 && max_interest_rate_bps <= MAX_INTEREST_RATE_BPS,
+        CreditError::InvalidPercentage
+    );
+    // The current rates must stay within the new ceiling
+    require!(
+        max_interest_rate_bps >= config.interest_rate_bps
+            && config.collateral_types
+                .iter()
+                .all(|collateral| collateral.interest_rate_bps <= max_interest_rate_bps),
+        CreditError::InterestRateAboveCeiling
+    );
+    
+    if max_interest_rate_bps <= config.max_interest_rate_bps {
+        config.max_interest_rate_bps = max_interest_rate_bps;
+        config.pending_max_interest_rate_bps = 0;
+        config.max_interest_rate_unlock_timestamp = 0;
+        msg!("Lowered max interest rate to {} bps", max_interest_rate_bps);
+    } else if config.pending_max_interest_rate_bps == max_interest_rate_bps {
+        require!(
+            now >= config.max_interest_rate_unlock_timestamp,
+            CreditError::TimelockNotElapsed
+        );
+        config.max_interest_rate_bps = max_interest_rate_bps;
+        config.pending_max_interest_rate_bps = 0;
+        config.max_interest_rate_unlock_timestamp = 0;
+        msg!("Raised max interest rate to {} bps", max_interest_rate_bps);
+    } else {
+        let unlock_timestamp = now
+            .checked_add(MAX_INTEREST_RATE_TIMELOCK_SECS)
+            .ok_or(CreditError::MathOverflow)?;
+        config.pending_max_interest_rate_bps = max_interest_rate_bps;
+        config.max_interest_rate_unlock_timestamp = unlock_timestamp;
+        msg!(
+            "Queued max interest rate raise to {} bps, applicable after {}",
+            max_interest_rate_bps,
+            unlock_timestamp
+        );
+    }
+    
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::state::{Config, OracleDecimals};
+
+#[derive(Accounts)]
+pub struct AdminSetOracleDecimals<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = config.admin == admin.key() @ crate::errors::CreditError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub admin: Signer<'info>,
+}
+
+/// Pin the quote decimals a price feed must report in, or clear the override with `None`
+pub fn handler(
+    ctx: Context<AdminSetOracleDecimals>,
+    oracle: Pubkey,
+    expected_quote_decimals: Option<u8>,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    require!(oracle != Pubkey::default(), crate::errors::CreditError::InvalidOracle);
+    
+    let existing = config.oracle_decimals
+        .iter()
+        .position(|entry| entry.is_active() && entry.oracle == oracle);
+    
+    match expected_quote_decimals {
+        Some(decimals) => {
+            require!(decimals <= MAX_QUOTE_DECIMALS, crate::errors::CreditError::InvalidParameter);
+            
+            // Reuse the feed's slot, else take the first empty one
+            let index = match existing {
+                Some(index) => index,
+                None => config.oracle_decimals
+                    .iter()
+                    .position(|entry| !entry.is_active())
+                    .ok_or(crate::errors::CreditError::InvalidParameter)?,
+            };
+            config.oracle_decimals[index] = OracleDecimals {
+                oracle,
+                expected_quote_decimals: decimals,
+            };
+            msg!("Oracle {} must quote in {} decimals", oracle, decimals);
+        }
+        None => {
+            if let Some(index) = existing {
+                config.oracle_decimals[index] = OracleDecimals::default();
+            }
+            msg!("Cleared quote decimals override for oracle {}", oracle);
+        }
+    }
+    
+    Ok(())
+}
@@ -1,8 +1,8 @@
 use anchor_lang::prelude::*;
 use crate::constants::*;
-use crate::state::Config;
+use crate::state::{Config, PendingParams};
 
-#[derive(AnchorSerialize, AnchorDeserialize)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
 pub struct UpdateParams {
     pub ltv_max_bps: Option<u16>,
     pub liquidation_threshold_bps: Option<u16>,
@@ -11,6 +11,92 @@ pub struct UpdateParams {
     pub sol_usd_oracle: Option<Pubkey>,
     pub jito_sol_usd_oracle: Option<Pubkey>,
     pub new_admin: Option<Pubkey>,
+    pub jito_sol_mint: Option<Pubkey>,
+    pub borrow_max_staleness_slots: Option<u64>,
+    pub liquidation_max_staleness_slots: Option<u64>,
+    pub max_confidence_bps: Option<u16>,
+    pub min_residual_collateral: Option<u64>,
+    pub min_accrual_interval_secs: Option<i64>,
+    pub liquidation_health_epsilon_bps: Option<u16>,
+    pub min_position_age_secs: Option<i64>,
+    pub min_reserve_ratio_bps: Option<u16>,
+    pub max_total_collateral_value_usdc: Option<u64>,
+    pub min_borrow_health_factor_bps: Option<u16>,
+    pub warning_threshold_bps: Option<u16>,
+    pub protocol_liquidator: Option<Pubkey>,
+    pub prepayment_fee_bps: Option<u16>,
+    pub min_loan_term_secs: Option<i64>,
+    pub max_liquidation_collateral_fraction_bps: Option<u16>,
+    pub pause_interest_accrual: Option<bool>,
+    pub credit_limit_buffer_bps: Option<u16>,
+    pub min_debt_after_liquidation: Option<u64>,
+    pub min_client_version: Option<u8>,
+    pub oracle_health_window_slots: Option<u64>,
+    pub protocol_owners: Option<[Pubkey; MAX_PROTOCOL_OWNERS]>,
+    pub ema_price_flags: Option<u8>,
+    pub debit_dust_threshold: Option<u64>,
+    pub max_active_reservations: Option<u16>,
+    pub pause_blocks_repays: Option<bool>,
+    pub borrow_valuation_mode: Option<u8>,
+    pub liquidation_valuation_mode: Option<u8>,
+    pub max_borrow_index_age_secs: Option<i64>,
+    pub max_borrow_liquidity_share_bps: Option<u16>,
+    pub credit_limit_refresh_interval_secs: Option<i64>,
+    pub credit_limit_refresh_price_bps: Option<u16>,
+    pub max_single_position_debt_usdc: Option<u64>,
+    pub full_liquidation_rebate_bps: Option<u16>,
+    pub params_timelock_slots: Option<u64>,
+    pub max_index_growth_wad: Option<u128>,
+    pub enforce_configured_oracles: Option<bool>,
+}
+
+impl UpdateParams {
+    /// Serialized size with every field set
+    pub const MAX_LEN: usize =
+        (1 + 2) + // ltv_max_bps
+        (1 + 2) + // liquidation_threshold_bps
+        (1 + 2) + // liquidation_bonus_bps
+        (1 + 2) + // interest_rate_bps
+        (1 + 32) + // sol_usd_oracle
+        (1 + 32) + // jito_sol_usd_oracle
+        (1 + 32) + // new_admin
+        (1 + 32) + // jito_sol_mint
+        (1 + 8) + // borrow_max_staleness_slots
+        (1 + 8) + // liquidation_max_staleness_slots
+        (1 + 2) + // max_confidence_bps
+        (1 + 8) + // min_residual_collateral
+        (1 + 8) + // min_accrual_interval_secs
+        (1 + 2) + // liquidation_health_epsilon_bps
+        (1 + 8) + // min_position_age_secs
+        (1 + 2) + // min_reserve_ratio_bps
+        (1 + 8) + // max_total_collateral_value_usdc
+        (1 + 2) + // min_borrow_health_factor_bps
+        (1 + 2) + // warning_threshold_bps
+        (1 + 32) + // protocol_liquidator
+        (1 + 2) + // prepayment_fee_bps
+        (1 + 8) + // min_loan_term_secs
+        (1 + 2) + // max_liquidation_collateral_fraction_bps
+        (1 + 1) + // pause_interest_accrual
+        (1 + 2) + // credit_limit_buffer_bps
+        (1 + 8) + // min_debt_after_liquidation
+        (1 + 1) + // min_client_version
+        (1 + 8) + // oracle_health_window_slots
+        (1 + (32 * MAX_PROTOCOL_OWNERS)) + // protocol_owners
+        (1 + 1) + // ema_price_flags
+        (1 + 8) + // debit_dust_threshold
+        (1 + 2) + // max_active_reservations
+        (1 + 1) + // pause_blocks_repays
+        (1 + 1) + // borrow_valuation_mode
+        (1 + 1) + // liquidation_valuation_mode
+        (1 + 8) + // max_borrow_index_age_secs
+        (1 + 2) + // max_borrow_liquidity_share_bps
+        (1 + 8) + // credit_limit_refresh_interval_secs
+        (1 + 2) + // credit_limit_refresh_price_bps
+        (1 + 8) + // max_single_position_debt_usdc
+        (1 + 2) + // full_liquidation_rebate_bps
+        (1 + 8) + // params_timelock_slots
+        (1 + 16) + // max_index_growth_wad
+        (1 + 1); // enforce_configured_oracles
 }
 
 #[derive(Accounts)]
@@ -23,12 +109,52 @@ pub struct AdminSetParams<'info> {
     )]
     pub config: Account<'info, Config>,
     
+    /// Changes queued while a params timelock is set
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = PendingParams::LEN,
+        seeds = [PENDING_PARAMS_SEED],
+        bump
+    )]
+    pub pending_params: Account<'info, PendingParams>,
+    
+    #[account(mut)]
     pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
 }
 
+/// Apply `params` now, or queue them (replacing anything already queued) when
+/// `params_timelock_slots` is set; `apply_pending_params` commits them later
 pub fn handler(ctx: Context<AdminSetParams>, params: UpdateParams) -> Result<()> {
     let config = &mut ctx.accounts.config;
+    if config.params_timelock_slots == 0 {
+        return apply_params(config, params);
+    }
+    
+    let clock = Clock::get()?;
+    let effective_slot = clock.slot
+        .checked_add(config.params_timelock_slots)
+        .ok_or(crate::errors::CreditError::MathOverflow)?;
+    let pending_params = &mut ctx.accounts.pending_params;
+    pending_params.params = params;
+    pending_params.queued_slot = clock.slot;
+    pending_params.effective_slot = effective_slot;
+    
+    msg!("Queued parameter changes, applicable from slot {}", effective_slot);
+    
+    emit!(ParamsQueued {
+        admin: ctx.accounts.admin.key(),
+        queued_slot: clock.slot,
+        effective_slot,
+    });
     
+    Ok(())
+}
+
+/// Validate and write every provided parameter into the config
+pub(crate) fn apply_params(config: &mut Config, params: UpdateParams) -> Result<()> {
     // Update LTV max if provided
     if let Some(ltv_max_bps) = params.ltv_max_bps {
         require!(ltv_max_bps <= 10000, crate::errors::CreditError::InvalidPercentage);
@@ -50,6 +176,11 @@ pub fn handler(ctx: Context<AdminSetParams>, params: UpdateParams) -> Result<()>
             liquidation_threshold_bps > config.ltv_max_bps, 
             crate::errors::CreditError::InvalidPercentage
         );
+        // Collateral-specific LTVs must stay below the threshold too
+        require!(
+            config.collateral_types.iter().all(|collateral| collateral.ltv_bps < liquidation_threshold_bps),
+            crate::errors::CreditError::InvalidPercentage
+        );
         config.liquidation_threshold_bps = liquidation_threshold_bps;
         msg!("Updated liquidation threshold to {}%", liquidation_threshold_bps / 100);
     }
@@ -57,9 +188,19 @@ pub fn handler(ctx: Context<AdminSetParams>, params: UpdateParams) -> Result<()>
     // Update liquidation bonus if provided
     if let Some(liquidation_bonus_bps) = params.liquidation_bonus_bps {
         require!(
-            liquidation_bonus_bps <= 2000, // Max 20% bonus
+            liquidation_bonus_bps <= MAX_LIQUIDATION_BONUS_BPS,
             crate::errors::CreditError::InvalidPercentage
         );
+        // Every collateral's protocol share must still leave liquidators enough incentive
+        require!(
+            config.collateral_types.iter().all(|collateral| {
+                Config::liquidator_bonus_sufficient(
+                    collateral.effective_liquidation_bonus_bps(liquidation_bonus_bps),
+                    collateral.protocol_liquidation_share_bps
+                )
+            }),
+            crate::errors::CreditError::InvalidParameter
+        );
         config.liquidation_bonus_bps = liquidation_bonus_bps;
         msg!("Updated liquidation bonus to {}%", liquidation_bonus_bps / 100);
     }
@@ -67,9 +208,13 @@ pub fn handler(ctx: Context<AdminSetParams>, params: UpdateParams) -> Result<()>
     // Update interest rate if provided
     if let Some(interest_rate_bps) = params.interest_rate_bps {
         require!(
-            interest_rate_bps <= 10000, // Max 100% APR
+            interest_rate_bps <= MAX_INTEREST_RATE_BPS,
             crate::errors::CreditError::InvalidPercentage
         );
+        require!(
+            interest_rate_bps <= config.max_interest_rate_bps,
+            crate::errors::CreditError::InterestRateAboveCeiling
+        );
         config.interest_rate_bps = interest_rate_bps;
         msg!("Updated interest rate to {}%", interest_rate_bps / 100);
     }
@@ -86,6 +231,348 @@ pub fn handler(ctx: Context<AdminSetParams>, params: UpdateParams) -> Result<()>
         msg!("Updated jitoSOL/USD oracle to {}", jito_sol_usd_oracle);
     }
     
+    // Migrate the canonical jitoSOL mint if provided (new mint must be equivalent)
+    if let Some(jito_sol_mint) = params.jito_sol_mint {
+        require!(
+            jito_sol_mint != Pubkey::default() && jito_sol_mint != config.jito_sol_mint,
+            crate::errors::CreditError::InvalidMint
+        );
+        require!(
+            jito_sol_mint != config.usdc_mint && jito_sol_mint != config.wsol_mint,
+            crate::errors::CreditError::DuplicateMint
+        );
+        let previous_mint = config.jito_sol_mint;
+        let previous_params = config.collateral_type(&previous_mint).copied();
+        
+        // Carry the old mint's collateral parameters over to its replacement
+        let collateral = config.register_collateral_type(jito_sol_mint)?;
+        if let Some(previous_params) = previous_params {
+            collateral.protocol_liquidation_share_bps = previous_params.protocol_liquidation_share_bps;
+            collateral.oracle = previous_params.oracle;
+            collateral.liquidation_priority = previous_params.liquidation_priority;
+            collateral.exchange_rate_oracle = previous_params.exchange_rate_oracle;
+        }
+        
+        config.previous_jito_sol_mint = previous_mint;
+        config.jito_sol_mint = jito_sol_mint;
+        msg!("Migrated jitoSOL mint from {} to {}", previous_mint, jito_sol_mint);
+    }
+    
+    // Update oracle staleness bounds if provided
+    // Liquidations must use a bound at least as tight as borrows
+    if params.borrow_max_staleness_slots.is_some() || params.liquidation_max_staleness_slots.is_some() {
+        let borrow_max_staleness_slots = params.borrow_max_staleness_slots
+            .unwrap_or(config.borrow_max_staleness_slots);
+        let liquidation_max_staleness_slots = params.liquidation_max_staleness_slots
+            .unwrap_or(config.liquidation_max_staleness_slots);
+        
+        require!(
+            borrow_max_staleness_slots > 0 && borrow_max_staleness_slots <= MAX_ORACLE_STALENESS_SLOTS_LIMIT,
+            crate::errors::CreditError::InvalidParameter
+        );
+        require!(
+            liquidation_max_staleness_slots > 0 && liquidation_max_staleness_slots <= borrow_max_staleness_slots,
+            crate::errors::CreditError::InvalidParameter
+        );
+        
+        config.borrow_max_staleness_slots = borrow_max_staleness_slots;
+        config.liquidation_max_staleness_slots = liquidation_max_staleness_slots;
+        msg!(
+            "Updated oracle staleness bounds - Borrow: {} slots, Liquidation: {} slots",
+            borrow_max_staleness_slots,
+            liquidation_max_staleness_slots
+        );
+    }
+    
+    // Update oracle confidence bound if provided
+    if let Some(max_confidence_bps) = params.max_confidence_bps {
+        require!(
+            max_confidence_bps > 0 && max_confidence_bps <= 10000,
+            crate::errors::CreditError::InvalidPercentage
+        );
+        config.max_confidence_bps = max_confidence_bps;
+        msg!("Updated max oracle confidence to {} bps", max_confidence_bps);
+    }
+    
+    // Update minimum residual collateral for partial liquidations if provided
+    if let Some(min_residual_collateral) = params.min_residual_collateral {
+        config.min_residual_collateral = min_residual_collateral;
+        msg!("Updated min residual collateral to {}", min_residual_collateral);
+    }
+    
+    // Update minimum debt a partial liquidation may leave if provided
+    if let Some(min_debt_after_liquidation) = params.min_debt_after_liquidation {
+        config.min_debt_after_liquidation = min_debt_after_liquidation;
+        msg!("Updated min debt after liquidation to {}", min_debt_after_liquidation);
+    }
+    
+    // Update interest accrual interval if provided
+    if let Some(min_accrual_interval_secs) = params.min_accrual_interval_secs {
+        require!(
+            (0..=MAX_ACCRUAL_INTERVAL_SECS).contains(&min_accrual_interval_secs),
+            crate::errors::CreditError::InvalidParameter
+        );
+        // Settle interest under the old interval before switching
+        config.accrue_interest(Clock::get()?.unix_timestamp)?;
+        config.min_accrual_interval_secs = min_accrual_interval_secs;
+        msg!("Updated min accrual interval to {} seconds", min_accrual_interval_secs);
+    }
+    
+    // Update liquidation health factor rounding allowance if provided
+    if let Some(liquidation_health_epsilon_bps) = params.liquidation_health_epsilon_bps {
+        require!(
+            liquidation_health_epsilon_bps <= MAX_LIQUIDATION_HEALTH_EPSILON_BPS,
+            crate::errors::CreditError::InvalidParameter
+        );
+        config.liquidation_health_epsilon_bps = liquidation_health_epsilon_bps;
+        msg!("Updated liquidation health epsilon to {} bps", liquidation_health_epsilon_bps);
+    }
+    
+    // Update minimum position age before the first borrow if provided
+    if let Some(min_position_age_secs) = params.min_position_age_secs {
+        require!(
+            (0..=MAX_MIN_POSITION_AGE_SECS).contains(&min_position_age_secs),
+            crate::errors::CreditError::InvalidParameter
+        );
+        config.min_position_age_secs = min_position_age_secs;
+        msg!("Updated min position age to {} seconds", min_position_age_secs);
+    }
+    
+    // Update treasury reserve ratio for supply withdrawals if provided
+    if let Some(min_reserve_ratio_bps) = params.min_reserve_ratio_bps {
+        require!(min_reserve_ratio_bps <= 10000, crate::errors::CreditError::InvalidPercentage);
+        config.min_reserve_ratio_bps = min_reserve_ratio_bps;
+        msg!("Updated min reserve ratio to {} bps", min_reserve_ratio_bps);
+    }
+    
+    // Update protocol-wide collateral value ceiling if provided (0 removes it)
+    if let Some(max_total_collateral_value_usdc) = params.max_total_collateral_value_usdc {
+        config.max_total_collateral_value_usdc = max_total_collateral_value_usdc;
+        msg!("Updated max total collateral value to {} USDC", max_total_collateral_value_usdc);
+    }
+    
+    // Update health factor required after borrows and withdrawals if provided
+    if let Some(min_borrow_health_factor_bps) = params.min_borrow_health_factor_bps {
+        require!(
+            min_borrow_health_factor_bps as u64 >= BPS_PRECISION,
+            crate::errors::CreditError::InvalidParameter
+        );
+        config.min_borrow_health_factor_bps = min_borrow_health_factor_bps;
+        msg!("Updated min borrow health factor to {} bps", min_borrow_health_factor_bps);
+    }
+    
+    // Update liquidation warning band if provided (must sit above the liquidation line)
+    if let Some(warning_threshold_bps) = params.warning_threshold_bps {
+        require!(
+            warning_threshold_bps as u64 > BPS_PRECISION,
+            crate::errors::CreditError::InvalidParameter
+        );
+        config.warning_threshold_bps = warning_threshold_bps;
+        msg!("Updated liquidation warning threshold to {} bps", warning_threshold_bps);
+    }
+    
+    // Update reserve-funded liquidation backstop if provided (default pubkey disables it)
+    if let Some(protocol_liquidator) = params.protocol_liquidator {
+        config.protocol_liquidator = protocol_liquidator;
+        msg!("Updated protocol liquidator to {}", protocol_liquidator);
+    }
+    
+    // Update early repayment fee if provided
+    if let Some(prepayment_fee_bps) = params.prepayment_fee_bps {
+        require!(
+            prepayment_fee_bps <= MAX_PREPAYMENT_FEE_BPS,
+            crate::errors::CreditError::InvalidPercentage
+        );
+        config.prepayment_fee_bps = prepayment_fee_bps;
+        msg!("Updated prepayment fee to {} bps", prepayment_fee_bps);
+    }
+    
+    // Update minimum loan term for the prepayment fee if provided
+    if let Some(min_loan_term_secs) = params.min_loan_term_secs {
+        require!(
+            (0..=MAX_MIN_LOAN_TERM_SECS).contains(&min_loan_term_secs),
+            crate::errors::CreditError::InvalidParameter
+        );
+        config.min_loan_term_secs = min_loan_term_secs;
+        msg!("Updated min loan term to {} seconds", min_loan_term_secs);
+    }
+    
+    // Update collateral-based cap on a single liquidation if provided
+    if let Some(fraction_bps) = params.max_liquidation_collateral_fraction_bps {
+        require!(
+            fraction_bps > 0 && fraction_bps as u64 <= BPS_PRECISION,
+            crate::errors::CreditError::InvalidPercentage
+        );
+        config.max_liquidation_collateral_fraction_bps = fraction_bps;
+        msg!("Updated max liquidation collateral fraction to {} bps", fraction_bps);
+    }
+    
+    // Update whether global pauses stop interest accrual if provided (applies from the next pause)
+    if let Some(pause_interest_accrual) = params.pause_interest_accrual {
+        config.pause_interest_accrual = pause_interest_accrual;
+        msg!("Updated pause interest accrual to {}", pause_interest_accrual);
+    }
+    
+    // Update credit limit headroom below the max LTV if provided
+    if let Some(credit_limit_buffer_bps) = params.credit_limit_buffer_bps {
+        require!(
+            credit_limit_buffer_bps < config.ltv_max_bps,
+            crate::errors::CreditError::InvalidPercentage
+        );
+        config.credit_limit_buffer_bps = credit_limit_buffer_bps;
+        msg!("Updated credit limit buffer to {} bps", credit_limit_buffer_bps);
+    }
+    
+    // Update minimum accepted client version if provided
+    if let Some(min_client_version) = params.min_client_version {
+        require!(
+            min_client_version <= PROTOCOL_VERSION,
+            crate::errors::CreditError::InvalidParameter
+        );
+        config.min_client_version = min_client_version;
+        msg!("Updated min client version to {}", min_client_version);
+    }
+    
+    // Update how recently a collateral oracle must have been healthy for deposits if provided
+    if let Some(oracle_health_window_slots) = params.oracle_health_window_slots {
+        config.oracle_health_window_slots = oracle_health_window_slots;
+        msg!("Updated oracle health window to {} slots", oracle_health_window_slots);
+    }
+    
+    // Update owners whose positions only the protocol liquidator may liquidate if provided
+    if let Some(protocol_owners) = params.protocol_owners {
+        config.protocol_owners = protocol_owners;
+        msg!("Updated protocol-owned position owners");
+    }
+    
+    // Update which operations are priced at the oracle EMA if provided
+    if let Some(ema_price_flags) = params.ema_price_flags {
+        require!(
+            ema_price_flags & !EMA_FOR_ALL_FLAGS == 0,
+            crate::errors::CreditError::InvalidParameter
+        );
+        config.ema_price_flags = ema_price_flags;
+        msg!("Updated EMA price flags to {:#06b}", ema_price_flags);
+    }
+    
+    // Update the reserved debit dust released on settle if provided
+    if let Some(debit_dust_threshold) = params.debit_dust_threshold {
+        config.debit_dust_threshold = debit_dust_threshold;
+        msg!("Updated debit dust threshold to {}", debit_dust_threshold);
+    }
+    
+    // Update the cap on pending debit reservations per account if provided
+    if let Some(max_active_reservations) = params.max_active_reservations {
+        require!(max_active_reservations > 0, crate::errors::CreditError::InvalidParameter);
+        config.max_active_reservations = max_active_reservations;
+        msg!("Updated max active debit reservations to {}", max_active_reservations);
+    }
+    
+    // Update whether the global pause blocks repayments if provided
+    if let Some(pause_blocks_repays) = params.pause_blocks_repays {
+        config.pause_blocks_repays = pause_blocks_repays;
+        msg!("Updated pause blocks repayments to {}", pause_blocks_repays);
+    }
+    
+    // Update the band point used for borrow power if provided
+    if let Some(borrow_valuation_mode) = params.borrow_valuation_mode {
+        require!(borrow_valuation_mode <= VALUATION_ASK, crate::errors::CreditError::InvalidParameter);
+        config.borrow_valuation_mode = borrow_valuation_mode;
+        msg!("Updated borrow valuation mode to {}", borrow_valuation_mode);
+    }
+    
+    // Update the band point used for liquidation eligibility if provided
+    if let Some(liquidation_valuation_mode) = params.liquidation_valuation_mode {
+        require!(liquidation_valuation_mode <= VALUATION_ASK, crate::errors::CreditError::InvalidParameter);
+        config.liquidation_valuation_mode = liquidation_valuation_mode;
+        msg!("Updated liquidation valuation mode to {}", liquidation_valuation_mode);
+    }
+    
+    // Update the borrow index age limit for borrows if provided
+    if let Some(max_borrow_index_age_secs) = params.max_borrow_index_age_secs {
+        // An accrual can leave up to one interval unaccrued, so the limit must allow for it
+        require!(
+            max_borrow_index_age_secs == 0
+                || max_borrow_index_age_secs >= config.min_accrual_interval_secs.max(1),
+            crate::errors::CreditError::InvalidParameter
+        );
+        config.max_borrow_index_age_secs = max_borrow_index_age_secs;
+        msg!("Updated max borrow index age to {} seconds", max_borrow_index_age_secs);
+    }
+    
+    // Update the share of idle liquidity one position may borrow if provided
+    if let Some(max_borrow_liquidity_share_bps) = params.max_borrow_liquidity_share_bps {
+        require!(
+            max_borrow_liquidity_share_bps <= 10000,
+            crate::errors::CreditError::InvalidPercentage
+        );
+        config.max_borrow_liquidity_share_bps = max_borrow_liquidity_share_bps;
+        msg!("Updated max borrow liquidity share to {} bps", max_borrow_liquidity_share_bps);
+    }
+    
+    // Update how long deposits may keep a cached credit limit if provided
+    if let Some(credit_limit_refresh_interval_secs) = params.credit_limit_refresh_interval_secs {
+        require!(credit_limit_refresh_interval_secs >= 0, crate::errors::CreditError::InvalidParameter);
+        config.credit_limit_refresh_interval_secs = credit_limit_refresh_interval_secs;
+        msg!("Updated credit limit refresh interval to {} seconds", credit_limit_refresh_interval_secs);
+    }
+    
+    // Update the price move that forces a credit limit recompute if provided
+    if let Some(credit_limit_refresh_price_bps) = params.credit_limit_refresh_price_bps {
+        require!(
+            credit_limit_refresh_price_bps <= 10000,
+            crate::errors::CreditError::InvalidPercentage
+        );
+        config.credit_limit_refresh_price_bps = credit_limit_refresh_price_bps;
+        msg!("Updated credit limit refresh price move to {} bps", credit_limit_refresh_price_bps);
+    }
+    
+    // Update the per-position debt cap if provided (0 = uncapped)
+    if let Some(max_single_position_debt_usdc) = params.max_single_position_debt_usdc {
+        require!(
+            max_single_position_debt_usdc == 0 || max_single_position_debt_usdc >= MIN_REPAY_AMOUNT,
+            crate::errors::CreditError::InvalidParameter
+        );
+        config.max_single_position_debt_usdc = max_single_position_debt_usdc;
+        msg!("Updated max single position debt to {} USDC", max_single_position_debt_usdc);
+    }
+    
+    // Update the full-liquidation rebate if provided
+    if let Some(full_liquidation_rebate_bps) = params.full_liquidation_rebate_bps {
+        require!(
+            full_liquidation_rebate_bps <= MAX_FULL_LIQUIDATION_REBATE_BPS,
+            crate::errors::CreditError::InvalidPercentage
+        );
+        config.full_liquidation_rebate_bps = full_liquidation_rebate_bps;
+        msg!("Updated full liquidation rebate to {} bps", full_liquidation_rebate_bps);
+    }
+    
+    // Update the params timelock if provided (0 = changes apply immediately)
+    if let Some(params_timelock_slots) = params.params_timelock_slots {
+        require!(
+            params_timelock_slots <= MAX_PARAMS_TIMELOCK_SLOTS,
+            crate::errors::CreditError::InvalidParameter
+        );
+        config.params_timelock_slots = params_timelock_slots;
+        msg!("Updated params timelock to {} slots", params_timelock_slots);
+    }
+    
+    // Update the per-accrual index jump ceiling if provided (0 = unbounded)
+    if let Some(max_index_growth_wad) = params.max_index_growth_wad {
+        require!(
+            max_index_growth_wad == 0 || max_index_growth_wad > WAD_PRECISION,
+            crate::errors::CreditError::InvalidParameter
+        );
+        config.max_index_growth_wad = max_index_growth_wad;
+        msg!("Updated max index growth per accrual to {} (WAD)", max_index_growth_wad);
+    }
+    
+    // Update whether oracle accounts must be the configured feeds if provided
+    if let Some(enforce_configured_oracles) = params.enforce_configured_oracles {
+        config.enforce_configured_oracles = enforce_configured_oracles;
+        msg!("Updated configured oracle enforcement to {}", enforce_configured_oracles);
+    }
+    
     // Update admin if provided (transfer ownership)
     if let Some(new_admin) = params.new_admin {
         config.admin = new_admin;
@@ -93,4 +580,12 @@ pub fn handler(ctx: Context<AdminSetParams>, params: UpdateParams) -> Result<()>
     }
     
     Ok(())
-} 
\ No newline at end of file
+}
+
+#[event]
+pub struct ParamsQueued {
+    pub admin: Pubkey,
+    pub queued_slot: u64,
+    /// First slot `apply_pending_params` accepts the changes in
+    pub effective_slot: u64,
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::state::Config;
+use super::admin_set_paused::ProtocolPausedStateChanged;
+
+#[derive(Accounts)]
+pub struct AdminSetPauseFlags<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = config.admin == admin.key() @ crate::errors::CreditError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<AdminSetPauseFlags>, pause_flags: u8) -> Result<u8> {
+    let config = &mut ctx.accounts.config;
+    
+    require!(
+        pause_flags & !PAUSE_ALL_FLAGS == 0,
+        crate::errors::CreditError::InvalidParameter
+    );
+    
+    // Replace the whole bitmask so the event always reflects the full state
+    config.pause_flags = pause_flags;
+    
+    msg!("Protocol pause flags set to: {:#07b}", pause_flags);
+    
+    // Emit event for monitoring
+    emit!(ProtocolPausedStateChanged {
+        admin: ctx.accounts.admin.key(),
+        paused: config.paused,
+        pause_flags,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(pause_flags)
+}
@@ -17,6 +17,14 @@ pub struct AdminSetPaused<'info> {
 
 pub fn handler(ctx: Context<AdminSetPaused>, paused: bool) -> Result<()> {
     let config = &mut ctx.accounts.config;
+    let now = Clock::get()?.unix_timestamp;
+    
+    // Exclude the paused window from interest accrual
+    if paused {
+        config.begin_accrual_pause(now)?;
+    } else {
+        config.end_accrual_pause(now)?;
+    }
     
     // Update paused state
     config.paused = paused;
@@ -27,7 +35,8 @@ pub fn handler(ctx: Context<AdminSetPaused>, paused: bool) -> Result<()> {
     emit!(ProtocolPausedStateChanged {
         admin: ctx.accounts.admin.key(),
         paused,
-        timestamp: Clock::get()?.unix_timestamp,
+        pause_flags: config.pause_flags,
+        timestamp: now,
     });
     
     Ok(())
@@ -37,5 +46,7 @@ pub fn handler(ctx: Context<AdminSetPaused>, paused: bool) -> Result<()> {
 pub struct ProtocolPausedStateChanged {
     pub admin: Pubkey,
     pub paused: bool,
+    /// Per-operation pause bitmask in effect after the change
+    pub pause_flags: u8,
     pub timestamp: i64,
 } 
\ No newline at end of file
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::{Config, UserPosition};
+
+#[derive(Accounts)]
+pub struct AdminSetSubsidy<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, user_position.owner.as_ref()],
+        bump,
+        constraint = user_position.is_initialized @ CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+    
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = config.admin == admin.key() @ CreditError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(
+    ctx: Context<AdminSetSubsidy>,
+    subsidy_bps: u16,
+    expiry_timestamp: i64,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let user_position = &mut ctx.accounts.user_position;
+    let clock = Clock::get()?;
+    
+    require!(subsidy_bps <= 10000, CreditError::InvalidPercentage);
+    require!(
+        subsidy_bps == 0 || expiry_timestamp > clock.unix_timestamp,
+        CreditError::InvalidParameter
+    );
+    
+    // Settle interest under the previous subsidy before switching
+    config.accrue_interest(clock.unix_timestamp)?;
+    user_position.settle_interest(config, clock.unix_timestamp)?;
+    
+    user_position.subsidy_bps = subsidy_bps;
+    user_position.subsidy_expiry_timestamp = expiry_timestamp;
+    
+    msg!(
+        "Set interest subsidy for {} to {} bps until {}",
+        user_position.owner,
+        subsidy_bps,
+        expiry_timestamp
+    );
+    
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::Config;
+
+#[derive(Accounts)]
+pub struct AdminShutdown<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = config.admin == admin.key() @ CreditError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub admin: Signer<'info>,
+}
+
+/// Emergency: permanently shut the protocol down after a catastrophic bug
+///
+/// Unlike the pause this cannot be undone. Deposits, borrows and liquidations
+/// stay blocked for good while repayments and withdrawals remain open, so users
+/// can exit. `confirmation` must be `SHUTDOWN_CONFIRMATION` verbatim.
+pub fn handler(ctx: Context<AdminShutdown>, confirmation: [u8; 8]) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    
+    require!(confirmation == SHUTDOWN_CONFIRMATION, CreditError::ConfirmationRequired);
+    require!(!config.shutdown, CreditError::InvalidParameter);
+    
+    config.shutdown = true;
+    
+    msg!("Protocol shut down by {}", ctx.accounts.admin.key());
+    
+    emit!(ProtocolShutdown {
+        admin: ctx.accounts.admin.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct ProtocolShutdown {
+    pub admin: Pubkey,
+    pub timestamp: i64,
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::{Config, UserPosition};
+
+#[derive(Accounts)]
+pub struct AdminSocializeBadDebt<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, user_position.owner.as_ref()],
+        bump,
+        constraint = user_position.is_initialized @ CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+    
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = config.admin == admin.key() @ CreditError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<AdminSocializeBadDebt>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let user_position = &mut ctx.accounts.user_position;
+    let clock = Clock::get()?;
+    
+    // Only positions with nothing left to seize can be written off
+    require!(
+        user_position.collateral_amount == 0,
+        CreditError::PositionNotBadDebt
+    );
+    
+    // Update global interest index
+    config.accrue_interest(clock.unix_timestamp)?;
+    
+    let current_debt = user_position.settle_interest(config, clock.unix_timestamp)?;
+    require!(current_debt > 0, CreditError::PositionNotBadDebt);
+    require!(user_position.owes_usdc(), CreditError::DebtMintMismatch);
+    
+    // Move the debt out of the active book into bad debt
+    user_position.bad_debt_usdc = current_debt;
+    user_position.last_update_slot = clock.slot;
+    user_position.last_update_timestamp = clock.unix_timestamp;
+    
+    config.total_debt_usdc = config.total_debt_usdc.saturating_sub(current_debt);
+    config.total_bad_debt_usdc = config.total_bad_debt_usdc
+        .checked_add(current_debt)
+        .ok_or(CreditError::MathOverflow)?;
+    
+    msg!("Socialized {} USDC bad debt for user: {}", current_debt, user_position.owner);
+    
+    emit!(BadDebtSocialized {
+        user: user_position.owner,
+        event_seq: user_position.next_event_seq(),
+        webhook_id: user_position.webhook_id,
+        amount: current_debt,
+        total_bad_debt: config.total_bad_debt_usdc,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct BadDebtSocialized {
+    pub user: Pubkey,
+    pub event_seq: u64,
+    pub webhook_id: [u8; 32],
+    pub amount: u64,
+    pub total_bad_debt: u64,
+    pub timestamp: i64,
+}
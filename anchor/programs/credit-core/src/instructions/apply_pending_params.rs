@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::{Config, PendingParams};
+use super::admin_set_params::apply_params;
+
+#[derive(Accounts)]
+pub struct ApplyPendingParams<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [PENDING_PARAMS_SEED],
+        bump
+    )]
+    pub pending_params: Account<'info, PendingParams>,
+}
+
+/// Commit the queued parameter changes once their timelock has elapsed
+///
+/// Permissionless: the admin chose the changes when queueing them, so anyone
+/// may apply them after the delay.
+pub fn handler(ctx: Context<ApplyPendingParams>) -> Result<()> {
+    let pending_params = &mut ctx.accounts.pending_params;
+    let clock = Clock::get()?;
+
+    require!(pending_params.is_queued(), CreditError::NoPendingParams);
+    require!(clock.slot >= pending_params.effective_slot, CreditError::TimelockNotElapsed);
+
+    let queued_slot = pending_params.queued_slot;
+    let params = std::mem::take(&mut pending_params.params);
+    pending_params.queued_slot = 0;
+    pending_params.effective_slot = 0;
+
+    apply_params(&mut ctx.accounts.config, params)?;
+
+    msg!("Applied parameter changes queued in slot {}", queued_slot);
+
+    emit!(ParamsApplied {
+        queued_slot,
+        applied_slot: clock.slot,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct ParamsApplied {
+    pub queued_slot: u64,
+    pub applied_slot: u64,
+}
@@ -0,0 +1,231 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::state::{Config, UserPosition};
+use crate::utils::{get_price_with_fallback, calculate_health_factor, sum_collateral_values};
+use crate::constants::*;
+use crate::errors::CreditError;
+
+#[derive(Accounts)]
+pub struct BorrowAsset<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, borrower.key().as_ref()],
+        bump,
+        constraint = user_position.owner == borrower.key() @ CreditError::Unauthorized,
+        constraint = user_position.is_initialized @ CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = !config.is_paused_for(PAUSE_BORROWS) @ CreditError::ProtocolPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Registered debt asset to borrow
+    #[account(constraint = config.debt_asset(&debt_mint.key()).is_some() @ CreditError::InvalidMint)]
+    pub debt_mint: Account<'info, Mint>,
+
+    /// Program vault for the debt asset
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, debt_mint.key().as_ref()],
+        bump,
+        token::mint = debt_mint,
+        token::authority = vault_authority
+    )]
+    pub asset_vault: Account<'info, TokenAccount>,
+
+    /// PDA authority for the vaults
+    /// CHECK: This is the PDA that has authority over the vaults
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Borrower's token account for the debt asset
+    #[account(
+        mut,
+        constraint = user_asset_account.owner == borrower.key() @ CreditError::Unauthorized,
+        constraint = user_asset_account.mint == debt_mint.key() @ CreditError::InvalidMint
+    )]
+    pub user_asset_account: Account<'info, TokenAccount>,
+
+    /// Pyth oracle for SOL/USD price
+    /// CHECK: Validated in handler
+    pub sol_usd_oracle: UncheckedAccount<'info>,
+
+    pub borrower: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Borrow a registered debt asset instead of USDC
+///
+/// Debt is tracked in the asset's own units and valued in USDC for the credit
+/// limit and health factor; an oracle-priced asset's feed is the first
+/// remaining account, followed by the oracles of the position's slot
+/// collateral as `UserPosition::collateral_values` expects them. A position
+/// owes one asset at a time.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, BorrowAsset<'info>>, amount: u64) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let user_position = &mut ctx.accounts.user_position;
+    let clock = Clock::get()?;
+
+    require!(amount > 0, CreditError::AmountTooSmall);
+    require!(!user_position.buffer_breached, CreditError::BufferBreached);
+    require!(
+        user_position.meets_min_age(config.min_position_age_secs, clock.unix_timestamp),
+        CreditError::PositionTooNew
+    );
+    let lendable = config.lendable_liquidity(&ctx.accounts.debt_mint.key(), ctx.accounts.asset_vault.amount)?;
+    require!(
+        lendable >= amount,
+        CreditError::InsufficientProtocolLiquidity
+    );
+    require!(
+        config.borrow_index_fresh(clock.unix_timestamp),
+        CreditError::StaleBorrowIndex
+    );
+
+    // Update global interest index and the position's existing debt
+    config.accrue_interest(clock.unix_timestamp)?;
+    user_position.settle_interest(config, clock.unix_timestamp)?;
+    user_position.select_debt_mint(ctx.accounts.debt_mint.key())?;
+
+    let new_debt = user_position.debt_usdc
+        .checked_add(amount)
+        .ok_or(CreditError::MathOverflow)?;
+    let (debt_oracle, collateral_oracles) = config.split_debt_oracle(&user_position.debt_mint, ctx.remaining_accounts);
+    let new_debt_value = user_position.debt_value(
+        config,
+        new_debt,
+        debt_oracle,
+        &clock,
+        config.borrow_max_staleness_slots
+    )?;
+
+    config.check_oracle(&user_position.collateral_mint, ctx.accounts.sol_usd_oracle.key)?;
+
+    // Revalue collateral at the current (confidence-adjusted) price
+    let sol_price = get_price_with_fallback(
+        &ctx.accounts.sol_usd_oracle.to_account_info(),
+        &clock,
+        config.borrow_max_staleness_slots,
+        config.expected_quote_decimals(&ctx.accounts.sol_usd_oracle.key())
+    )?
+    .with_ema(config.uses_ema_for(EMA_FOR_BORROWS))?
+    .collateral_price(config.max_confidence_bps, config.borrow_valuation_mode);
+
+    let collateral_values = user_position.collateral_values(
+        config,
+        sol_price,
+        collateral_oracles,
+        &clock,
+        config.borrow_max_staleness_slots,
+        |price| Ok(price
+            .with_ema(config.uses_ema_for(EMA_FOR_BORROWS))?
+            .collateral_price(config.max_confidence_bps, config.borrow_valuation_mode))
+    )?;
+    user_position.update_credit_limit(
+        config.weighted_credit_limit(&collateral_values)?,
+        CREDIT_LIMIT_BORROW,
+        sol_price,
+        clock.unix_timestamp
+    );
+    require!(
+        new_debt_value <= user_position.credit_limit,
+        CreditError::DebtLimitExceeded
+    );
+
+    require!(
+        config.within_position_debt_cap(new_debt_value),
+        CreditError::PositionDebtCapExceeded
+    );
+
+    // The liquidity share cap applies to the asset's own vault
+    if let Some(liquidity_limit) = config.liquidity_credit_limit(
+        user_position.debt_usdc,
+        lendable
+    )? {
+        require!(new_debt <= liquidity_limit, CreditError::InsufficientProtocolLiquidity);
+    }
+
+    let health_factor = calculate_health_factor(
+        sum_collateral_values(&collateral_values)?,
+        new_debt_value,
+        config.liquidation_threshold_bps
+    )?;
+    require!(
+        config.health_after_allowed(None, health_factor),
+        CreditError::UnhealthyPosition
+    );
+
+    let vault_authority_bump = ctx.bumps.vault_authority;
+    let vault_authority_seeds = &[VAULT_AUTHORITY_SEED, &[vault_authority_bump]];
+    let signer_seeds = &[&vault_authority_seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.asset_vault.to_account_info(),
+        to: ctx.accounts.user_asset_account.to_account_info(),
+        authority: ctx.accounts.vault_authority.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds), amount)?;
+
+    if user_position.debt_usdc == 0 {
+        user_position.borrow_start_timestamp = clock.unix_timestamp;
+    }
+    user_position.debt_usdc = new_debt;
+    user_position.last_update_slot = clock.slot;
+    user_position.last_update_timestamp = clock.unix_timestamp;
+
+    let total_debt = config.debt_total_mut(&user_position.debt_mint)?;
+    *total_debt = total_debt
+        .checked_add(amount)
+        .ok_or(CreditError::MathOverflow)?;
+
+    msg!(
+        "Borrowed {} of {} for user: {}, debt worth {} USDC, health factor: {}",
+        amount,
+        user_position.debt_mint,
+        user_position.owner,
+        new_debt_value,
+        health_factor
+    );
+
+    emit!(AssetBorrowed {
+        user: user_position.owner,
+        event_seq: user_position.next_event_seq(),
+        webhook_id: user_position.webhook_id,
+        debt_mint: user_position.debt_mint,
+        amount,
+        new_debt,
+        debt_value: new_debt_value,
+        health_factor,
+        timestamp: clock.unix_timestamp,
+    });
+
+    #[cfg(feature = "verbose-state")]
+    user_position.log_state(Some(sol_price), config.liquidation_threshold_bps);
+
+    Ok(())
+}
+
+#[event]
+pub struct AssetBorrowed {
+    pub user: Pubkey,
+    pub event_seq: u64,
+    pub webhook_id: [u8; 32],
+    pub debt_mint: Pubkey,
+    pub amount: u64,
+    /// Debt after the borrow, in the asset's units
+    pub new_debt: u64,
+    /// USDC value of the debt after the borrow (6 decimals)
+    pub debt_value: u64,
+    pub health_factor: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,249 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::state::{Config, UserPosition};
+use crate::utils::{get_price_with_fallback, calculate_health_factor, sum_collateral_values};
+use crate::constants::*;
+use crate::errors::CreditError;
+
+#[derive(Accounts)]
+pub struct BorrowUsdc<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, borrower.key().as_ref()],
+        bump,
+        constraint = user_position.owner == borrower.key() @ CreditError::Unauthorized,
+        constraint = user_position.is_initialized @ CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = !config.is_paused_for(PAUSE_BORROWS) @ CreditError::ProtocolPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Treasury USDC token account (program vault for the USDC mint)
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, config.usdc_mint.as_ref()],
+        bump,
+        token::mint = config.usdc_mint,
+        token::authority = vault_authority,
+        constraint = treasury_usdc.delegate.is_none() @ CreditError::VaultDelegateSet
+    )]
+    pub treasury_usdc: Account<'info, TokenAccount>,
+
+    /// PDA authority for the vaults
+    /// CHECK: This is the PDA that has authority over the treasury
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// User's USDC associated token account, created (borrower pays rent) if missing
+    #[account(
+        init_if_needed,
+        payer = borrower,
+        associated_token::mint = usdc_mint,
+        associated_token::authority = borrower
+    )]
+    pub user_usdc_account: Account<'info, TokenAccount>,
+
+    #[account(address = config.usdc_mint @ CreditError::InvalidMint)]
+    pub usdc_mint: Account<'info, Mint>,
+
+    /// Pyth oracle for SOL/USD price
+    /// CHECK: Validated in handler
+    pub sol_usd_oracle: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Borrow USDC against everything the position holds
+///
+/// Borrow power sums each collateral's value at its own LTV. The legacy
+/// collateral pair is priced by `sol_usd_oracle`; `remaining_accounts` holds the
+/// registered oracle of every occupied multi-collateral slot, in slot order,
+/// followed by the exchange-rate feed of each held collateral priced through one.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BorrowUsdc<'info>>,
+    amount_usdc: u64,
+    client_version: u8,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let user_position = &mut ctx.accounts.user_position;
+    let clock = Clock::get()?;
+
+    // Reject clients built against an older instruction layout
+    require!(config.accepts_client_version(client_version), CreditError::ClientVersionTooOld);
+
+    require!(amount_usdc >= MIN_REPAY_AMOUNT, CreditError::AmountTooSmall);
+    require!(!user_position.buffer_breached, CreditError::BufferBreached);
+    require!(
+        user_position.meets_min_age(config.min_position_age_secs, clock.unix_timestamp),
+        CreditError::PositionTooNew
+    );
+
+    // Fail clearly rather than inside the token transfer when suppliers have withdrawn;
+    // buffers, debit balances and reserves sitting in the treasury are not lendable
+    let lendable_usdc = config.lendable_liquidity(&config.usdc_mint, ctx.accounts.treasury_usdc.amount)?;
+    require!(
+        lendable_usdc >= amount_usdc,
+        CreditError::InsufficientProtocolLiquidity
+    );
+
+    // A long-idle index has to be caught up by an accrue_interest earlier in the
+    // transaction rather than in one step here
+    require!(
+        config.borrow_index_fresh(clock.unix_timestamp),
+        CreditError::StaleBorrowIndex
+    );
+
+    // Update global interest index
+    config.accrue_interest(clock.unix_timestamp)?;
+
+    // Update user's existing debt with interest
+    user_position.settle_interest(config, clock.unix_timestamp)?;
+    user_position.select_debt_mint(Pubkey::default())?;
+
+    // Check if user can borrow this amount
+    let new_debt = user_position.debt_usdc
+        .checked_add(amount_usdc)
+        .ok_or(CreditError::MathOverflow)?;
+
+    config.check_oracle(&user_position.collateral_mint, ctx.accounts.sol_usd_oracle.key)?;
+
+    // Revalue collateral at the current (confidence-adjusted) price
+    let sol_price = get_price_with_fallback(
+        &ctx.accounts.sol_usd_oracle.to_account_info(),
+        &clock,
+        config.borrow_max_staleness_slots,
+        config.expected_quote_decimals(&ctx.accounts.sol_usd_oracle.key())
+    )?
+    .with_ema(config.uses_ema_for(EMA_FOR_BORROWS))?
+    .collateral_price(config.max_confidence_bps, config.borrow_valuation_mode);
+
+    let collateral_values = user_position.collateral_values(
+        config,
+        sol_price,
+        ctx.remaining_accounts,
+        &clock,
+        config.borrow_max_staleness_slots,
+        |price| Ok(price
+            .with_ema(config.uses_ema_for(EMA_FOR_BORROWS))?
+            .collateral_price(config.max_confidence_bps, config.borrow_valuation_mode))
+    )?;
+    let collateral_value_usd = sum_collateral_values(&collateral_values)?;
+    let credit_limit = config.weighted_credit_limit(&collateral_values)?;
+    user_position.update_credit_limit(credit_limit, CREDIT_LIMIT_BORROW, sol_price, clock.unix_timestamp);
+
+    // Ensure not exceeding credit limit, counting credit held for linked debit spends
+    let committed_debt = new_debt
+        .checked_add(user_position.pending_credit_usdc)
+        .ok_or(CreditError::MathOverflow)?;
+    require!(
+        committed_debt <= user_position.credit_limit,
+        CreditError::DebtLimitExceeded
+    );
+
+    // However well collateralized, no single position may owe more than the cap
+    require!(
+        config.within_position_debt_cap(new_debt),
+        CreditError::PositionDebtCapExceeded
+    );
+
+    // However much collateral backs it, one position may only take its share
+    // of the USDC still available to lend
+    if let Some(liquidity_limit) = config.liquidity_credit_limit(
+        user_position.debt_usdc,
+        lendable_usdc
+    )? {
+        require!(new_debt <= liquidity_limit, CreditError::InsufficientProtocolLiquidity);
+    }
+
+    // Check health factor after borrowing
+    let health_factor = calculate_health_factor(
+        collateral_value_usd,
+        new_debt,
+        config.liquidation_threshold_bps
+    )?;
+
+    // Require the configured minimum health factor
+    require!(
+        config.health_after_allowed(None, health_factor),
+        CreditError::UnhealthyPosition
+    );
+
+    // Transfer USDC from treasury to user
+    let vault_authority_bump = ctx.bumps.vault_authority;
+    let vault_authority_seeds = &[
+        VAULT_AUTHORITY_SEED,
+        &[vault_authority_bump]
+    ];
+    let signer = &[&vault_authority_seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.treasury_usdc.to_account_info(),
+        to: ctx.accounts.user_usdc_account.to_account_info(),
+        authority: ctx.accounts.vault_authority.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+    token::transfer(cpi_ctx, amount_usdc)?;
+
+    // Update user position
+    if user_position.debt_usdc == 0 {
+        user_position.borrow_start_timestamp = clock.unix_timestamp;
+    }
+    user_position.debt_usdc = new_debt;
+    user_position.lifetime_borrows = user_position.lifetime_borrows
+        .checked_add(amount_usdc)
+        .ok_or(CreditError::MathOverflow)?;
+    user_position.last_update_slot = clock.slot;
+    user_position.last_update_timestamp = clock.unix_timestamp;
+
+    // Update global debt
+    config.total_debt_usdc = config.total_debt_usdc
+        .checked_add(amount_usdc)
+        .ok_or(CreditError::MathOverflow)?;
+
+    msg!("Borrowed {} USDC for user: {}", amount_usdc, ctx.accounts.borrower.key());
+    msg!("New debt: {} USDC, Health factor: {}", new_debt, health_factor);
+
+    // Emit event for indexers
+    emit!(DebtBorrowed {
+        user: ctx.accounts.borrower.key(),
+        event_seq: user_position.next_event_seq(),
+        webhook_id: user_position.webhook_id,
+        amount: amount_usdc,
+        new_debt,
+        health_factor,
+        timestamp: clock.unix_timestamp,
+    });
+
+    #[cfg(feature = "verbose-state")]
+    user_position.log_state(Some(sol_price), config.liquidation_threshold_bps);
+
+    Ok(())
+}
+
+#[event]
+pub struct DebtBorrowed {
+    pub user: Pubkey,
+    pub event_seq: u64,
+    pub webhook_id: [u8; 32],
+    pub amount: u64,
+    pub new_debt: u64,
+    pub health_factor: u64,
+    pub timestamp: i64,
+}
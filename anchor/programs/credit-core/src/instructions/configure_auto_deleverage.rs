@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::{Config, UserPosition};
+
+#[derive(Accounts)]
+pub struct ConfigureAutoDeleverage<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, owner.key().as_ref()],
+        bump,
+        constraint = user_position.owner == owner.key() @ CreditError::Unauthorized,
+        constraint = user_position.is_initialized @ CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+    
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// Owner's USDC token account funding (or receiving) the buffer
+    #[account(
+        mut,
+        constraint = user_usdc_account.owner == owner.key() @ CreditError::Unauthorized,
+        constraint = user_usdc_account.mint == config.usdc_mint @ CreditError::InvalidMint
+    )]
+    pub user_usdc_account: Account<'info, TokenAccount>,
+    
+    /// Program's USDC treasury account holding the buffer
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, config.usdc_mint.as_ref()],
+        bump,
+        token::mint = config.usdc_mint,
+        token::authority = vault_authority
+    )]
+    pub treasury_usdc_account: Account<'info, TokenAccount>,
+    
+    /// PDA authority for the treasury
+    /// CHECK: This is the PDA that has authority over the treasury
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    pub owner: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+/// Opt in to auto-deleverage and top up the repay buffer
+///
+/// A threshold of 0 opts out and refunds whatever is left in the buffer.
+pub fn handler(
+    ctx: Context<ConfigureAutoDeleverage>,
+    threshold_bps: u16,
+    buffer_deposit: u64,
+) -> Result<()> {
+    // The threshold has to sit above the liquidation line to be any use
+    require!(
+        threshold_bps == 0
+            || (threshold_bps as u64 > BPS_PRECISION && threshold_bps <= MAX_AUTO_DELEVERAGE_THRESHOLD_BPS),
+        CreditError::InvalidParameter
+    );
+    
+    if threshold_bps == 0 {
+        require!(buffer_deposit == 0, CreditError::InvalidParameter);
+        
+        let refund = ctx.accounts.user_position.deleverage_buffer_usdc;
+        if refund > 0 {
+            let vault_authority_bump = ctx.bumps.vault_authority;
+            let vault_authority_seeds = &[VAULT_AUTHORITY_SEED, &[vault_authority_bump]];
+            let signer_seeds = &[&vault_authority_seeds[..]];
+            
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.treasury_usdc_account.to_account_info(),
+                to: ctx.accounts.user_usdc_account.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds), refund)?;
+        }
+        
+        let config = &mut ctx.accounts.config;
+        config.total_deleverage_buffers_usdc = config.total_deleverage_buffers_usdc.saturating_sub(refund);
+        
+        let user_position = &mut ctx.accounts.user_position;
+        user_position.auto_deleverage_threshold_bps = 0;
+        user_position.deleverage_buffer_usdc = 0;
+        
+        msg!("Disabled auto-deleverage for {}, refunded {} USDC", user_position.owner, refund);
+        return Ok(());
+    }
+    
+    if buffer_deposit > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.user_usdc_account.to_account_info(),
+            to: ctx.accounts.treasury_usdc_account.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new(cpi_program, cpi_accounts), buffer_deposit)?;
+    }
+    
+    let config = &mut ctx.accounts.config;
+    config.total_deleverage_buffers_usdc = config.total_deleverage_buffers_usdc
+        .checked_add(buffer_deposit)
+        .ok_or(CreditError::MathOverflow)?;
+    
+    let user_position = &mut ctx.accounts.user_position;
+    user_position.auto_deleverage_threshold_bps = threshold_bps;
+    user_position.deleverage_buffer_usdc = user_position.deleverage_buffer_usdc
+        .checked_add(buffer_deposit)
+        .ok_or(CreditError::MathOverflow)?;
+    
+    msg!(
+        "Auto-deleverage for {} at health factor {} bps, buffer: {} USDC",
+        user_position.owner,
+        threshold_bps,
+        user_position.deleverage_buffer_usdc
+    );
+    
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::UserPosition;
+
+#[derive(Accounts)]
+pub struct ConfigureStopLoss<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, owner.key().as_ref()],
+        bump,
+        constraint = user_position.owner == owner.key() @ CreditError::Unauthorized,
+        constraint = user_position.is_initialized @ CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+    
+    pub owner: Signer<'info>,
+}
+
+/// Set the health factor at which keepers may sell collateral to repay debt (0 disables)
+pub fn handler(ctx: Context<ConfigureStopLoss>, stop_loss_health_bps: u16) -> Result<()> {
+    // The stop-loss has to sit above the liquidation line to be any use
+    require!(
+        stop_loss_health_bps == 0
+            || (stop_loss_health_bps as u64 > BPS_PRECISION && stop_loss_health_bps <= MAX_STOP_LOSS_HEALTH_BPS),
+        CreditError::InvalidParameter
+    );
+    
+    let user_position = &mut ctx.accounts.user_position;
+    user_position.stop_loss_health_bps = stop_loss_health_bps;
+    
+    msg!("Stop-loss for {} at health factor {} bps", user_position.owner, stop_loss_health_bps);
+    
+    Ok(())
+}
@@ -1,19 +1,26 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 
-use crate::state::DebitAccount;
+use crate::state::{Config, DebitAccount};
+use crate::constants::*;
 use crate::errors::CreditError;
 
 /// Deposit USDC into debit account
 pub fn handler(ctx: Context<DebitDeposit>, amount: u64) -> Result<()> {
     let debit_account = &mut ctx.accounts.debit_account;
+    let config = &mut ctx.accounts.config;
     let clock = Clock::get()?;
     
+    require!(!config.is_paused_for(PAUSE_DEPOSITS), CreditError::ProtocolPaused);
+    require!(amount > 0, CreditError::AmountTooSmall);
+    
     // Initialize if first deposit
     if !debit_account.is_initialized {
+        let limits = config
+            .tier_limits(DEBIT_TIER_BASIC)
+            .ok_or(error!(CreditError::InvalidDebitTier))?;
         debit_account.owner = ctx.accounts.user.key();
-        debit_account.daily_limit = 1000_000_000; // $1000 default
-        debit_account.monthly_limit = 10000_000_000; // $10,000 default
+        debit_account.apply_tier(DEBIT_TIER_BASIC, limits);
         debit_account.status = 1; // Active
         debit_account.is_initialized = true;
         debit_account.last_daily_reset = clock.unix_timestamp;
@@ -45,6 +52,10 @@ pub fn handler(ctx: Context<DebitDeposit>, amount: u64) -> Result<()> {
         .checked_add(amount)
         .ok_or(error!(CreditError::MathOverflow))?;
     
+    config.total_debit_balances_usdc = config.total_debit_balances_usdc
+        .checked_add(amount)
+        .ok_or(error!(CreditError::MathOverflow))?;
+    
     msg!("Deposited {} USDC to debit account", amount);
     msg!("New available balance: {}", debit_account.usdc_available);
     
@@ -60,22 +71,31 @@ pub struct DebitDeposit<'info> {
         init_if_needed,
         payer = user,
         space = DebitAccount::LEN,
-        seeds = [b"debit", user.key().as_ref()],
+        seeds = [DEBIT_ACCOUNT_SEED, user.key().as_ref()],
         bump
     )]
     pub debit_account: Account<'info, DebitAccount>,
     
     #[account(
         mut,
-        constraint = user_usdc_account.owner == user.key() @ CreditError::InvalidOwner,
-        constraint = user_usdc_account.mint == anchor_spl::token::spl_token::native_mint::ID @ CreditError::InvalidMint
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        constraint = user_usdc_account.owner == user.key() @ CreditError::Unauthorized,
+        constraint = user_usdc_account.mint == config.usdc_mint @ CreditError::InvalidMint
     )]
     pub user_usdc_account: Account<'info, TokenAccount>,
     
+    /// Program's USDC vault
     #[account(
         mut,
-        seeds = [b"vault", b"usdc"],
-        bump
+        seeds = [VAULT_SEED, config.usdc_mint.as_ref()],
+        bump,
+        token::mint = config.usdc_mint
     )]
     pub vault_usdc_account: Account<'info, TokenAccount>,
     
@@ -1,15 +1,72 @@
 use anchor_lang::prelude::*;
 
-use crate::state::{DebitAccount, Config};
+use crate::state::{DebitAccount, Config, UserPosition};
+use crate::constants::*;
 use crate::errors::CreditError;
+use super::debit_spend::linked_credit_position;
 
 /// Settle a debit card transaction (commit reserved funds)
+///
+/// Whatever the debit reserve can't cover comes out of the credit held on the
+/// linked position and is booked there as USDC debt.
 pub fn handler(ctx: Context<DebitSettle>, amount: u64) -> Result<()> {
+    let dust_threshold = ctx.accounts.config.debit_dust_threshold;
     let debit_account = &mut ctx.accounts.debit_account;
     let clock = Clock::get()?;
     
     // Commit the reserved funds
-    debit_account.commit_reserved(amount, clock.unix_timestamp)?;
+    let from_credit = debit_account.commit_reserved(amount, clock.unix_timestamp)?;
+    
+    // The debit part of the spend is paid out and no longer owed to the owner
+    let config = &mut ctx.accounts.config;
+    config.total_debit_balances_usdc = config.total_debit_balances_usdc.saturating_sub(amount - from_credit);
+    
+    if from_credit > 0 {
+        let config = &mut ctx.accounts.config;
+        let user_position = linked_credit_position(debit_account, ctx.accounts.linked_position.as_mut())?;
+        
+        config.accrue_interest(clock.unix_timestamp)?;
+        user_position.settle_interest(config, clock.unix_timestamp)?;
+        user_position.select_debt_mint(Pubkey::default())?;
+        
+        if user_position.debt_usdc == 0 {
+            user_position.borrow_start_timestamp = clock.unix_timestamp;
+        }
+        user_position.pending_credit_usdc = user_position.pending_credit_usdc
+            .checked_sub(from_credit)
+            .ok_or(CreditError::MathOverflow)?;
+        user_position.debt_usdc = user_position.debt_usdc
+            .checked_add(from_credit)
+            .ok_or(CreditError::MathOverflow)?;
+        user_position.lifetime_borrows = user_position.lifetime_borrows
+            .checked_add(from_credit)
+            .ok_or(CreditError::MathOverflow)?;
+        user_position.last_update_slot = clock.slot;
+        user_position.last_update_timestamp = clock.unix_timestamp;
+        
+        config.total_debt_usdc = config.total_debt_usdc
+            .checked_add(from_credit)
+            .ok_or(CreditError::MathOverflow)?;
+        
+        msg!("Booked {} USDC of the spend as debt on linked credit position", from_credit);
+        
+        // The same residue on the credit side would hold the position's credit indefinitely
+        let credit_dust = debit_account.credit_reserved;
+        if credit_dust > 0 && credit_dust < dust_threshold {
+            debit_account.release_credit(credit_dust)?;
+            user_position.pending_credit_usdc = user_position.pending_credit_usdc
+                .checked_sub(credit_dust)
+                .ok_or(CreditError::MathOverflow)?;
+            msg!("Released {} USDC of held credit dust", credit_dust);
+        }
+    }
+    
+    // Settling slightly under the authorization leaves a residue nothing will commit
+    let dust = debit_account.usdc_reserved;
+    if dust > 0 && dust < dust_threshold {
+        debit_account.release_reserved(dust)?;
+        msg!("Released {} USDC of reserved dust", dust);
+    }
     
     msg!("Settled {} USDC debit transaction", amount);
     msg!("Lifetime spent: {}", debit_account.lifetime_spent);
@@ -25,7 +82,8 @@ pub struct DebitSettle<'info> {
     pub authority: Signer<'info>,
     
     #[account(
-        seeds = [b"config"],
+        mut,
+        seeds = [CONFIG_SEED],
         bump,
         constraint = authority.key() == config.admin @ CreditError::Unauthorized
     )]
@@ -33,10 +91,13 @@ pub struct DebitSettle<'info> {
     
     #[account(
         mut,
-        seeds = [b"debit", debit_account.owner.as_ref()],
+        seeds = [DEBIT_ACCOUNT_SEED, debit_account.owner.as_ref()],
         bump
     )]
     pub debit_account: Account<'info, DebitAccount>,
+    
+    /// Credit position linked to the debit account; only needed when the
+    /// settlement draws on credit held for the spend
+    #[account(mut)]
+    pub linked_position: Option<Account<'info, UserPosition>>,
 }
-
-
@@ -1,28 +1,54 @@
 use anchor_lang::prelude::*;
 
-use crate::state::{DebitAccount, Config};
+use crate::state::{DebitAccount, Config, UserPosition};
+use crate::constants::*;
 use crate::errors::CreditError;
 
 /// Record a debit card spend (called by backend after authorization)
+///
+/// A spend beyond the available balance is declined unless the account has a
+/// linked credit position: then the balance is drained and the shortfall is
+/// held against the position's credit line until the spend settles.
 pub fn handler(ctx: Context<DebitSpend>, amount: u64) -> Result<()> {
     let debit_account = &mut ctx.accounts.debit_account;
-    let config = &ctx.accounts.config;
+    let config = &mut ctx.accounts.config;
     let clock = Clock::get()?;
     
-    // Check if protocol is paused
-    require!(!config.is_paused, CreditError::ProtocolPaused);
+    // Card spends are blocked along with borrows, not only by the global pause
+    require!(!config.is_paused_for(PAUSE_BORROWS), CreditError::ProtocolPaused);
     
     // Check if account is active
     require!(debit_account.status == 1, CreditError::AccountNotActive);
     
     // Check if spending is allowed
     require!(
-        debit_account.can_spend(amount, clock.unix_timestamp)?,
+        debit_account.within_limits(amount, clock.unix_timestamp)?,
         CreditError::SpendingNotAllowed
     );
     
-    // Reserve funds for this transaction
-    debit_account.reserve_funds(amount)?;
+    let from_debit = amount.min(debit_account.usdc_available);
+    let from_credit = amount - from_debit;
+    require!(
+        from_credit == 0 || debit_account.has_linked_position(),
+        CreditError::SpendingNotAllowed
+    );
+    
+    // Bound pending authorizations so they can't lock the whole balance
+    require!(
+        debit_account.active_reservations < config.max_active_reservations,
+        CreditError::TooManyReservations
+    );
+    
+    if from_credit == 0 {
+        // Reserve funds for this transaction
+        debit_account.reserve_funds(amount)?;
+    } else {
+        let user_position = linked_credit_position(debit_account, ctx.accounts.linked_position.as_mut())?;
+        hold_credit(config, user_position, from_credit, &clock)?;
+        debit_account.reserve_with_credit(from_debit, from_credit)?;
+        
+        msg!("Held {} USDC of the spend against linked credit position", from_credit);
+    }
     
     msg!("Reserved {} USDC for debit spend", amount);
     msg!("Available: {}, Reserved: {}", 
@@ -33,13 +59,66 @@ pub fn handler(ctx: Context<DebitSpend>, amount: u64) -> Result<()> {
     Ok(())
 }
 
+/// The position passed for the debit account's credit leg, checked against its link
+pub(crate) fn linked_credit_position<'a, 'info>(
+    debit_account: &DebitAccount,
+    linked_position: Option<&'a mut Account<'info, UserPosition>>,
+) -> Result<&'a mut Account<'info, UserPosition>> {
+    let user_position = linked_position.ok_or(error!(CreditError::Unauthorized))?;
+    require_keys_eq!(user_position.key(), debit_account.linked_position, CreditError::Unauthorized);
+    require_keys_eq!(user_position.owner, debit_account.owner, CreditError::Unauthorized);
+    Ok(user_position)
+}
+
+/// Hold `amount` of the position's remaining USDC credit for a pending spend
+///
+/// Debt is settled first so accrued interest counts against the limit along
+/// with every hold already outstanding.
+fn hold_credit(
+    config: &mut Config,
+    user_position: &mut UserPosition,
+    amount: u64,
+    clock: &Clock,
+) -> Result<()> {
+    require!(user_position.is_initialized, CreditError::PositionNotInitialized);
+    require!(!user_position.buffer_breached, CreditError::BufferBreached);
+    require!(
+        config.borrow_index_fresh(clock.unix_timestamp),
+        CreditError::StaleBorrowIndex
+    );
+    
+    config.accrue_interest(clock.unix_timestamp)?;
+    user_position.settle_interest(config, clock.unix_timestamp)?;
+    user_position.select_debt_mint(Pubkey::default())?;
+    
+    let pending_credit = user_position.pending_credit_usdc
+        .checked_add(amount)
+        .ok_or(CreditError::MathOverflow)?;
+    let committed_debt = user_position.debt_usdc
+        .checked_add(pending_credit)
+        .ok_or(CreditError::MathOverflow)?;
+    require!(
+        committed_debt <= user_position.credit_limit,
+        CreditError::DebtLimitExceeded
+    );
+    require!(
+        config.within_position_debt_cap(committed_debt),
+        CreditError::PositionDebtCapExceeded
+    );
+    
+    user_position.pending_credit_usdc = pending_credit;
+    
+    Ok(())
+}
+
 #[derive(Accounts)]
 pub struct DebitSpend<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
     
     #[account(
-        seeds = [b"config"],
+        mut,
+        seeds = [CONFIG_SEED],
         bump,
         constraint = authority.key() == config.admin @ CreditError::Unauthorized
     )]
@@ -47,10 +126,13 @@ pub struct DebitSpend<'info> {
     
     #[account(
         mut,
-        seeds = [b"debit", debit_account.owner.as_ref()],
+        seeds = [DEBIT_ACCOUNT_SEED, debit_account.owner.as_ref()],
         bump
     )]
     pub debit_account: Account<'info, DebitAccount>,
+    
+    /// Credit position linked to the debit account; only needed when the spend
+    /// exceeds the available balance
+    #[account(mut)]
+    pub linked_position: Option<Account<'info, UserPosition>>,
 }
-
-
@@ -0,0 +1,290 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::state::{Config, UserPosition};
+use crate::utils::{get_price_with_fallback, calculate_collateral_value, calculate_health_factor, calculate_max_borrow};
+use crate::constants::*;
+use crate::errors::CreditError;
+use super::borrow_usdc::DebtBorrowed;
+use super::deposit_collateral_wsol::CollateralDeposited;
+
+#[derive(Accounts)]
+pub struct DepositAndBorrow<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, owner.key().as_ref()],
+        bump,
+        constraint = user_position.owner == owner.key() @ CreditError::Unauthorized,
+        constraint = user_position.is_initialized @ CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = !config.is_paused_for(PAUSE_DEPOSITS | PAUSE_BORROWS) @ CreditError::ProtocolPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = user_wsol_account.owner == owner.key() @ CreditError::Unauthorized,
+        constraint = user_wsol_account.mint == config.wsol_mint @ CreditError::InvalidCollateralMint
+    )]
+    pub user_wsol_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, config.wsol_mint.as_ref()],
+        bump,
+        token::mint = config.wsol_mint,
+        token::authority = vault_authority,
+        constraint = vault_wsol.delegate.is_none() @ CreditError::VaultDelegateSet
+    )]
+    pub vault_wsol: Account<'info, TokenAccount>,
+
+    /// Treasury USDC token account (program vault for the USDC mint)
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, config.usdc_mint.as_ref()],
+        bump,
+        token::mint = config.usdc_mint,
+        token::authority = vault_authority,
+        constraint = treasury_usdc.delegate.is_none() @ CreditError::VaultDelegateSet
+    )]
+    pub treasury_usdc: Account<'info, TokenAccount>,
+
+    /// PDA authority for the vaults
+    /// CHECK: This is the PDA that has authority over the treasury
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// User's USDC associated token account, created (owner pays rent) if missing
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = usdc_mint,
+        associated_token::authority = owner
+    )]
+    pub user_usdc_account: Account<'info, TokenAccount>,
+
+    #[account(address = config.usdc_mint @ CreditError::InvalidMint)]
+    pub usdc_mint: Account<'info, Mint>,
+
+    /// Pyth oracle for SOL/USD price
+    /// CHECK: Validated in handler
+    pub sol_usd_oracle: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Deposit WSOL and borrow against it in one step, borrowing up to `max_borrow_usdc`
+///
+/// Nothing has been priced in by a separate deposit, so the borrow is sized
+/// at the bottom of the oracle's confidence band whatever the valuation mode:
+/// the most that keeps the position within its credit limit and the borrow
+/// health buffer at price minus confidence (and within the liquidity share and
+/// per-position debt cap). Returns the amount borrowed.
+pub fn handler(
+    ctx: Context<DepositAndBorrow>,
+    deposit_amount: u64,
+    max_borrow_usdc: u64,
+    client_version: u8,
+) -> Result<u64> {
+    let config = &mut ctx.accounts.config;
+    let user_position = &mut ctx.accounts.user_position;
+    let clock = Clock::get()?;
+
+    // Reject clients built against an older instruction layout
+    require!(config.accepts_client_version(client_version), CreditError::ClientVersionTooOld);
+
+    require!(deposit_amount > 0, CreditError::AmountTooSmall);
+    require!(max_borrow_usdc >= MIN_REPAY_AMOUNT, CreditError::AmountTooSmall);
+    require!(!user_position.buffer_breached, CreditError::BufferBreached);
+    require!(
+        user_position.meets_min_age(config.min_position_age_secs, clock.unix_timestamp),
+        CreditError::PositionTooNew
+    );
+    require!(
+        user_position.collateral_mint == Pubkey::default() || user_position.collateral_mint == config.wsol_mint,
+        CreditError::InvalidCollateralMint
+    );
+    require!(!user_position.has_collateral_entries(), CreditError::MixedCollateralFormats);
+    require!(
+        config.collateral_oracle_healthy(&config.wsol_mint, clock.slot),
+        CreditError::OracleUnhealthy
+    );
+    require!(
+        config.borrow_index_fresh(clock.unix_timestamp),
+        CreditError::StaleBorrowIndex
+    );
+
+    // Update global interest index and settle the position's debt against it once
+    config.accrue_interest(clock.unix_timestamp)?;
+    user_position.settle_interest(config, clock.unix_timestamp)?;
+    user_position.select_debt_mint(Pubkey::default())?;
+
+    let new_collateral_amount = user_position.collateral_amount
+        .checked_add(deposit_amount)
+        .ok_or(CreditError::MathOverflow)?;
+    let new_total_collateral = config.total_collateral
+        .checked_add(deposit_amount)
+        .ok_or(CreditError::MathOverflow)?;
+
+    config.check_oracle(&config.wsol_mint, ctx.accounts.sol_usd_oracle.key)?;
+
+    // Price minus confidence, the pessimistic end of the band
+    let oracle_price = get_price_with_fallback(
+        &ctx.accounts.sol_usd_oracle.to_account_info(),
+        &clock,
+        config.borrow_max_staleness_slots,
+        config.expected_quote_decimals(&ctx.accounts.sol_usd_oracle.key())
+    )?
+    .with_ema(config.uses_ema_for(EMA_FOR_BORROWS))?;
+    let sol_price = oracle_price.valued_at(VALUATION_BID);
+    require!(
+        config.within_collateral_cap(new_total_collateral, sol_price)?,
+        CreditError::CollateralCapExceeded
+    );
+
+    let collateral_value_usd = calculate_collateral_value(new_collateral_amount, sol_price)?;
+    let credit_limit = calculate_max_borrow(collateral_value_usd, config.credit_limit_ltv_bps())?;
+
+    // Largest debt that keeps the health factor at the borrow buffer
+    let buffer_debt_limit = (collateral_value_usd as u128)
+        .checked_mul(config.liquidation_threshold_bps as u128)
+        .ok_or(CreditError::MathOverflow)?
+        / (config.min_borrow_health_factor_bps as u128).max(1);
+    let mut debt_limit = (credit_limit as u128).min(buffer_debt_limit) as u64;
+    let lendable_usdc = config.lendable_liquidity(&config.usdc_mint, ctx.accounts.treasury_usdc.amount)?;
+    if let Some(liquidity_limit) = config.liquidity_credit_limit(
+        user_position.debt_usdc,
+        lendable_usdc
+    )? {
+        debt_limit = debt_limit.min(liquidity_limit);
+    }
+    if config.max_single_position_debt_usdc > 0 {
+        debt_limit = debt_limit.min(config.max_single_position_debt_usdc);
+    }
+
+    let borrow_amount = debt_limit
+        .saturating_sub(user_position.debt_usdc)
+        .min(max_borrow_usdc)
+        .min(lendable_usdc);
+    require!(borrow_amount >= MIN_REPAY_AMOUNT, CreditError::DebtLimitExceeded);
+
+    let new_debt = user_position.debt_usdc
+        .checked_add(borrow_amount)
+        .ok_or(CreditError::MathOverflow)?;
+    let health_factor = calculate_health_factor(
+        collateral_value_usd,
+        new_debt,
+        config.liquidation_threshold_bps
+    )?;
+    require!(
+        config.health_after_allowed(None, health_factor),
+        CreditError::UnhealthyPosition
+    );
+
+    // Transfer WSOL from user to vault
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.user_wsol_account.to_account_info(),
+                to: ctx.accounts.vault_wsol.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            }
+        ),
+        deposit_amount
+    )?;
+
+    // Transfer USDC from treasury to user
+    let vault_authority_bump = ctx.bumps.vault_authority;
+    let vault_authority_seeds = &[
+        VAULT_AUTHORITY_SEED,
+        &[vault_authority_bump]
+    ];
+    let signer = &[&vault_authority_seeds[..]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.treasury_usdc.to_account_info(),
+                to: ctx.accounts.user_usdc_account.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            signer
+        ),
+        borrow_amount
+    )?;
+
+    // Update user position
+    if user_position.first_deposit_timestamp == 0 {
+        user_position.first_deposit_timestamp = clock.unix_timestamp;
+    }
+    if user_position.debt_usdc == 0 {
+        user_position.borrow_start_timestamp = clock.unix_timestamp;
+    }
+    user_position.collateral_mint = config.wsol_mint;
+    user_position.collateral_amount = new_collateral_amount;
+    user_position.update_credit_limit(credit_limit, CREDIT_LIMIT_DEPOSIT, sol_price, clock.unix_timestamp);
+    user_position.debt_usdc = new_debt;
+    user_position.lifetime_borrows = user_position.lifetime_borrows
+        .checked_add(borrow_amount)
+        .ok_or(CreditError::MathOverflow)?;
+    user_position.last_update_slot = clock.slot;
+    user_position.last_update_timestamp = clock.unix_timestamp;
+
+    // Update global totals
+    config.total_collateral = new_total_collateral;
+    config.total_debt_usdc = config.total_debt_usdc
+        .checked_add(borrow_amount)
+        .ok_or(CreditError::MathOverflow)?;
+
+    msg!(
+        "Deposited {} WSOL and borrowed {} USDC (of {} requested) for {}, health factor {}",
+        deposit_amount,
+        borrow_amount,
+        max_borrow_usdc,
+        user_position.owner,
+        health_factor
+    );
+
+    emit!(CollateralDeposited {
+        user: user_position.owner,
+        event_seq: user_position.next_event_seq(),
+        webhook_id: user_position.webhook_id,
+        amount: deposit_amount,
+        total_collateral: new_collateral_amount,
+        credit_limit,
+        available_credit: credit_limit.saturating_sub(new_debt),
+        oracle_price: sol_price,
+        oracle_publish_slot: oracle_price.publish_slot,
+        timestamp: clock.unix_timestamp,
+    });
+
+    emit!(DebtBorrowed {
+        user: user_position.owner,
+        event_seq: user_position.next_event_seq(),
+        webhook_id: user_position.webhook_id,
+        amount: borrow_amount,
+        new_debt,
+        health_factor,
+        timestamp: clock.unix_timestamp,
+    });
+
+    #[cfg(feature = "verbose-state")]
+    user_position.log_state(Some(sol_price), config.liquidation_threshold_bps);
+
+    Ok(borrow_amount)
+}
@@ -0,0 +1,203 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::state::{Config, UserPosition};
+use crate::errors::CreditError;
+use crate::utils::{get_price_with_fallback, calculate_collateral_value};
+use crate::constants::*;
+use super::deposit_collateral_wsol::CollateralDeposited;
+
+#[derive(Accounts)]
+pub struct DepositCollateralBatch<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, owner.key().as_ref()],
+        bump,
+        constraint = user_position.owner == owner.key() @ CreditError::Unauthorized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Pyth oracle account
+    pub sol_usd_oracle: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Deposit collateral from several token accounts in one transaction
+///
+/// `remaining_accounts` holds `(source_token_account, vault)` pairs, one per
+/// entry in `amounts`, then the debt asset's oracle if the position owes
+/// something other than USDC. Every source must hold the position's collateral
+/// mint; the position is revalued once after all transfers.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DepositCollateralBatch<'info>>,
+    amounts: Vec<u64>,
+    client_version: u8,
+) -> Result<()> {
+    require!(!ctx.accounts.config.is_paused_for(PAUSE_DEPOSITS), CreditError::ProtocolPaused);
+    require!(
+        ctx.accounts.config.accepts_client_version(client_version),
+        CreditError::ClientVersionTooOld
+    );
+    require!(
+        !amounts.is_empty() && amounts.len() <= MAX_BATCH_DEPOSITS,
+        CreditError::InvalidParameter
+    );
+    require!(
+        ctx.remaining_accounts.len() == amounts.len() * 2
+            || ctx.remaining_accounts.len() == amounts.len() * 2 + 1,
+        CreditError::InvalidParameter
+    );
+
+    // Legacy collateral cannot sit beside multi-collateral slots
+    require!(
+        !ctx.accounts.user_position.has_collateral_entries(),
+        CreditError::MixedCollateralFormats
+    );
+
+    // Only WSOL collateral is priced, so every tranche must be WSOL
+    let collateral_mint = ctx.accounts.config.wsol_mint;
+    if ctx.accounts.user_position.collateral_mint == Pubkey::default() {
+        ctx.accounts.user_position.collateral_mint = collateral_mint;
+    } else {
+        require_keys_eq!(
+            ctx.accounts.user_position.collateral_mint,
+            collateral_mint,
+            CreditError::InvalidCollateralMint
+        );
+    }
+
+    let (expected_vault, _) = Pubkey::find_program_address(
+        &[VAULT_SEED, collateral_mint.as_ref()],
+        ctx.program_id
+    );
+    let (vault_authority, _) = Pubkey::find_program_address(&[VAULT_AUTHORITY_SEED], ctx.program_id);
+
+    let mut total_deposited: u64 = 0;
+    for (pair, amount) in ctx.remaining_accounts.chunks(2).zip(amounts.iter()) {
+        let source_info = &pair[0];
+        let vault_info = &pair[1];
+
+        require!(*amount > 0, CreditError::AmountTooSmall);
+
+        let source = Account::<TokenAccount>::try_from(source_info)?;
+        require_keys_eq!(source.mint, collateral_mint, CreditError::InvalidCollateralMint);
+        require_keys_eq!(source.owner, ctx.accounts.owner.key(), CreditError::Unauthorized);
+        require_keys_eq!(vault_info.key(), expected_vault, CreditError::InvalidAuthority);
+        let vault = Account::<TokenAccount>::try_from(vault_info)?;
+        require!(
+            vault.owner == vault_authority && vault.delegate.is_none(),
+            CreditError::VaultDelegateSet
+        );
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: source_info.clone(),
+                    to: vault_info.clone(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                }
+            ),
+            *amount
+        )?;
+
+        total_deposited = total_deposited
+            .checked_add(*amount)
+            .ok_or(CreditError::MathOverflow)?;
+    }
+
+    let config = &mut ctx.accounts.config;
+    let user_position = &mut ctx.accounts.user_position;
+    let clock = Clock::get()?;
+    require!(
+        config.collateral_oracle_healthy(&config.wsol_mint, clock.slot),
+        CreditError::OracleUnhealthy
+    );
+
+    // Update global interest index and settle the position's debt against it once
+    config.accrue_interest(clock.unix_timestamp)?;
+    user_position.settle_interest(config, clock.unix_timestamp)?;
+
+    let new_collateral_amount = user_position.collateral_amount
+        .checked_add(total_deposited)
+        .ok_or(CreditError::MathOverflow)?;
+
+    config.check_oracle(&config.wsol_mint, ctx.accounts.sol_usd_oracle.key)?;
+
+    let oracle_price = get_price_with_fallback(
+        &ctx.accounts.sol_usd_oracle.to_account_info(),
+        &clock,
+        config.borrow_max_staleness_slots,
+        config.expected_quote_decimals(&ctx.accounts.sol_usd_oracle.key())
+    )?
+    .with_ema(config.uses_ema_for(EMA_FOR_DEPOSITS))?;
+    let sol_price = oracle_price.collateral_price(config.max_confidence_bps, config.borrow_valuation_mode);
+
+    // Keep protocol TVL under the configured ceiling
+    let new_total_collateral = config.total_collateral
+        .checked_add(total_deposited)
+        .ok_or(CreditError::MathOverflow)?;
+    require!(
+        config.within_collateral_cap(new_total_collateral, sol_price)?,
+        CreditError::CollateralCapExceeded
+    );
+
+    let collateral_value_usd = calculate_collateral_value(new_collateral_amount, sol_price)?;
+    let new_credit_limit = user_position.deposit_credit_limit(
+        config,
+        collateral_value_usd,
+        sol_price,
+        clock.unix_timestamp
+    )?;
+    let available_credit = user_position.available_credit(
+        config,
+        new_credit_limit,
+        ctx.remaining_accounts.get(amounts.len() * 2),
+        &clock,
+        config.borrow_max_staleness_slots
+    )?;
+
+    // Update position once for the whole batch
+    user_position.collateral_amount = new_collateral_amount;
+    user_position.update_credit_limit(new_credit_limit, CREDIT_LIMIT_DEPOSIT, sol_price, clock.unix_timestamp);
+    user_position.last_update_slot = clock.slot;
+    user_position.last_update_timestamp = clock.unix_timestamp;
+    if user_position.first_deposit_timestamp == 0 {
+        user_position.first_deposit_timestamp = clock.unix_timestamp;
+    }
+
+    config.total_collateral = new_total_collateral;
+
+    msg!(
+        "Batch deposited {} from {} accounts - Collateral: {}, Credit Limit: ${}",
+        total_deposited,
+        amounts.len(),
+        user_position.collateral_amount,
+        new_credit_limit
+    );
+
+    emit!(CollateralDeposited {
+        user: user_position.owner,
+        event_seq: user_position.next_event_seq(),
+        webhook_id: user_position.webhook_id,
+        amount: total_deposited,
+        total_collateral: user_position.collateral_amount,
+        credit_limit: new_credit_limit,
+        available_credit,
+        oracle_price: sol_price,
+        oracle_publish_slot: oracle_price.publish_slot,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
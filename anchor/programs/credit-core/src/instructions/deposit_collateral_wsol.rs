@@ -2,7 +2,8 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 use crate::state::{Config, UserPosition};
 use crate::errors::CreditError;
-use crate::utils::{get_price_with_fallback, calculate_max_borrow, calculate_borrow_index};
+use crate::utils::{get_price_with_fallback, calculate_collateral_value};
+use crate::constants::{CREDIT_LIMIT_DEPOSIT, EMA_FOR_DEPOSITS, PAUSE_DEPOSITS};
 
 #[derive(Accounts)]
 pub struct DepositCollateralWsol<'info> {
@@ -26,7 +27,9 @@ pub struct DepositCollateralWsol<'info> {
     #[account(
         mut,
         seeds = [b"vault", wsol_mint.key().as_ref()],
-        bump
+        bump,
+        token::authority = vault_authority,
+        constraint = vault_wsol.delegate.is_none() @ CreditError::VaultDelegateSet
     )]
     pub vault_wsol: Account<'info, TokenAccount>,
 
@@ -49,12 +52,20 @@ pub struct DepositCollateralWsol<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<DepositCollateralWsol>, amount: u64) -> Result<()> {
+/// Deposit WSOL collateral
+///
+/// A position with debt in an asset other than USDC passes that asset's
+/// oracle as the first remaining account, to value the debt against the new
+/// credit limit.
+pub fn handler(ctx: Context<DepositCollateralWsol>, amount: u64, client_version: u8) -> Result<()> {
     let config = &mut ctx.accounts.config;
     let user_position = &mut ctx.accounts.user_position;
 
     // Ensure protocol is not paused
-    require!(!config.paused, CreditError::ProtocolPaused);
+    require!(!config.is_paused_for(PAUSE_DEPOSITS), CreditError::ProtocolPaused);
+    
+    // Reject clients built against an older instruction layout
+    require!(config.accepts_client_version(client_version), CreditError::ClientVersionTooOld);
 
     // Ensure WSOL mint matches config
     require_keys_eq!(
@@ -69,29 +80,6 @@ pub fn handler(ctx: Context<DepositCollateralWsol>, amount: u64) -> Result<()> {
         CreditError::Unauthorized
     );
 
-    // If this is the first deposit, set the collateral mint
-    if user_position.collateral_mint == Pubkey::default() {
-        user_position.collateral_mint = ctx.accounts.wsol_mint.key();
-    } else {
-        // Ensure user is depositing the same collateral type
-        require_keys_eq!(
-            user_position.collateral_mint,
-            ctx.accounts.wsol_mint.key(),
-            CreditError::InvalidCollateralMint
-        );
-    }
-
-    let clock = Clock::get()?;
-
-    // Update global interest index
-    config.global_borrow_index = calculate_borrow_index(
-        config.last_update_timestamp,
-        clock.unix_timestamp,
-        config.global_borrow_index,
-        config.interest_rate_bps
-    )?;
-    config.last_update_timestamp = clock.unix_timestamp;
-
     // Transfer WSOL from user to vault
     let transfer_ix = Transfer {
         from: ctx.accounts.user_wsol_account.to_account_info(),
@@ -113,44 +101,148 @@ pub fn handler(ctx: Context<DepositCollateralWsol>, amount: u64) -> Result<()> {
         ctx.accounts.owner.key()
     );
 
+    record_wsol_deposit(
+        config,
+        user_position,
+        ctx.accounts.wsol_mint.key(),
+        &ctx.accounts.sol_usd_oracle.to_account_info(),
+        ctx.remaining_accounts.first(),
+        amount
+    )
+}
+
+/// Book `amount` WSOL already moved into the vault: settle the position's
+/// interest and revalue its credit limit (shared with init_and_deposit)
+pub(crate) fn record_wsol_deposit(
+    config: &mut Config,
+    user_position: &mut UserPosition,
+    wsol_mint: Pubkey,
+    sol_usd_oracle: &AccountInfo,
+    debt_oracle: Option<&AccountInfo>,
+    amount: u64,
+) -> Result<()> {
+    // Legacy collateral cannot sit beside multi-collateral slots
+    require!(!user_position.has_collateral_entries(), CreditError::MixedCollateralFormats);
+
+    // If this is the first deposit, set the collateral mint
+    if user_position.collateral_mint == Pubkey::default() {
+        user_position.collateral_mint = wsol_mint;
+    } else {
+        // Ensure user is depositing the same collateral type
+        require_keys_eq!(
+            user_position.collateral_mint,
+            wsol_mint,
+            CreditError::InvalidCollateralMint
+        );
+    }
+
+    let clock = Clock::get()?;
+    require!(
+        config.collateral_oracle_healthy(&wsol_mint, clock.slot),
+        CreditError::OracleUnhealthy
+    );
+
+    // Update global interest index and settle the position's debt against it once
+    config.accrue_interest(clock.unix_timestamp)?;
+    user_position.settle_interest(config, clock.unix_timestamp)?;
+
     // Calculate new total collateral
     let new_collateral_amount = user_position.collateral_amount
         .checked_add(amount)
         .ok_or(CreditError::MathOverflow)?;
 
+    config.check_oracle(&wsol_mint, sol_usd_oracle.key)?;
+
     // Get current SOL price and update credit limit
-    let sol_price = get_price_with_fallback(&ctx.accounts.sol_usd_oracle.to_account_info())?;
+    // A wide confidence band values collateral at price minus confidence
+    let oracle_price = get_price_with_fallback(
+        sol_usd_oracle,
+        &clock,
+        config.borrow_max_staleness_slots,
+        config.expected_quote_decimals(sol_usd_oracle.key)
+    )?
+    .with_ema(config.uses_ema_for(EMA_FOR_DEPOSITS))?;
+    let sol_price = oracle_price.collateral_price(config.max_confidence_bps, config.borrow_valuation_mode);
+
+    // Keep protocol TVL under the configured ceiling
+    let new_total_collateral = config.total_collateral
+        .checked_add(amount)
+        .ok_or(CreditError::MathOverflow)?;
+    require!(
+        config.within_collateral_cap(new_total_collateral, sol_price)?,
+        CreditError::CollateralCapExceeded
+    );
 
     // Calculate collateral value in USD (amount is in lamports, sol_price is in USDC decimals)
-    // collateral_value_usd = amount * sol_price / 10^9
-    let collateral_value_usd = (new_collateral_amount as u128)
-        .checked_mul(sol_price as u128)
-        .ok_or(CreditError::MathOverflow)?
-        .checked_div(1_000_000_000) // Convert from lamports to SOL
-        .ok_or(CreditError::MathOverflow)? as u64;
-
-    let new_credit_limit = calculate_max_borrow(
+    let collateral_value_usd = calculate_collateral_value(new_collateral_amount, sol_price)?;
+
+    let new_credit_limit = user_position.deposit_credit_limit(
+        config,
         collateral_value_usd,
-        config.ltv_max_bps
+        sol_price,
+        clock.unix_timestamp
+    )?;
+    // What the position can still borrow with its interest-settled debt counted
+    let available_credit = user_position.available_credit(
+        config,
+        new_credit_limit,
+        debt_oracle,
+        &clock,
+        config.borrow_max_staleness_slots
     )?;
 
     // Update position
     user_position.collateral_amount = new_collateral_amount;
     user_position.last_update_slot = clock.slot;
     user_position.last_update_timestamp = clock.unix_timestamp;
-    user_position.collateral_mint = ctx.accounts.wsol_mint.key();
-    user_position.credit_limit = new_credit_limit;
+    user_position.update_credit_limit(new_credit_limit, CREDIT_LIMIT_DEPOSIT, sol_price, clock.unix_timestamp);
+    if user_position.first_deposit_timestamp == 0 {
+        user_position.first_deposit_timestamp = clock.unix_timestamp;
+    }
 
-    // Update global totals
-    config.total_collateral = config.total_collateral
-        .saturating_add(collateral_value_usd);
+    // Update global totals (collateral units, not USD)
+    config.total_collateral = new_total_collateral;
 
     msg!(
-        "Updated position - Collateral: {} WSOL, Value: ${}, Credit Limit: ${}",
+        "Updated position - Collateral: {} WSOL, Value: ${}, Credit Limit: ${}, Available: ${}",
         user_position.collateral_amount,
         collateral_value_usd,
-        new_credit_limit
+        new_credit_limit,
+        available_credit
     );
 
+    emit!(CollateralDeposited {
+        user: user_position.owner,
+        event_seq: user_position.next_event_seq(),
+        webhook_id: user_position.webhook_id,
+        amount,
+        total_collateral: user_position.collateral_amount,
+        credit_limit: new_credit_limit,
+        available_credit,
+        oracle_price: sol_price,
+        oracle_publish_slot: oracle_price.publish_slot,
+        timestamp: clock.unix_timestamp,
+    });
+
+    #[cfg(feature = "verbose-state")]
+    user_position.log_state(Some(sol_price), config.liquidation_threshold_bps);
+
     Ok(())
 }
+
+#[event]
+pub struct CollateralDeposited {
+    pub user: Pubkey,
+    pub event_seq: u64,
+    pub webhook_id: [u8; 32],
+    pub amount: u64,
+    pub total_collateral: u64,
+    pub credit_limit: u64,
+    /// Credit limit net of the position's current debt
+    pub available_credit: u64,
+    /// Collateral price the credit limit was computed at (6 decimals)
+    pub oracle_price: u64,
+    /// Slot the oracle published that price in
+    pub oracle_publish_slot: u64,
+    pub timestamp: i64,
+}
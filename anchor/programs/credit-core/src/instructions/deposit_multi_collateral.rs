@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::state::{Config, UserPosition};
+use crate::errors::CreditError;
+use crate::constants::*;
+use super::deposit_collateral_wsol::CollateralDeposited;
+
+#[derive(Accounts)]
+pub struct DepositMultiCollateral<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, owner.key().as_ref()],
+        bump,
+        constraint = user_position.owner == owner.key() @ CreditError::Unauthorized,
+        constraint = user_position.is_initialized @ CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub collateral_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_collateral_account.owner == owner.key() @ CreditError::Unauthorized,
+        constraint = user_collateral_account.mint == collateral_mint.key() @ CreditError::InvalidCollateralMint
+    )]
+    pub user_collateral_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, collateral_mint.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::authority = vault_authority,
+        constraint = vault_collateral_account.delegate.is_none() @ CreditError::VaultDelegateSet
+    )]
+    pub vault_collateral_account: Account<'info, TokenAccount>,
+
+    /// CHECK: PDA authority for the vaults
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Price feed registered for the collateral type
+    /// CHECK: Validated against the collateral type in handler
+    pub collateral_oracle: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Deposit any registered collateral into the position's multi-collateral slots
+///
+/// Positions hold collateral either in the single collateral_mint/collateral_amount
+/// pair or in the slots, so this requires the single pair to be empty. Collateral
+/// priced through an exchange rate takes its rate feed as the first remaining account.
+pub fn handler(ctx: Context<DepositMultiCollateral>, amount: u64) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let user_position = &mut ctx.accounts.user_position;
+    let mint = ctx.accounts.collateral_mint.key();
+    let clock = Clock::get()?;
+
+    require!(!config.is_paused_for(PAUSE_DEPOSITS), CreditError::ProtocolPaused);
+    require!(amount > 0, CreditError::AmountTooSmall);
+    let collateral_type = *config
+        .collateral_type(&mint)
+        .ok_or(CreditError::InvalidCollateralMint)?;
+    require_keys_eq!(ctx.accounts.collateral_oracle.key(), collateral_type.oracle, CreditError::InvalidOracle);
+    require!(
+        collateral_type.oracle_recently_healthy(clock.slot, config.oracle_health_window_slots),
+        CreditError::OracleUnhealthy
+    );
+    require!(user_position.collateral_amount == 0, CreditError::PositionHasCollateral);
+
+    // Keep protocol TVL under the configured ceiling
+    let oracle_price = config.collateral_type_price(
+        &collateral_type,
+        &ctx.accounts.collateral_oracle.to_account_info(),
+        ctx.remaining_accounts.first(),
+        &clock,
+        config.borrow_max_staleness_slots
+    )?
+    .with_ema(config.uses_ema_for(EMA_FOR_DEPOSITS))?;
+    let price = oracle_price.collateral_price(config.max_confidence_bps, config.borrow_valuation_mode);
+    let new_total_collateral = config.total_collateral
+        .checked_add(amount)
+        .ok_or(CreditError::MathOverflow)?;
+    require!(
+        config.within_collateral_cap(new_total_collateral, price)?,
+        CreditError::CollateralCapExceeded
+    );
+
+    // Update global interest index and settle the position's debt against it once
+    config.accrue_interest(clock.unix_timestamp)?;
+    user_position.settle_interest(config, clock.unix_timestamp)?;
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.user_collateral_account.to_account_info(),
+                to: ctx.accounts.vault_collateral_account.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            }
+        ),
+        amount
+    )?;
+
+    user_position.add_collateral(mint, amount)?;
+    user_position.last_update_slot = clock.slot;
+    user_position.last_update_timestamp = clock.unix_timestamp;
+    if user_position.first_deposit_timestamp == 0 {
+        user_position.first_deposit_timestamp = clock.unix_timestamp;
+    }
+
+    config.total_collateral = new_total_collateral;
+
+    msg!("Deposited {} of collateral {} for user: {}", amount, mint, user_position.owner);
+
+    // The cached limit isn't revalued here; USDC debt nets off it directly, other
+    // debt would need its oracle, so none is reported available until a refresh
+    let available_credit = if user_position.owes_usdc() {
+        user_position.credit_limit.saturating_sub(user_position.debt_usdc)
+    } else {
+        0
+    };
+
+    emit!(CollateralDeposited {
+        user: user_position.owner,
+        event_seq: user_position.next_event_seq(),
+        webhook_id: user_position.webhook_id,
+        amount,
+        total_collateral: user_position.collateral_amount,
+        credit_limit: user_position.credit_limit,
+        available_credit,
+        oracle_price: price,
+        oracle_publish_slot: oracle_price.publish_slot,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, UserPosition};
+use crate::utils::get_price_with_fallback;
+use crate::constants::*;
+
+#[derive(Accounts)]
+pub struct EmitLiquidationWarning<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, user_position.owner.as_ref()],
+        bump,
+        constraint = user_position.is_initialized @ crate::errors::CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Pyth oracle account
+    pub sol_usd_oracle: UncheckedAccount<'info>,
+
+    pub keeper: Signer<'info>,
+}
+
+/// Permissionless: emit a `LiquidationWarning` for a position whose health
+/// factor is below `warning_threshold_bps` but not yet liquidatable
+///
+/// Uses the same price and staleness bound as `liquidate` so a warned
+/// position is judged the way a liquidator would see it.
+pub fn handler(ctx: Context<EmitLiquidationWarning>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let user_position = &mut ctx.accounts.user_position;
+    let clock = Clock::get()?;
+
+    // Update global interest index
+    config.accrue_interest(clock.unix_timestamp)?;
+
+    // Bring the position's debt up to date
+    let current_debt = user_position.settle_interest(config, clock.unix_timestamp)?;
+    let debt_value = user_position.debt_value(
+        config,
+        current_debt,
+        ctx.remaining_accounts.first(),
+        &clock,
+        config.liquidation_max_staleness_slots
+    )?;
+
+    config.check_oracle(&user_position.collateral_mint, ctx.accounts.sol_usd_oracle.key)?;
+
+    let sol_price = get_price_with_fallback(
+        &ctx.accounts.sol_usd_oracle.to_account_info(),
+        &clock,
+        config.liquidation_max_staleness_slots,
+        config.expected_quote_decimals(&ctx.accounts.sol_usd_oracle.key())
+    )?
+    .with_ema(config.uses_ema_for(EMA_FOR_LIQUIDATIONS))?
+    .valued_at(config.liquidation_valuation_mode);
+
+    let health_factor = user_position.calculate_health_factor(
+        sol_price,
+        config.liquidation_threshold_bps,
+        debt_value
+    )?;
+
+    // Warn only between the liquidation line and the warning threshold
+    let liquidation_cutoff = BPS_PRECISION.saturating_sub(config.liquidation_health_epsilon_bps as u64);
+    require!(
+        health_factor >= liquidation_cutoff && health_factor < config.warning_threshold_bps as u64,
+        crate::errors::CreditError::PositionNotInWarningBand
+    );
+
+    msg!(
+        "Liquidation warning for {} - Health factor: {}, Debt: {}",
+        user_position.owner,
+        health_factor,
+        current_debt
+    );
+
+    emit!(LiquidationWarning {
+        user: user_position.owner,
+        event_seq: user_position.next_event_seq(),
+        webhook_id: user_position.webhook_id,
+        keeper: ctx.accounts.keeper.key(),
+        health_factor,
+        warning_threshold_bps: config.warning_threshold_bps,
+        debt: current_debt,
+        oracle_price: sol_price,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct LiquidationWarning {
+    pub user: Pubkey,
+    pub event_seq: u64,
+    pub webhook_id: [u8; 32],
+    pub keeper: Pubkey,
+    /// Health factor (bps) at the time of the warning
+    pub health_factor: u64,
+    pub warning_threshold_bps: u16,
+    pub debt: u64,
+    /// Oracle price the health factor was computed at (6 decimals)
+    pub oracle_price: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::{Config, UserPosition};
+use crate::utils::get_price_with_fallback;
+
+#[derive(Accounts)]
+pub struct EnforceBuffer<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, user_position.owner.as_ref()],
+        bump,
+        constraint = user_position.is_initialized @ CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Pyth oracle account
+    pub sol_usd_oracle: UncheckedAccount<'info>,
+}
+
+/// Permissionless: flag a position whose health factor has drifted below the
+/// borrow buffer (`min_borrow_health_factor_bps`), or clear the flag once it
+/// is back above it
+///
+/// A flagged position cannot borrow, which gives its owner a warning band
+/// before liquidation. Owners call this themselves to lift the flag after
+/// repaying or depositing. Returns whether the position is flagged.
+pub fn handler(ctx: Context<EnforceBuffer>) -> Result<bool> {
+    let config = &mut ctx.accounts.config;
+    let user_position = &mut ctx.accounts.user_position;
+    let clock = Clock::get()?;
+
+    config.accrue_interest(clock.unix_timestamp)?;
+    user_position.settle_interest(config, clock.unix_timestamp)?;
+    let debt_value = user_position.debt_value(
+        config,
+        user_position.debt_usdc,
+        ctx.remaining_accounts.first(),
+        &clock,
+        config.borrow_max_staleness_slots
+    )?;
+
+    config.check_oracle(&user_position.collateral_mint, ctx.accounts.sol_usd_oracle.key)?;
+
+    // Health is judged as liquidations would judge it
+    let price = get_price_with_fallback(
+        &ctx.accounts.sol_usd_oracle.to_account_info(),
+        &clock,
+        config.borrow_max_staleness_slots,
+        config.expected_quote_decimals(&ctx.accounts.sol_usd_oracle.key())
+    )?
+    .with_ema(config.uses_ema_for(EMA_FOR_LIQUIDATIONS))?
+    .valued_at(config.liquidation_valuation_mode);
+    let health_factor = user_position.calculate_health_factor(
+        price,
+        config.liquidation_threshold_bps,
+        debt_value
+    )?;
+
+    let breached = health_factor < config.min_borrow_health_factor_bps as u64;
+    let changed = breached != user_position.buffer_breached;
+    user_position.buffer_breached = breached;
+    user_position.last_health_factor = health_factor;
+    user_position.last_health_timestamp = clock.unix_timestamp;
+
+    msg!(
+        "Position {} health factor {} vs buffer {}: {}",
+        user_position.owner,
+        health_factor,
+        config.min_borrow_health_factor_bps,
+        if breached { "breached" } else { "clear" }
+    );
+
+    if changed {
+        emit!(BufferStatusChanged {
+            user: user_position.owner,
+            event_seq: user_position.next_event_seq(),
+            webhook_id: user_position.webhook_id,
+            breached,
+            health_factor,
+            buffer_health_factor: config.min_borrow_health_factor_bps as u64,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    Ok(breached)
+}
+
+#[event]
+pub struct BufferStatusChanged {
+    pub user: Pubkey,
+    pub event_seq: u64,
+    pub webhook_id: [u8; 32],
+    pub breached: bool,
+    pub health_factor: u64,
+    pub buffer_health_factor: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::{Config, UserPosition};
+use crate::utils::{get_price_with_fallback, calculate_collateral_value, usdc_to_collateral};
+use super::repay_with_collateral::sell_collateral_to_reserves;
+
+#[derive(Accounts)]
+pub struct ExecuteStopLoss<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, user_position.owner.as_ref()],
+        bump,
+        constraint = user_position.is_initialized @ CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+    
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// Pyth oracle for SOL/USD price
+    /// CHECK: Validated in handler
+    pub sol_usd_oracle: UncheckedAccount<'info>,
+    
+    pub keeper: Signer<'info>,
+}
+
+/// Permissionless: once a position's health factor falls below its stop-loss,
+/// sell just enough collateral to reserves at the oracle price to bring it back
+///
+/// Health is judged the way `liquidate` judges it, and the sale goes through
+/// the same reserves path as `repay_with_collateral`.
+pub fn handler(ctx: Context<ExecuteStopLoss>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let user_position = &mut ctx.accounts.user_position;
+    let clock = Clock::get()?;
+    
+    require!(!config.repays_paused(), CreditError::ProtocolPaused);
+    require!(user_position.owes_usdc(), CreditError::DebtMintMismatch);
+    let stop_loss_bps = user_position.stop_loss_health_bps as u64;
+    require!(stop_loss_bps > 0, CreditError::StopLossNotTriggered);
+    
+    config.accrue_interest(clock.unix_timestamp)?;
+    let current_debt = user_position.settle_interest(config, clock.unix_timestamp)?;
+    
+    config.check_oracle(&user_position.collateral_mint, ctx.accounts.sol_usd_oracle.key)?;
+    
+    let price = get_price_with_fallback(
+        &ctx.accounts.sol_usd_oracle.to_account_info(),
+        &clock,
+        config.liquidation_max_staleness_slots,
+        config.expected_quote_decimals(&ctx.accounts.sol_usd_oracle.key())
+    )?
+    .with_ema(config.uses_ema_for(EMA_FOR_LIQUIDATIONS))?
+    .valued_at(config.liquidation_valuation_mode);
+    
+    let health_factor = user_position.calculate_health_factor(
+        price,
+        config.liquidation_threshold_bps,
+        current_debt
+    )?;
+    require!(health_factor < stop_loss_bps, CreditError::StopLossNotTriggered);
+    
+    // Selling x of value leaves (V - x) * T = H * (D - x), so x = (H * D - V * T) / (H - T)
+    let collateral_value = calculate_collateral_value(user_position.collateral_amount, price)? as u128;
+    let threshold = config.liquidation_threshold_bps as u128;
+    let shortfall = (stop_loss_bps as u128)
+        .checked_mul(current_debt as u128)
+        .and_then(|debt| debt.checked_sub(collateral_value.checked_mul(threshold)?))
+        .ok_or(CreditError::MathOverflow)?;
+    let gain_per_sale = stop_loss_bps as u128 - threshold;
+    let sell_value = shortfall
+        .checked_add(gain_per_sale - 1)
+        .ok_or(CreditError::MathOverflow)?
+        / gain_per_sale;
+    let collateral_amount = usdc_to_collateral(sell_value.min(u64::MAX as u128) as u64, price)?
+        .min(user_position.collateral_amount);
+    
+    let (repay_amount, collateral_sold) = sell_collateral_to_reserves(
+        config,
+        user_position,
+        current_debt,
+        collateral_amount,
+        price,
+        &clock
+    )?;
+    let new_health_factor = user_position.calculate_health_factor(
+        price,
+        config.liquidation_threshold_bps,
+        user_position.debt_usdc
+    )?;
+    user_position.last_health_factor = new_health_factor;
+    user_position.last_health_timestamp = clock.unix_timestamp;
+    
+    msg!(
+        "Stop-loss for {}: sold {} collateral for {} USDC, health factor {} -> {}",
+        user_position.owner,
+        collateral_sold,
+        repay_amount,
+        health_factor,
+        new_health_factor
+    );
+    
+    emit!(StopLossExecuted {
+        user: user_position.owner,
+        event_seq: user_position.next_event_seq(),
+        webhook_id: user_position.webhook_id,
+        keeper: ctx.accounts.keeper.key(),
+        repay_amount,
+        collateral_sold,
+        oracle_price: price,
+        health_factor_before: health_factor,
+        health_factor_after: new_health_factor,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct StopLossExecuted {
+    pub user: Pubkey,
+    pub event_seq: u64,
+    pub webhook_id: [u8; 32],
+    pub keeper: Pubkey,
+    pub repay_amount: u64,
+    pub collateral_sold: u64,
+    /// Price the collateral was sold to reserves at (6 decimals)
+    pub oracle_price: u64,
+    pub health_factor_before: u64,
+    pub health_factor_after: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::constants::*;
+use crate::state::Config;
+
+/// Stable, versioned snapshot of the protocol config for clients
+///
+/// New fields are only ever appended, with `version` bumped, so clients
+/// don't depend on the raw `Config` account layout.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ConfigView {
+    pub version: u8,
+    pub admin: Pubkey,
+    pub paused: bool,
+    pub pause_flags: u8,
+    pub ltv_max_bps: u16,
+    pub liquidation_threshold_bps: u16,
+    pub liquidation_bonus_bps: u16,
+    pub min_borrow_health_factor_bps: u16,
+    pub usdc_mint: Pubkey,
+    pub wsol_mint: Pubkey,
+    pub jito_sol_mint: Pubkey,
+    pub sol_usd_oracle: Pubkey,
+    pub total_debt_usdc: u64,
+    pub total_collateral: u64,
+    pub total_reserves_usdc: u64,
+    pub total_supplied_usdc: u64,
+    /// Annual borrow rate currently charged (basis points)
+    pub current_borrow_rate_bps: u16,
+    /// Debt as a share of debt plus treasury liquidity (basis points)
+    pub utilization_bps: u16,
+    /// Global borrow index accrued to the current timestamp (Ray precision)
+    pub borrow_index_now: u128,
+    pub timestamp: i64,
+    /// Instruction layout version this program build speaks
+    pub protocol_version: u8,
+    /// Lowest client version accepted by borrow/deposit instructions (0 = unchecked)
+    pub min_client_version: u8,
+}
+
+#[derive(Accounts)]
+pub struct GetConfig<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Treasury USDC vault, for utilization
+    #[account(
+        seeds = [VAULT_SEED, config.usdc_mint.as_ref()],
+        bump,
+        token::mint = config.usdc_mint
+    )]
+    pub treasury_usdc_account: Account<'info, TokenAccount>,
+}
+
+/// Read-only: return the config with computed fields as a `ConfigView`
+pub fn handler(ctx: Context<GetConfig>) -> Result<ConfigView> {
+    let config = &ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    // Project the index to now without writing the account
+    let mut accrued = (**config).clone();
+    accrued.accrue_interest(clock.unix_timestamp)?;
+
+    let liquidity = (config.total_debt_usdc as u128)
+        .checked_add(ctx.accounts.treasury_usdc_account.amount as u128)
+        .ok_or(crate::errors::CreditError::MathOverflow)?;
+    let utilization_bps = if liquidity == 0 {
+        0
+    } else {
+        (config.total_debt_usdc as u128)
+            .checked_mul(BPS_PRECISION as u128)
+            .ok_or(crate::errors::CreditError::MathOverflow)?
+            .checked_div(liquidity)
+            .ok_or(crate::errors::CreditError::MathOverflow)? as u16
+    };
+
+    Ok(ConfigView {
+        version: CONFIG_VIEW_VERSION,
+        admin: config.admin,
+        paused: config.paused,
+        pause_flags: config.pause_flags,
+        ltv_max_bps: config.ltv_max_bps,
+        liquidation_threshold_bps: config.liquidation_threshold_bps,
+        liquidation_bonus_bps: config.liquidation_bonus_bps,
+        min_borrow_health_factor_bps: config.min_borrow_health_factor_bps,
+        usdc_mint: config.usdc_mint,
+        wsol_mint: config.wsol_mint,
+        jito_sol_mint: config.jito_sol_mint,
+        sol_usd_oracle: config.sol_usd_oracle,
+        total_debt_usdc: config.total_debt_usdc,
+        total_collateral: config.total_collateral,
+        total_reserves_usdc: config.total_reserves_usdc,
+        total_supplied_usdc: config.total_supplied_usdc,
+        current_borrow_rate_bps: config.interest_rate_bps,
+        utilization_bps,
+        borrow_index_now: accrued.global_borrow_index,
+        timestamp: clock.unix_timestamp,
+        protocol_version: PROTOCOL_VERSION,
+        min_client_version: config.min_client_version,
+    })
+}
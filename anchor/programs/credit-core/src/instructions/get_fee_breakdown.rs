@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::state::Config;
+
+/// Lifetime protocol revenue by source, alongside the current USDC reserves
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct FeeBreakdown {
+    pub prepayment_fees_usdc: u64,
+    pub liquidation_fees_usdc: u64,
+    pub total_reserves_usdc: u64,
+}
+
+#[derive(Accounts)]
+pub struct GetFeeBreakdown<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// View: protocol fees collected so far, split by the action that generated them
+///
+/// The buckets only ever grow; `total_reserves_usdc` is what the reserves hold
+/// now, after subsidies, backstop liquidations and deployments to supply.
+pub fn handler(ctx: Context<GetFeeBreakdown>) -> Result<FeeBreakdown> {
+    let config = &ctx.accounts.config;
+
+    Ok(FeeBreakdown {
+        prepayment_fees_usdc: config.prepayment_fees_usdc,
+        liquidation_fees_usdc: config.liquidation_fees_usdc,
+        total_reserves_usdc: config.total_reserves_usdc,
+    })
+}
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, UserPosition};
+use crate::constants::*;
+
+#[derive(Accounts)]
+pub struct GetLiquidationPrice<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, user_position.owner.as_ref()],
+        bump,
+        constraint = user_position.is_initialized @ crate::errors::CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Permissionless: the collateral price (6 decimals) at which the position's
+/// health factor falls to 1.0, given its current debt and collateral
+///
+/// Rounded up, so the returned price is the lowest one that still keeps the
+/// health factor at 1.0. Returns 0 when there is no debt (no liquidation price)
+/// and u64::MAX when debt is backed by no collateral. Debt owed in another
+/// asset is valued with that asset's feed as the first remaining account.
+pub fn handler(ctx: Context<GetLiquidationPrice>) -> Result<u64> {
+    let config = &mut ctx.accounts.config;
+    let user_position = &mut ctx.accounts.user_position;
+    let clock = Clock::get()?;
+
+    // Update global interest index and bring the position's debt up to date
+    config.accrue_interest(clock.unix_timestamp)?;
+    let current_debt = user_position.settle_interest(config, clock.unix_timestamp)?;
+
+    if current_debt == 0 {
+        msg!("No debt - {} has no liquidation price", user_position.owner);
+        return Ok(0);
+    }
+    if user_position.collateral_amount == 0 {
+        msg!("No collateral - {} is liquidatable at any price", user_position.owner);
+        return Ok(u64::MAX);
+    }
+
+    let debt_value = user_position.debt_value(
+        config,
+        current_debt,
+        ctx.remaining_accounts.first(),
+        &clock,
+        config.borrow_max_staleness_slots
+    )?;
+
+    // Health factor 1.0 means collateral value * threshold == debt value
+    let collateral_value = ceil_div(
+        (debt_value as u128)
+            .checked_mul(BPS_PRECISION as u128)
+            .ok_or(crate::errors::CreditError::MathOverflow)?,
+        config.liquidation_threshold_bps as u128
+    )?;
+    let liquidation_price = ceil_div(
+        collateral_value
+            .checked_mul(1_000_000_000u128) // Price has 6 decimals, collateral 9
+            .ok_or(crate::errors::CreditError::MathOverflow)?,
+        user_position.collateral_amount as u128
+    )?
+    .min(u64::MAX as u128) as u64;
+
+    msg!(
+        "Liquidation price for {}: {} (debt: {}, collateral: {})",
+        user_position.owner,
+        liquidation_price,
+        current_debt,
+        user_position.collateral_amount
+    );
+
+    Ok(liquidation_price)
+}
+
+fn ceil_div(numerator: u128, denominator: u128) -> Result<u128> {
+    require!(denominator > 0, crate::errors::CreditError::MathOverflow);
+    numerator
+        .checked_add(denominator - 1)
+        .map(|n| n / denominator)
+        .ok_or(error!(crate::errors::CreditError::MathOverflow))
+}
@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::state::{Config, UserPosition};
+use crate::constants::*;
+use crate::errors::CreditError;
+use super::deposit_collateral_wsol::record_wsol_deposit;
+use super::init_position::initialize_position;
+
+#[derive(Accounts)]
+pub struct InitAndDeposit<'info> {
+    /// Created (owner pays rent) if the owner has no position yet
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = UserPosition::LEN,
+        seeds = [USER_POSITION_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = user_wsol_account.owner == owner.key() @ CreditError::Unauthorized
+    )]
+    pub user_wsol_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, wsol_mint.key().as_ref()],
+        bump,
+        token::authority = vault_authority,
+        constraint = vault_wsol.delegate.is_none() @ CreditError::VaultDelegateSet
+    )]
+    pub vault_wsol: Account<'info, TokenAccount>,
+
+    /// CHECK: PDA authority for the vaults
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(address = config.wsol_mint @ CreditError::InvalidCollateralMint)]
+    pub wsol_mint: Account<'info, Mint>,
+
+    /// CHECK: Pyth oracle account
+    pub sol_usd_oracle: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Deposit WSOL, opening the owner's position first if it doesn't exist yet
+///
+/// Lets a new user get from wallet to collateralized position in one
+/// transaction; an existing position is deposited into as by
+/// deposit_collateral_wsol.
+pub fn handler(ctx: Context<InitAndDeposit>, amount: u64, client_version: u8) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let user_position = &mut ctx.accounts.user_position;
+
+    require!(!config.is_paused_for(PAUSE_DEPOSITS), CreditError::ProtocolPaused);
+    require!(config.accepts_client_version(client_version), CreditError::ClientVersionTooOld);
+    require!(amount > 0, CreditError::AmountTooSmall);
+
+    if !user_position.is_initialized {
+        initialize_position(user_position, ctx.accounts.owner.key(), config, &Clock::get()?);
+        msg!("User position initialized for: {}", ctx.accounts.owner.key());
+    }
+    require!(
+        user_position.owner == ctx.accounts.owner.key(),
+        CreditError::Unauthorized
+    );
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.user_wsol_account.to_account_info(),
+                to: ctx.accounts.vault_wsol.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            }
+        ),
+        amount
+    )?;
+
+    record_wsol_deposit(
+        config,
+        user_position,
+        ctx.accounts.wsol_mint.key(),
+        &ctx.accounts.sol_usd_oracle.to_account_info(),
+        ctx.remaining_accounts.first(),
+        amount
+    )
+}
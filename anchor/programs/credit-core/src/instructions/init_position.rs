@@ -26,19 +26,26 @@ pub struct InitPosition<'info> {
 }
 
 pub fn handler(ctx: Context<InitPosition>) -> Result<()> {
-    let user_position = &mut ctx.accounts.user_position;
     let config = &ctx.accounts.config;
     let clock = Clock::get()?;
     
     // Check protocol is not paused
     require!(!config.paused, crate::errors::CreditError::ProtocolPaused);
     
-    // Initialize user position
-    user_position.owner = ctx.accounts.owner.key();
-    user_position.collateral_mint = config.jito_sol_mint; // MVP only supports jitoSOL
+    initialize_position(&mut ctx.accounts.user_position, ctx.accounts.owner.key(), config, &clock);
+    
+    msg!("User position initialized for: {}", ctx.accounts.owner.key());
+    
+    Ok(())
+}
+
+/// Set up a freshly allocated position for `owner` (shared with init_and_deposit)
+pub(crate) fn initialize_position(user_position: &mut UserPosition, owner: Pubkey, config: &Config, clock: &Clock) {
+    user_position.owner = owner;
+    user_position.collateral_mint = config.wsol_mint; // Single-pair deposits take WSOL only
     user_position.collateral_amount = 0;
     user_position.debt_usdc = 0;
-    user_position.borrow_index_snapshot = config.global_borrow_index;
+    user_position.borrow_index_snapshot = config.borrow_index_for(&config.wsol_mint);
     user_position.last_update_slot = clock.slot;
     user_position.last_update_timestamp = clock.unix_timestamp;
     user_position.lifetime_borrows = 0;
@@ -46,9 +53,20 @@ pub fn handler(ctx: Context<InitPosition>) -> Result<()> {
     user_position.liquidation_count = 0;
     user_position.is_initialized = true;
     user_position.credit_limit = 0; // Will be set based on collateral deposits
+    user_position.last_health_factor = u64::MAX; // No debt yet
+    user_position.last_health_timestamp = clock.unix_timestamp;
+    user_position.last_interest_timestamp = clock.unix_timestamp;
+    user_position.first_deposit_timestamp = 0;
+    user_position.auto_deleverage_threshold_bps = 0;
+    user_position.deleverage_buffer_usdc = 0;
+    user_position.event_seq = 0;
+    user_position.collaterals = Default::default();
+    user_position.borrow_start_timestamp = 0;
+    user_position.webhook_id = [0; 32];
+    user_position.credit_limit_price = 0;
+    user_position.credit_limit_timestamp = 0;
+    user_position.debt_mint = Pubkey::default();
+    user_position.stop_loss_health_bps = 0;
+    user_position.buffer_breached = false;
     user_position._reserved = [0; 16];
-    
-    msg!("User position initialized for: {}", ctx.accounts.owner.key());
-    
-    Ok(())
-} 
\ No newline at end of file
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::state::Config;
+use crate::constants::*;
+use crate::errors::CreditError;
+
+#[derive(Accounts)]
+pub struct InitVault<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = config.admin == admin.key() @ CreditError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Program vault for the mint (PDA)
+    #[account(
+        init,
+        payer = admin,
+        seeds = [VAULT_SEED, mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault_authority
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    /// CHECK: PDA authority for vault operations
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Collateral, USDC or debt asset mint tracked by the protocol
+    #[account(
+        constraint = mint.key() == config.usdc_mint
+            || mint.key() == config.wsol_mint
+            || mint.key() == config.jito_sol_mint
+            || config.debt_asset(&mint.key()).is_some() @ CreditError::InvalidMint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitVault>) -> Result<()> {
+    msg!("Vault initialized for mint: {}", ctx.accounts.mint.key());
+    msg!("Vault: {}", ctx.accounts.vault.key());
+    msg!("Authority: {}", ctx.accounts.vault_authority.key());
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 use crate::constants::*;
-use crate::state::Config;
+use crate::state::{Config, DebitTierLimits};
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct InitializeParams {
@@ -39,8 +39,24 @@ pub fn handler(ctx: Context<Initialize>, params: InitializeParams) -> Result<()>
     require!(params.liquidation_threshold_bps <= 10000, crate::errors::CreditError::InvalidPercentage);
     require!(params.liquidation_bonus_bps <= 10000, crate::errors::CreditError::InvalidPercentage);
     require!(params.interest_rate_bps <= 10000, crate::errors::CreditError::InvalidPercentage);
+    require!(params.interest_rate_bps <= DEFAULT_MAX_INTEREST_RATE_BPS, crate::errors::CreditError::InterestRateAboveCeiling);
     require!(params.ltv_max_bps < params.liquidation_threshold_bps, crate::errors::CreditError::InvalidPercentage);
     
+    // The native deposit path only works with the canonical wrapped SOL mint
+    require!(
+        params.wsol_mint.to_string() == WSOL_MINT,
+        crate::errors::CreditError::InvalidCollateralMint
+    );
+    
+    // USDC is lent out against the collateral mints, and each collateral mint
+    // gets its own registry entry and vault, so all three must be distinct
+    require!(
+        params.usdc_mint != params.jito_sol_mint
+            && params.usdc_mint != params.wsol_mint
+            && params.jito_sol_mint != params.wsol_mint,
+        crate::errors::CreditError::DuplicateMint
+    );
+    
     // Initialize config
     config.admin = ctx.accounts.admin.key();
     config.paused = false;
@@ -56,6 +72,73 @@ pub fn handler(ctx: Context<Initialize>, params: InitializeParams) -> Result<()>
     config.last_update_timestamp = Clock::get()?.unix_timestamp;
     config.total_debt_usdc = 0;
     config.total_collateral = 0;
+    config.borrow_max_staleness_slots = DEFAULT_BORROW_MAX_STALENESS_SLOTS;
+    config.liquidation_max_staleness_slots = DEFAULT_LIQUIDATION_MAX_STALENESS_SLOTS;
+    config.max_confidence_bps = MAX_CONFIDENCE_DEVIATION_BPS;
+    config.max_interest_rate_bps = DEFAULT_MAX_INTEREST_RATE_BPS;
+    config.pending_max_interest_rate_bps = 0;
+    config.max_interest_rate_unlock_timestamp = 0;
+    config.pause_flags = 0;
+    config.min_residual_collateral = DEFAULT_MIN_RESIDUAL_COLLATERAL;
+    config.min_accrual_interval_secs = DEFAULT_MIN_ACCRUAL_INTERVAL_SECS;
+    config.liquidation_health_epsilon_bps = DEFAULT_LIQUIDATION_HEALTH_EPSILON_BPS;
+    config.total_bad_debt_usdc = 0;
+    config.collateral_types = Default::default();
+    config.register_collateral_type(params.wsol_mint)?.oracle = params.sol_usd_oracle;
+    config.register_collateral_type(params.jito_sol_mint)?.oracle = params.jito_sol_usd_oracle;
+    config.previous_jito_sol_mint = Pubkey::default();
+    config.total_reserves_usdc = 0;
+    config.min_position_age_secs = 0;
+    config.total_supplied_usdc = 0;
+    config.min_reserve_ratio_bps = DEFAULT_MIN_RESERVE_RATIO_BPS;
+    config.max_total_collateral_value_usdc = 0;
+    config.min_borrow_health_factor_bps = HEALTH_FACTOR_BUFFER_BPS;
+    config.tier_config = Default::default();
+    config.tier_config[DEBIT_TIER_BASIC as usize] = DebitTierLimits {
+        daily_limit: BASIC_DAILY_LIMIT,
+        monthly_limit: BASIC_MONTHLY_LIMIT,
+        per_tx_limit: BASIC_PER_TX_LIMIT,
+    };
+    config.tier_config[DEBIT_TIER_PREMIUM as usize] = DebitTierLimits {
+        daily_limit: PREMIUM_DAILY_LIMIT,
+        monthly_limit: PREMIUM_MONTHLY_LIMIT,
+        per_tx_limit: PREMIUM_PER_TX_LIMIT,
+    };
+    config.warning_threshold_bps = DEFAULT_WARNING_THRESHOLD_BPS;
+    config.protocol_liquidator = Pubkey::default();
+    config.prepayment_fee_bps = 0;
+    config.min_loan_term_secs = 0;
+    config.max_liquidation_collateral_fraction_bps = DEFAULT_MAX_LIQUIDATION_COLLATERAL_FRACTION_BPS;
+    config.pause_interest_accrual = true;
+    config.paused_since = 0;
+    config.total_paused_secs = 0;
+    config.credit_limit_buffer_bps = 0;
+    config.min_debt_after_liquidation = 0;
+    config.min_client_version = 0;
+    config.oracle_decimals = Default::default();
+    config.oracle_health_window_slots = 0;
+    config.protocol_owners = [Pubkey::default(); MAX_PROTOCOL_OWNERS];
+    config.ema_price_flags = 0;
+    config.debit_dust_threshold = DEFAULT_DEBIT_DUST_THRESHOLD;
+    config.max_active_reservations = DEFAULT_MAX_ACTIVE_RESERVATIONS;
+    config.pause_blocks_repays = false;
+    config.borrow_valuation_mode = VALUATION_MID;
+    config.liquidation_valuation_mode = VALUATION_MID;
+    config.max_borrow_index_age_secs = 0;
+    config.max_borrow_liquidity_share_bps = 0;
+    config.credit_limit_refresh_interval_secs = 0;
+    config.credit_limit_refresh_price_bps = 0;
+    config.debt_assets = Default::default();
+    config.max_single_position_debt_usdc = 0;
+    config.full_liquidation_rebate_bps = 0;
+    config.params_timelock_slots = 0;
+    config.max_index_growth_wad = 0;
+    config.shutdown = false;
+    config.enforce_configured_oracles = true;
+    config.prepayment_fees_usdc = 0;
+    config.liquidation_fees_usdc = 0;
+    config.total_deleverage_buffers_usdc = 0;
+    config.total_debit_balances_usdc = 0;
     config._reserved = [0; 16];
     
     msg!("Protocol initialized with admin: {}", ctx.accounts.admin.key());
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::{DebitAccount, UserPosition};
+
+#[derive(Accounts)]
+pub struct LinkCreditPosition<'info> {
+    #[account(
+        mut,
+        seeds = [DEBIT_ACCOUNT_SEED, owner.key().as_ref()],
+        bump,
+        constraint = debit_account.owner == owner.key() @ CreditError::Unauthorized,
+        constraint = debit_account.is_initialized @ CreditError::AccountNotActive
+    )]
+    pub debit_account: Account<'info, DebitAccount>,
+    
+    #[account(
+        seeds = [USER_POSITION_SEED, owner.key().as_ref()],
+        bump,
+        constraint = user_position.owner == owner.key() @ CreditError::Unauthorized,
+        constraint = user_position.is_initialized @ CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+    
+    pub owner: Signer<'info>,
+}
+
+/// Link (or unlink) the owner's credit position to their debit account
+///
+/// While linked, a card spend the debit balance can't cover is held against
+/// the position's credit line instead of being declined. Unlinking waits for
+/// every credit-backed authorization to settle.
+pub fn handler(ctx: Context<LinkCreditPosition>, linked: bool) -> Result<()> {
+    let debit_account = &mut ctx.accounts.debit_account;
+    
+    if linked {
+        debit_account.linked_position = ctx.accounts.user_position.key();
+    } else {
+        require!(debit_account.credit_reserved == 0, CreditError::CreditHoldsOutstanding);
+        debit_account.linked_position = Pubkey::default();
+    }
+    
+    msg!("Credit position {} for debit account of {}", if linked { "linked" } else { "unlinked" }, debit_account.owner);
+    
+    Ok(())
+}
@@ -0,0 +1,518 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::constants::*;
+use crate::state::{Config, UserPosition};
+use crate::utils::{
+    get_price_with_fallback, calculate_collateral_value, calculate_liquidation_bonus, usdc_to_collateral,
+};
+
+#[derive(Accounts)]
+pub struct Liquidate<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, user_being_liquidated.key().as_ref()],
+        bump,
+        constraint = user_position.is_initialized @ crate::errors::CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+    
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// The user being liquidated
+    /// CHECK: Validated against position owner
+    pub user_being_liquidated: UncheckedAccount<'info>,
+    
+    /// Liquidator's token account for the position's debt asset (USDC unless it borrowed another)
+    #[account(
+        mut,
+        constraint = liquidator_usdc_account.owner == liquidator.key() @ crate::errors::CreditError::Unauthorized,
+        constraint = liquidator_usdc_account.mint == *user_position.owed_mint(&config.usdc_mint) @ crate::errors::CreditError::InvalidCollateralMint
+    )]
+    pub liquidator_usdc_account: Account<'info, TokenAccount>,
+    
+    /// Liquidator's collateral token account (to receive seized collateral)
+    #[account(
+        mut,
+        constraint = liquidator_collateral_account.key() != vault_collateral_account.key() @ crate::errors::CreditError::ReceivingAccountIsVault,
+        constraint = liquidator_collateral_account.owner == liquidator.key() @ crate::errors::CreditError::Unauthorized,
+        constraint = liquidator_collateral_account.mint == user_position.collateral_mint @ crate::errors::CreditError::InvalidCollateralMint
+    )]
+    pub liquidator_collateral_account: Account<'info, TokenAccount>,
+    
+    /// Program's treasury for the position's debt asset
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user_position.owed_mint(&config.usdc_mint).as_ref()],
+        bump,
+        token::mint = user_position.owed_mint(&config.usdc_mint),
+        token::authority = vault_authority,
+        constraint = treasury_usdc_account.delegate.is_none() @ crate::errors::CreditError::VaultDelegateSet
+    )]
+    pub treasury_usdc_account: Account<'info, TokenAccount>,
+    
+    /// Program's vault for the position's collateral mint
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user_position.collateral_mint.as_ref()],
+        bump,
+        token::mint = user_position.collateral_mint,
+        token::authority = vault_authority,
+        constraint = vault_collateral_account.delegate.is_none() @ crate::errors::CreditError::VaultDelegateSet
+    )]
+    pub vault_collateral_account: Account<'info, TokenAccount>,
+    
+    /// PDA authority for the vault
+    /// CHECK: This is the PDA that has authority over the vault
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    /// Pyth oracle for SOL/USD price
+    /// CHECK: Validated in handler
+    pub sol_usd_oracle: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub liquidator: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// How much debt a liquidation should repay
+pub(crate) enum LiquidationSize {
+    /// Repay up to this amount of USDC
+    Amount(u64),
+    /// Repay exactly what brings the health factor (bps) to this target
+    TargetHealth(u64),
+}
+
+pub fn handler(ctx: Context<Liquidate>, repay_amount: u64) -> Result<()> {
+    execute(ctx, LiquidationSize::Amount(repay_amount))
+}
+
+pub(crate) fn execute(ctx: Context<Liquidate>, size: LiquidationSize) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let user_position = &mut ctx.accounts.user_position;
+    let clock = Clock::get()?;
+    
+    // Check liquidations are not paused
+    require!(!config.is_paused_for(PAUSE_LIQUIDATIONS), crate::errors::CreditError::ProtocolPaused);
+    
+    // Verify user being liquidated matches position
+    require!(
+        ctx.accounts.user_being_liquidated.key() == user_position.owner,
+        crate::errors::CreditError::Unauthorized
+    );
+    require!(
+        config.may_liquidate(&user_position.owner, &ctx.accounts.liquidator.key()),
+        crate::errors::CreditError::ProtocolPositionRestricted
+    );
+    
+    // Only the legacy collateral pair is valued and seized here; slot
+    // collateral is priced and seized slot by slot in liquidate_multi
+    require!(
+        !user_position.has_collateral_entries(),
+        crate::errors::CreditError::SlotCollateralUnsupported
+    );
+    
+    // Validate repay amount
+    if let LiquidationSize::Amount(repay_amount) = size {
+        require!(repay_amount > 0, crate::errors::CreditError::AmountTooSmall);
+    }
+    
+    // Update global interest index
+    config.accrue_interest(clock.unix_timestamp)?;
+    
+    // Calculate current debt with interest; everything below is sized from this
+    // settled debt, so an owner repay that landed first shrinks the liquidation
+    let current_debt = user_position.settle_interest(config, clock.unix_timestamp)?;
+    // A debt-free position is healthy whatever its collateral is worth
+    require!(current_debt > 0, crate::errors::CreditError::PositionHealthy);
+    
+    config.check_oracle(&user_position.collateral_mint, ctx.accounts.sol_usd_oracle.key)?;
+    
+    // Get current price (tighter staleness bound than borrows)
+    let oracle_price = get_price_with_fallback(
+        &ctx.accounts.sol_usd_oracle.to_account_info(),
+        &clock,
+        config.liquidation_max_staleness_slots,
+        config.expected_quote_decimals(&ctx.accounts.sol_usd_oracle.key())
+    )?
+    .with_ema(config.uses_ema_for(EMA_FOR_LIQUIDATIONS))?;
+    let sol_price = oracle_price.price;
+    let valuation_price = oracle_price.valued_at(config.liquidation_valuation_mode);
+    
+    // Debt owed in another asset is sized by its USDC value and repaid in the
+    // asset (its feed is the first remaining account when oracle-priced)
+    let debt_valuation = config.debt_valuation(
+        &user_position.debt_mint,
+        ctx.remaining_accounts.first(),
+        &clock,
+        config.liquidation_max_staleness_slots
+    )?;
+    let debt_value = debt_valuation.value(current_debt)?;
+    
+    // Check if position is unhealthy (can be liquidated), at the configured
+    // point of the confidence band; seizure is still sized at the price
+    let health_factor = user_position.calculate_health_factor(
+        valuation_price,
+        config.liquidation_threshold_bps,
+        debt_value
+    )?;
+    
+    // Health factor must be below 1.0 by more than the rounding allowance
+    let liquidation_cutoff = BPS_PRECISION.saturating_sub(config.liquidation_health_epsilon_bps as u64);
+    require!(
+        health_factor < liquidation_cutoff,
+        crate::errors::CreditError::PositionHealthy
+    );
+    
+    // The collateral type's bonus override, if any, prices every seizure below
+    let liquidation_bonus_bps = config.liquidation_bonus_bps_for(&user_position.collateral_mint);
+    
+    let repay_value = match size {
+        LiquidationSize::Amount(repay_amount) => debt_valuation.value(repay_amount)?,
+        LiquidationSize::TargetHealth(target_health_bps) => {
+            require!(
+                target_health_bps > health_factor,
+                crate::errors::CreditError::InvalidParameter
+            );
+            repay_for_target_health(
+                user_position.collateral_amount,
+                debt_value,
+                valuation_price,
+                sol_price,
+                config.liquidation_threshold_bps,
+                liquidation_bonus_bps,
+                target_health_bps
+            )?
+        }
+    };
+    
+    // Calculate maximum liquidation amount (can liquidate up to 50% of debt in one go)
+    let max_liquidation = debt_value / 2;
+    
+    // Also cap by the share of collateral one liquidation may seize
+    let max_seizable_collateral = (user_position.collateral_amount as u128)
+        .checked_mul(config.max_liquidation_collateral_fraction_bps as u128)
+        .ok_or(crate::errors::CreditError::MathOverflow)?
+        .checked_div(BPS_PRECISION as u128)
+        .ok_or(crate::errors::CreditError::MathOverflow)? as u64;
+    let max_collateral_liquidation = repay_covered_by(
+        max_seizable_collateral,
+        liquidation_bonus_bps,
+        sol_price
+    )?;
+    
+    let mut actual_repay_value = repay_value
+        .min(max_liquidation)
+        .min(max_collateral_liquidation)
+        .min(debt_value);
+    
+    // Calculate collateral to seize (repay amount + bonus)
+    let mut actual_collateral_seized = collateral_for_repay(
+        actual_repay_value,
+        liquidation_bonus_bps,
+        sol_price
+    )?;
+    
+    // If the position can't cover the repay plus bonus, the liquidator only
+    // repays what the collateral they actually receive is worth (net of bonus)
+    if actual_collateral_seized > user_position.collateral_amount {
+        let max_repay_covered = repay_covered_by(
+            user_position.collateral_amount,
+            liquidation_bonus_bps,
+            sol_price
+        )?;
+        actual_repay_value = actual_repay_value.min(max_repay_covered);
+        actual_collateral_seized = user_position.collateral_amount;
+    }
+    
+    // A partial liquidation must not strand dust collateral or dust debt,
+    // so upgrade it to a full liquidation (up to available collateral)
+    let residual_collateral = user_position.collateral_amount - actual_collateral_seized;
+    let residual_debt = debt_value - actual_repay_value;
+    let full_liquidation = residual_debt > 0
+        && (residual_collateral < config.min_residual_collateral
+            || residual_debt < config.min_debt_after_liquidation);
+    if full_liquidation {
+        let max_repay_covered = repay_covered_by(
+            user_position.collateral_amount,
+            liquidation_bonus_bps,
+            sol_price
+        )?;
+    
+        actual_repay_value = debt_value.min(max_repay_covered);
+        actual_collateral_seized = if max_repay_covered < debt_value {
+            // Underwater: take everything so no rounding dust is left behind
+            user_position.collateral_amount
+        } else {
+            collateral_for_repay(
+                actual_repay_value,
+                liquidation_bonus_bps,
+                sol_price
+            )?.min(user_position.collateral_amount)
+        };
+    
+        msg!(
+            "Residual collateral {} or debt {} below minimum, fully liquidating",
+            residual_collateral,
+            residual_debt
+        );
+    }
+    
+    // Back to the debt asset's units; repaying the whole value clears the whole debt
+    let actual_repay_amount = if actual_repay_value >= debt_value {
+        current_debt
+    } else {
+        debt_valuation.amount_for(actual_repay_value)?.min(current_debt)
+    };
+    
+    // Split the bonus: the protocol's share stays in the vault as reserves
+    let protocol_share_bps = config
+        .collateral_type(&user_position.collateral_mint)
+        .ok_or(crate::errors::CreditError::InvalidCollateralMint)?
+        .protocol_liquidation_share_bps;
+    let bonus_amount = calculate_liquidation_bonus(actual_repay_value, liquidation_bonus_bps)?;
+    let protocol_bonus = (bonus_amount as u128)
+        .checked_mul(protocol_share_bps as u128)
+        .ok_or(crate::errors::CreditError::MathOverflow)?
+        .checked_div(BPS_PRECISION as u128)
+        .ok_or(crate::errors::CreditError::MathOverflow)? as u64;
+    let mut protocol_collateral = usdc_to_collateral(protocol_bonus, sol_price)?.min(actual_collateral_seized);
+    
+    // The protocol backstop repays out of reserves (already in the treasury)
+    // and keeps all seized collateral in the vault as reserve collateral
+    let from_reserves = config.is_protocol_liquidator(&ctx.accounts.liquidator.key());
+    if from_reserves {
+        // Reserves are USDC, so they can only repay USDC debt
+        require!(user_position.owes_usdc(), crate::errors::CreditError::DebtMintMismatch);
+        require!(
+            config.total_reserves_usdc >= actual_repay_amount,
+            crate::errors::CreditError::InsufficientReserves
+        );
+        config.total_reserves_usdc -= actual_repay_amount;
+        protocol_collateral = actual_collateral_seized;
+    } else {
+        // Transfer the debt asset from liquidator to treasury
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.liquidator_usdc_account.to_account_info(),
+            to: ctx.accounts.treasury_usdc_account.to_account_info(),
+            authority: ctx.accounts.liquidator.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token::transfer(cpi_ctx, actual_repay_amount)?;
+    }
+    let liquidator_collateral = actual_collateral_seized - protocol_collateral;
+    
+    let vault_authority_bump = ctx.bumps.vault_authority;
+    let vault_authority_seeds = &[VAULT_AUTHORITY_SEED, &[vault_authority_bump]];
+    let signer_seeds = &[&vault_authority_seeds[..]];
+    
+    // Clearing a whole USDC debt earns a rebate out of reserves, so small
+    // positions are worth liquidating fully; paid only while reserves cover it
+    let mut rebate_amount = 0;
+    if !from_reserves && actual_repay_amount == current_debt && user_position.owes_usdc() {
+        rebate_amount = (actual_repay_amount as u128)
+            .checked_mul(config.full_liquidation_rebate_bps as u128)
+            .ok_or(crate::errors::CreditError::MathOverflow)?
+            .checked_div(BPS_PRECISION as u128)
+            .ok_or(crate::errors::CreditError::MathOverflow)? as u64;
+        if rebate_amount > config.total_reserves_usdc {
+            msg!("Reserves {} cannot cover the full liquidation rebate", config.total_reserves_usdc);
+            rebate_amount = 0;
+        }
+    }
+    if rebate_amount > 0 {
+        config.total_reserves_usdc -= rebate_amount;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.treasury_usdc_account.to_account_info(),
+            to: ctx.accounts.liquidator_usdc_account.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        token::transfer(cpi_ctx, rebate_amount)?;
+    }
+    
+    // Transfer collateral from vault to liquidator
+    if liquidator_collateral > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault_collateral_account.to_account_info(),
+            to: ctx.accounts.liquidator_collateral_account.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        token::transfer(cpi_ctx, liquidator_collateral)?;
+    }
+    
+    // Update user position
+    let new_debt = current_debt
+        .checked_sub(actual_repay_amount)
+        .ok_or(crate::errors::CreditError::MathOverflow)?;
+    let new_collateral = user_position.collateral_amount
+        .checked_sub(actual_collateral_seized)
+        .ok_or(crate::errors::CreditError::MathOverflow)?;
+    
+    user_position.debt_usdc = new_debt;
+    user_position.collateral_amount = new_collateral;
+    user_position.borrow_index_snapshot = config.borrow_index_for(&user_position.collateral_mint);
+    user_position.liquidation_count += 1;
+    user_position.last_update_slot = clock.slot;
+    user_position.last_update_timestamp = clock.unix_timestamp;
+    
+    // Update global tracking
+    let total_debt = config.debt_total_mut(&user_position.debt_mint)?;
+    *total_debt = total_debt.saturating_sub(actual_repay_amount);
+    config.total_collateral = config.total_collateral
+        .checked_sub(actual_collateral_seized)
+        .ok_or(crate::errors::CreditError::MathOverflow)?;
+    if protocol_collateral > 0 {
+        let collateral = config
+            .collateral_type_mut(&user_position.collateral_mint)
+            .ok_or(crate::errors::CreditError::InvalidCollateralMint)?;
+        collateral.reserve_collateral = collateral.reserve_collateral
+            .checked_add(protocol_collateral)
+            .ok_or(crate::errors::CreditError::MathOverflow)?;
+    }
+    // A backstop liquidation buys the collateral out of reserves; only the
+    // bonus share taken from a liquidator is revenue
+    if !from_reserves {
+        config.liquidation_fees_usdc = config.liquidation_fees_usdc
+            .checked_add(calculate_collateral_value(protocol_collateral, sol_price)?)
+            .ok_or(crate::errors::CreditError::MathOverflow)?;
+    }
+    
+    msg!("Liquidated position of user: {}", user_position.owner);
+    msg!("Repaid: {} debt, Seized: {} collateral ({} to protocol reserves)", actual_repay_amount, actual_collateral_seized, protocol_collateral);
+    msg!("Remaining debt: {} USDC, Remaining collateral: {}", new_debt, new_collateral);
+    
+    // Emit event for indexers
+    emit!(PositionLiquidated {
+        user: user_position.owner,
+        event_seq: user_position.next_event_seq(),
+        webhook_id: user_position.webhook_id,
+        liquidator: ctx.accounts.liquidator.key(),
+        repay_amount: actual_repay_amount,
+        collateral_seized: actual_collateral_seized,
+        protocol_collateral,
+        remaining_debt: new_debt,
+        remaining_collateral: new_collateral,
+        full_liquidation,
+        from_reserves,
+        rebate_amount,
+        oracle_price: sol_price,
+        health_factor_at_liquidation: health_factor,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    #[cfg(feature = "verbose-state")]
+    user_position.log_state(Some(sol_price), config.liquidation_threshold_bps);
+    
+    Ok(())
+}
+
+/// Collateral owed to the liquidator for a repayment (repay amount + bonus)
+fn collateral_for_repay(repay_amount: u64, liquidation_bonus_bps: u16, price: u64) -> Result<u64> {
+    let bonus_amount = calculate_liquidation_bonus(repay_amount, liquidation_bonus_bps)?;
+    let total_value_to_seize = repay_amount
+        .checked_add(bonus_amount)
+        .ok_or(crate::errors::CreditError::MathOverflow)?;
+    
+    usdc_to_collateral(total_value_to_seize, price)
+}
+
+/// Largest repayment whose value plus bonus is covered by `collateral_amount`
+fn repay_covered_by(collateral_amount: u64, liquidation_bonus_bps: u16, price: u64) -> Result<u64> {
+    let collateral_value = calculate_collateral_value(collateral_amount, price)?;
+    let repay = (collateral_value as u128)
+        .checked_mul(BPS_PRECISION as u128)
+        .ok_or(crate::errors::CreditError::MathOverflow)?
+        .checked_div(BPS_PRECISION as u128 + liquidation_bonus_bps as u128)
+        .ok_or(crate::errors::CreditError::MathOverflow)?;
+    
+    Ok(repay as u64)
+}
+
+/// Smallest repayment that lifts the health factor to `target_health_bps`
+///
+/// Repaying `R` removes `R` of debt and `R * (1 + bonus)` of collateral value
+/// (seized at `seize_price`, valued at `valuation_price`), so solving
+/// `(V - R * (1 + b) * Pv / Ps) * T = H * (D - R)` for `R` gives
+/// `R = (H * D - V * T) / (H - (1 + b) * T * Pv / Ps)`, rounded up. Errors if the
+/// bonus is large enough that seizing collateral can't raise the health factor.
+fn repay_for_target_health(
+    collateral_amount: u64,
+    current_debt: u64,
+    valuation_price: u64,
+    seize_price: u64,
+    liquidation_threshold_bps: u16,
+    liquidation_bonus_bps: u16,
+    target_health_bps: u64,
+) -> Result<u64> {
+    let collateral_value = calculate_collateral_value(collateral_amount, valuation_price)? as u128;
+    let target = target_health_bps as u128;
+    let threshold = liquidation_threshold_bps as u128;
+    
+    // Both sides scaled by seize_price * BPS_PRECISION to stay in integers
+    let shortfall = target
+        .checked_mul(current_debt as u128)
+        .and_then(|debt| debt.checked_sub(collateral_value.checked_mul(threshold)?))
+        .ok_or(crate::errors::CreditError::MathOverflow)?
+        .checked_mul(seize_price as u128)
+        .and_then(|value| value.checked_mul(BPS_PRECISION as u128))
+        .ok_or(crate::errors::CreditError::MathOverflow)?;
+    let gain_per_repay = target
+        .checked_mul(seize_price as u128)
+        .and_then(|value| value.checked_mul(BPS_PRECISION as u128))
+        .ok_or(crate::errors::CreditError::MathOverflow)?
+        .checked_sub(
+            (BPS_PRECISION as u128 + liquidation_bonus_bps as u128)
+                .checked_mul(threshold)
+                .and_then(|value| value.checked_mul(valuation_price as u128))
+                .ok_or(crate::errors::CreditError::MathOverflow)?
+        )
+        .filter(|gain| *gain > 0)
+        .ok_or(crate::errors::CreditError::TargetHealthUnreachable)?;
+    
+    let repay = shortfall
+        .checked_add(gain_per_repay - 1)
+        .ok_or(crate::errors::CreditError::MathOverflow)?
+        / gain_per_repay;
+    
+    Ok(repay.min(u64::MAX as u128) as u64)
+}
+
+#[event]
+pub struct PositionLiquidated {
+    pub user: Pubkey,
+    pub event_seq: u64,
+    pub webhook_id: [u8; 32],
+    pub liquidator: Pubkey,
+    pub repay_amount: u64,
+    pub collateral_seized: u64,
+    /// Portion of the seized collateral kept by the protocol
+    pub protocol_collateral: u64,
+    pub remaining_debt: u64,
+    pub remaining_collateral: u64,
+    /// Whether a partial liquidation was upgraded to avoid stranding dust collateral or debt
+    pub full_liquidation: bool,
+    /// Whether the protocol backstop repaid out of reserves
+    pub from_reserves: bool,
+    /// USDC paid from reserves to the liquidator for clearing the whole debt
+    pub rebate_amount: u64,
+    /// Oracle price the liquidation was computed at (6 decimals)
+    pub oracle_price: u64,
+    /// Health factor (bps) before the liquidation was applied
+    pub health_factor_at_liquidation: u64,
+    pub timestamp: i64,
+} 
\ No newline at end of file
@@ -0,0 +1,316 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::{Config, UserPosition};
+use crate::utils::{
+    calculate_health_factor, calculate_liquidation_bonus,
+    calculate_token_value, usdc_to_token,
+};
+
+#[derive(Accounts)]
+pub struct LiquidateMulti<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, user_being_liquidated.key().as_ref()],
+        bump,
+        constraint = user_position.is_initialized @ CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+    
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// The user being liquidated
+    /// CHECK: Validated against position owner
+    pub user_being_liquidated: UncheckedAccount<'info>,
+    
+    /// Liquidator's USDC token account
+    #[account(
+        mut,
+        constraint = liquidator_usdc_account.owner == liquidator.key() @ CreditError::Unauthorized,
+        constraint = liquidator_usdc_account.mint == config.usdc_mint @ CreditError::InvalidMint
+    )]
+    pub liquidator_usdc_account: Account<'info, TokenAccount>,
+    
+    /// Program's USDC treasury account
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, config.usdc_mint.as_ref()],
+        bump,
+        token::mint = config.usdc_mint,
+        token::authority = vault_authority,
+        constraint = treasury_usdc_account.delegate.is_none() @ CreditError::VaultDelegateSet
+    )]
+    pub treasury_usdc_account: Account<'info, TokenAccount>,
+    
+    /// PDA authority for the vaults
+    /// CHECK: This is the PDA that has authority over the vaults
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub liquidator: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+/// Liquidate a multi-collateral position, seizing collateral in priority order
+///
+/// `remaining_accounts` holds an `(oracle, vault, liquidator_token_account)`
+/// triple for every occupied slot of `UserPosition::collaterals`, in slot order,
+/// followed by the exchange-rate feed of each held collateral priced through one.
+/// Collateral types with the lowest `liquidation_priority` are seized first.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, LiquidateMulti<'info>>,
+    repay_amount: u64,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let user_position = &mut ctx.accounts.user_position;
+    let clock = Clock::get()?;
+    
+    require!(!config.is_paused_for(PAUSE_LIQUIDATIONS), CreditError::ProtocolPaused);
+    require!(
+        ctx.accounts.user_being_liquidated.key() == user_position.owner,
+        CreditError::Unauthorized
+    );
+    require!(
+        config.may_liquidate(&user_position.owner, &ctx.accounts.liquidator.key()),
+        CreditError::ProtocolPositionRestricted
+    );
+    require!(user_position.owes_usdc(), CreditError::DebtMintMismatch);
+    require!(repay_amount > 0, CreditError::AmountTooSmall);
+    
+    // Only slot collateral is valued and seized here; the legacy pair goes through liquidate
+    require!(user_position.collateral_amount == 0, CreditError::MixedCollateralFormats);
+    
+    let slots: Vec<usize> = (0..MAX_POSITION_COLLATERALS)
+        .filter(|&slot| user_position.collaterals[slot].is_active())
+        .collect();
+    let rate_oracle_count = slots
+        .iter()
+        .filter(|&&slot| {
+            config
+                .collateral_type(&user_position.collaterals[slot].mint)
+                .is_some_and(|collateral_type| collateral_type.has_exchange_rate())
+        })
+        .count();
+    require!(
+        !slots.is_empty() && ctx.remaining_accounts.len() == slots.len() * 3 + rate_oracle_count,
+        CreditError::InvalidParameter
+    );
+    let (slot_accounts, mut rate_oracles) = ctx.remaining_accounts.split_at(slots.len() * 3);
+    
+    config.accrue_interest(clock.unix_timestamp)?;
+    let current_debt = user_position.settle_interest(config, clock.unix_timestamp)?;
+    require!(current_debt > 0, CreditError::PositionHealthy);
+    
+    // Price every held collateral at its registered oracle
+    let mut prices = [0u64; MAX_POSITION_COLLATERALS];
+    let mut priorities = [0u8; MAX_POSITION_COLLATERALS];
+    let mut share_bps = [0u16; MAX_POSITION_COLLATERALS];
+    let mut bonus_bps = [0u16; MAX_POSITION_COLLATERALS];
+    let mut decimals = [0u8; MAX_POSITION_COLLATERALS];
+    let mut collateral_value: u64 = 0;
+    for (slot, accounts) in slots.iter().zip(slot_accounts.chunks(3)) {
+        let entry = user_position.collaterals[*slot];
+        let collateral_type = config
+            .collateral_type(&entry.mint)
+            .ok_or(CreditError::InvalidCollateralMint)?;
+        require_keys_eq!(accounts[0].key(), collateral_type.oracle, CreditError::InvalidOracle);
+        
+        let rate_oracle = if collateral_type.has_exchange_rate() {
+            let (first, rest) = rate_oracles.split_first().ok_or(CreditError::InvalidOracle)?;
+            rate_oracles = rest;
+            Some(first)
+        } else {
+            None
+        };
+        let oracle_price = config.collateral_type_price(
+            collateral_type,
+            &accounts[0],
+            rate_oracle,
+            &clock,
+            config.liquidation_max_staleness_slots
+        )?
+        .with_ema(config.uses_ema_for(EMA_FOR_LIQUIDATIONS))?;
+        prices[*slot] = oracle_price.price;
+        priorities[*slot] = collateral_type.liquidation_priority;
+        share_bps[*slot] = collateral_type.protocol_liquidation_share_bps;
+        bonus_bps[*slot] = collateral_type.effective_liquidation_bonus_bps(config.liquidation_bonus_bps);
+        decimals[*slot] = collateral_type.decimals;
+        // Eligibility uses the configured band point, seizure the price
+        let eligibility_price = oracle_price.valued_at(config.liquidation_valuation_mode);
+        collateral_value = collateral_value
+            .checked_add(calculate_token_value(entry.amount, eligibility_price, decimals[*slot])?)
+            .ok_or(CreditError::MathOverflow)?;
+    }
+    
+    let health_factor = calculate_health_factor(
+        collateral_value,
+        current_debt,
+        config.liquidation_threshold_bps
+    )?;
+    let liquidation_cutoff = BPS_PRECISION.saturating_sub(config.liquidation_health_epsilon_bps as u64);
+    require!(health_factor < liquidation_cutoff, CreditError::PositionHealthy);
+    
+    let mut actual_repay_amount = repay_amount.min(current_debt / 2);
+    
+    // Walk the slots in priority order, slot order breaking ties; each slot
+    // covers repayment plus its own collateral type's bonus
+    let mut order: Vec<usize> = (0..slots.len()).collect();
+    order.sort_by_key(|&k| (priorities[slots[k]], slots[k]));
+    
+    let mut seized = [0u64; MAX_POSITION_COLLATERALS];
+    let mut repay_remaining = actual_repay_amount;
+    for &k in order.iter() {
+        if repay_remaining == 0 {
+            break;
+        }
+        let slot = slots[k];
+        let bonus_amount = calculate_liquidation_bonus(repay_remaining, bonus_bps[slot])?;
+        let value_to_seize = repay_remaining
+            .checked_add(bonus_amount)
+            .ok_or(CreditError::MathOverflow)?;
+        let wanted = usdc_to_token(value_to_seize, prices[slot], decimals[slot])?;
+        let available = user_position.collaterals[slot].amount;
+        if wanted <= available {
+            seized[slot] = wanted;
+            repay_remaining = 0;
+        } else {
+            seized[slot] = available;
+            let repay_covered = (calculate_token_value(available, prices[slot], decimals[slot])? as u128)
+                .checked_mul(BPS_PRECISION as u128)
+                .ok_or(CreditError::MathOverflow)?
+                .checked_div(BPS_PRECISION as u128 + bonus_bps[slot] as u128)
+                .ok_or(CreditError::MathOverflow)? as u64;
+            repay_remaining = repay_remaining.saturating_sub(repay_covered);
+        }
+    }
+    
+    // Not enough collateral to cover repay + bonus: shrink the repayment to match
+    actual_repay_amount -= repay_remaining;
+    
+    // Transfer USDC from liquidator to treasury
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.liquidator_usdc_account.to_account_info(),
+        to: ctx.accounts.treasury_usdc_account.to_account_info(),
+        authority: ctx.accounts.liquidator.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::transfer(CpiContext::new(cpi_program, cpi_accounts), actual_repay_amount)?;
+    
+    let vault_authority_bump = ctx.bumps.vault_authority;
+    let vault_authority_seeds = &[VAULT_AUTHORITY_SEED, &[vault_authority_bump]];
+    let signer_seeds = &[&vault_authority_seeds[..]];
+    
+    let mut total_seized: u64 = 0;
+    for (slot, accounts) in slots.iter().zip(slot_accounts.chunks(3)) {
+        let amount = seized[*slot];
+        if amount == 0 {
+            continue;
+        }
+        let mint = user_position.collaterals[*slot].mint;
+        
+        let (expected_vault, _) = Pubkey::find_program_address(&[VAULT_SEED, mint.as_ref()], ctx.program_id);
+        require_keys_eq!(accounts[1].key(), expected_vault, CreditError::InvalidAuthority);
+        let vault = Account::<TokenAccount>::try_from(&accounts[1])?;
+        require!(
+            vault.owner == ctx.accounts.vault_authority.key() && vault.delegate.is_none(),
+            CreditError::VaultDelegateSet
+        );
+        require_keys_neq!(accounts[2].key(), expected_vault, CreditError::ReceivingAccountIsVault);
+        let liquidator_account = Account::<TokenAccount>::try_from(&accounts[2])?;
+        require_keys_eq!(liquidator_account.mint, mint, CreditError::InvalidCollateralMint);
+        require_keys_eq!(liquidator_account.owner, ctx.accounts.liquidator.key(), CreditError::Unauthorized);
+        
+        // The protocol keeps its share of the bonus portion of this seizure
+        let protocol_collateral = (amount as u128)
+            .checked_mul(bonus_bps[*slot] as u128 * share_bps[*slot] as u128)
+            .ok_or(CreditError::MathOverflow)?
+            .checked_div((BPS_PRECISION as u128 + bonus_bps[*slot] as u128) * BPS_PRECISION as u128)
+            .ok_or(CreditError::MathOverflow)? as u64;
+        
+        let cpi_accounts = Transfer {
+            from: accounts[1].clone(),
+            to: accounts[2].clone(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(
+            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds),
+            amount - protocol_collateral
+        )?;
+        
+        user_position.collaterals[*slot].amount -= amount;
+        if protocol_collateral > 0 {
+            let collateral = config
+                .collateral_type_mut(&mint)
+                .ok_or(CreditError::InvalidCollateralMint)?;
+            collateral.reserve_collateral = collateral.reserve_collateral
+                .checked_add(protocol_collateral)
+                .ok_or(CreditError::MathOverflow)?;
+            config.liquidation_fees_usdc = config.liquidation_fees_usdc
+                .checked_add(calculate_token_value(protocol_collateral, prices[*slot], decimals[*slot])?)
+                .ok_or(CreditError::MathOverflow)?;
+        }
+        total_seized = total_seized
+            .checked_add(amount)
+            .ok_or(CreditError::MathOverflow)?;
+        
+        msg!("Seized {} of collateral {} ({} to protocol reserves)", amount, mint, protocol_collateral);
+    }
+    
+    let new_debt = current_debt
+        .checked_sub(actual_repay_amount)
+        .ok_or(CreditError::MathOverflow)?;
+    user_position.debt_usdc = new_debt;
+    user_position.borrow_index_snapshot = config.borrow_index_for(&user_position.collateral_mint);
+    user_position.liquidation_count += 1;
+    user_position.last_update_slot = clock.slot;
+    user_position.last_update_timestamp = clock.unix_timestamp;
+    
+    config.total_debt_usdc = config.total_debt_usdc.saturating_sub(actual_repay_amount);
+    config.total_collateral = config.total_collateral
+        .checked_sub(total_seized)
+        .ok_or(CreditError::MathOverflow)?;
+    
+    msg!("Liquidated multi-collateral position of user: {}", user_position.owner);
+    msg!("Repaid: {} USDC, Remaining debt: {} USDC", actual_repay_amount, new_debt);
+    
+    emit!(MultiCollateralLiquidated {
+        user: user_position.owner,
+        event_seq: user_position.next_event_seq(),
+        webhook_id: user_position.webhook_id,
+        liquidator: ctx.accounts.liquidator.key(),
+        repay_amount: actual_repay_amount,
+        collateral_seized: seized.to_vec(),
+        remaining_debt: new_debt,
+        health_factor_at_liquidation: health_factor,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct MultiCollateralLiquidated {
+    pub user: Pubkey,
+    pub event_seq: u64,
+    pub webhook_id: [u8; 32],
+    pub liquidator: Pubkey,
+    pub repay_amount: u64,
+    /// Collateral seized per slot, in `UserPosition::collaterals` order
+    pub collateral_seized: Vec<u64>,
+    pub remaining_debt: u64,
+    /// Health factor (bps) before the liquidation was applied
+    pub health_factor_at_liquidation: u64,
+    pub timestamp: i64,
+}
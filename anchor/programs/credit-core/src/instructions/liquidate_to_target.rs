@@ -0,0 +1,11 @@
+use anchor_lang::prelude::*;
+use crate::instructions::liquidate::{execute, Liquidate, LiquidationSize};
+
+/// Liquidate just enough debt to bring the position to `target_health_bps`
+///
+/// Uses the same accounts and checks as `liquidate`; the computed repayment is
+/// still bounded by the close factor, the seizable collateral fraction and
+/// the collateral the position holds, so the result can fall short of the target.
+pub fn handler(ctx: Context<Liquidate>, target_health_bps: u64) -> Result<()> {
+    execute(ctx, LiquidationSize::TargetHealth(target_health_bps))
+}
@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, UserPosition};
+use crate::utils::get_price_with_fallback;
+use crate::constants::*;
+
+#[derive(Accounts)]
+pub struct MaxWithdrawable<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, user_position.owner.as_ref()],
+        bump,
+        constraint = user_position.is_initialized @ crate::errors::CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Pyth oracle account
+    pub sol_usd_oracle: UncheckedAccount<'info>,
+}
+
+/// Permissionless: compute the largest collateral amount the owner can
+/// withdraw while keeping the health factor at or above
+/// `min_borrow_health_factor_bps`
+///
+/// Uses the same confidence-adjusted price as `withdraw_collateral` and
+/// returns the amount (collateral base units) as return data.
+pub fn handler(ctx: Context<MaxWithdrawable>) -> Result<u64> {
+    let config = &mut ctx.accounts.config;
+    let user_position = &mut ctx.accounts.user_position;
+    let clock = Clock::get()?;
+
+    // Update global interest index
+    config.accrue_interest(clock.unix_timestamp)?;
+
+    // Bring the position's debt up to date
+    let current_debt = user_position.settle_interest(config, clock.unix_timestamp)?;
+
+    config.check_oracle(&user_position.collateral_mint, ctx.accounts.sol_usd_oracle.key)?;
+
+    let sol_price = get_price_with_fallback(
+        &ctx.accounts.sol_usd_oracle.to_account_info(),
+        &clock,
+        config.borrow_max_staleness_slots,
+        config.expected_quote_decimals(&ctx.accounts.sol_usd_oracle.key())
+    )?
+    .with_ema(config.uses_ema_for(EMA_FOR_WITHDRAWALS))?
+    .collateral_price(config.max_confidence_bps, config.borrow_valuation_mode);
+
+    let debt_value = user_position.debt_value(
+        config,
+        current_debt,
+        ctx.remaining_accounts.first(),
+        &clock,
+        config.borrow_max_staleness_slots
+    )?;
+
+    if current_debt == 0 {
+        msg!("No debt - all {} collateral withdrawable", user_position.collateral_amount);
+        return Ok(user_position.collateral_amount);
+    }
+
+    // Invert the health factor calculation, rounding each step up so the
+    // remaining collateral is the smallest amount that still meets the buffer
+    let liquidation_value = ceil_div(
+        (config.min_borrow_health_factor_bps as u128)
+            .checked_mul(debt_value as u128)
+            .ok_or(crate::errors::CreditError::MathOverflow)?,
+        HEALTH_FACTOR_SCALE as u128
+    )?;
+    let collateral_value = ceil_div(
+        liquidation_value
+            .checked_mul(BPS_PRECISION as u128)
+            .ok_or(crate::errors::CreditError::MathOverflow)?,
+        config.liquidation_threshold_bps as u128
+    )?;
+    let required_collateral = ceil_div(
+        collateral_value
+            .checked_mul(1_000_000_000u128) // Price has 6 decimals, collateral 9
+            .ok_or(crate::errors::CreditError::MathOverflow)?,
+        sol_price as u128
+    )?;
+
+    let max_amount = (user_position.collateral_amount as u128)
+        .saturating_sub(required_collateral) as u64;
+
+    msg!(
+        "Max withdrawable for {}: {} (debt: {}, collateral: {})",
+        user_position.owner,
+        max_amount,
+        current_debt,
+        user_position.collateral_amount
+    );
+
+    Ok(max_amount)
+}
+
+fn ceil_div(numerator: u128, denominator: u128) -> Result<u128> {
+    require!(denominator > 0, crate::errors::CreditError::MathOverflow);
+    numerator
+        .checked_add(denominator - 1)
+        .map(|n| n / denominator)
+        .ok_or(error!(crate::errors::CreditError::MathOverflow))
+}
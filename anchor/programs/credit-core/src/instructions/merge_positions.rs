@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::{Config, UserPosition};
+
+#[derive(Accounts)]
+pub struct MergePositions<'info> {
+    /// Position that absorbs the other
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, owner.key().as_ref()],
+        bump,
+        constraint = user_position.owner == owner.key() @ CreditError::Unauthorized,
+        constraint = user_position.is_initialized @ CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    /// Position emptied into `user_position` and closed
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, source_owner.key().as_ref()],
+        bump,
+        constraint = source_position.owner == source_owner.key() @ CreditError::Unauthorized,
+        constraint = source_position.is_initialized @ CreditError::PositionNotInitialized,
+        close = owner
+    )]
+    pub source_position: Account<'info, UserPosition>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Receives the source position's rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Second wallet of the same holder; its signature authorizes giving up its position
+    #[account(constraint = source_owner.key() != owner.key() @ CreditError::InvalidParameter)]
+    pub source_owner: Signer<'info>,
+}
+
+/// Merge the position of another wallet the owner controls into the owner's own
+///
+/// Collateral in both representations, debt and the auto-deleverage buffer are
+/// summed; no tokens move since the vaults are shared. The source's interest is
+/// settled and its debt blended into the owner's by `UserPosition::absorb_debt`,
+/// so the combined debt keeps accruing as the two would have. The source
+/// account is closed to the owner.
+pub fn handler(ctx: Context<MergePositions>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let user_position = &mut ctx.accounts.user_position;
+    let source = &mut ctx.accounts.source_position;
+    let clock = Clock::get()?;
+
+    // Both accrue on the index of their collateral, so it has to be the same one
+    require_keys_eq!(source.collateral_mint, user_position.collateral_mint, CreditError::InvalidCollateralMint);
+    // A linked debit account still holds credit against the source
+    require!(source.pending_credit_usdc == 0, CreditError::CreditHoldsOutstanding);
+
+    config.accrue_interest(clock.unix_timestamp)?;
+    source.settle_interest(config, clock.unix_timestamp)?;
+
+    if source.debt_usdc > 0 {
+        user_position.select_debt_mint(source.debt_mint)?;
+    }
+    let borrow_index = config.borrow_index_for(&user_position.collateral_mint);
+    user_position.absorb_debt(source.debt_usdc, source.borrow_index_snapshot, borrow_index)?;
+
+    user_position.collateral_amount = user_position.collateral_amount
+        .checked_add(source.collateral_amount)
+        .ok_or(CreditError::MathOverflow)?;
+    for entry in source.collaterals.iter().filter(|entry| entry.is_active() && entry.amount > 0) {
+        user_position.add_collateral(entry.mint, entry.amount)?;
+    }
+    require!(
+        user_position.collateral_amount == 0 || !user_position.has_collateral_entries(),
+        CreditError::MixedCollateralFormats
+    );
+
+    user_position.deleverage_buffer_usdc = user_position.deleverage_buffer_usdc
+        .checked_add(source.deleverage_buffer_usdc)
+        .ok_or(CreditError::MathOverflow)?;
+    user_position.bad_debt_usdc = user_position.bad_debt_usdc
+        .checked_add(source.bad_debt_usdc)
+        .ok_or(CreditError::MathOverflow)?;
+    user_position.lifetime_borrows = user_position.lifetime_borrows.saturating_add(source.lifetime_borrows);
+    user_position.lifetime_repayments = user_position.lifetime_repayments.saturating_add(source.lifetime_repayments);
+    user_position.liquidation_count = user_position.liquidation_count.saturating_add(source.liquidation_count);
+    user_position.buffer_breached |= source.buffer_breached;
+
+    // Keep the earlier of each start time (0 means unset)
+    let earliest = |a: i64, b: i64| if a == 0 || (b != 0 && b < a) { b } else { a };
+    user_position.first_deposit_timestamp = earliest(user_position.first_deposit_timestamp, source.first_deposit_timestamp);
+    user_position.borrow_start_timestamp = earliest(user_position.borrow_start_timestamp, source.borrow_start_timestamp);
+
+    // Each limit was taken on its own collateral; the next refresh recomputes the sum
+    let credit_limit = user_position.credit_limit.saturating_add(source.credit_limit);
+    let credit_limit_price = user_position.credit_limit_price;
+    user_position.update_credit_limit(credit_limit, CREDIT_LIMIT_MERGE, credit_limit_price, clock.unix_timestamp);
+
+    user_position.last_update_slot = clock.slot;
+    user_position.last_update_timestamp = clock.unix_timestamp;
+
+    msg!(
+        "Merged position of {} into {}: collateral {}, debt {} USDC",
+        source.owner,
+        user_position.owner,
+        user_position.collateral_amount,
+        user_position.debt_usdc
+    );
+
+    emit!(PositionsMerged {
+        user: user_position.owner,
+        event_seq: user_position.next_event_seq(),
+        webhook_id: user_position.webhook_id,
+        source_owner: source.owner,
+        collateral_amount: user_position.collateral_amount,
+        debt: user_position.debt_usdc,
+        borrow_index_snapshot: user_position.borrow_index_snapshot,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct PositionsMerged {
+    pub user: Pubkey,
+    pub event_seq: u64,
+    pub webhook_id: [u8; 32],
+    pub source_owner: Pubkey,
+    pub collateral_amount: u64,
+    pub debt: u64,
+    pub borrow_index_snapshot: u128,
+    pub timestamp: i64,
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::{Config, UserPosition};
+
+#[derive(Accounts)]
+pub struct MigratePositionMint<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, owner.key().as_ref()],
+        bump,
+        constraint = user_position.owner == owner.key() @ CreditError::Unauthorized,
+        constraint = user_position.is_initialized @ CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+    
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub owner: Signer<'info>,
+}
+
+/// Only positions on the mint the admin migrated away from can move, and only
+/// while empty: their collateral sits in the old mint's vault.
+pub fn handler(ctx: Context<MigratePositionMint>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let user_position = &mut ctx.accounts.user_position;
+    
+    require!(
+        config.previous_jito_sol_mint != Pubkey::default()
+            && user_position.collateral_mint == config.previous_jito_sol_mint,
+        CreditError::InvalidCollateralMint
+    );
+    require!(
+        user_position.collateral_amount == 0,
+        CreditError::PositionHasCollateral
+    );
+    
+    // Settle debt on the old mint's borrow index before moving to the new one
+    let clock = Clock::get()?;
+    config.accrue_interest(clock.unix_timestamp)?;
+    user_position.settle_interest(config, clock.unix_timestamp)?;
+    
+    let previous_mint = user_position.collateral_mint;
+    user_position.collateral_mint = config.jito_sol_mint;
+    user_position.borrow_index_snapshot = config.borrow_index_for(&config.jito_sol_mint);
+    
+    msg!(
+        "Migrated position of {} from {} to {}",
+        user_position.owner,
+        previous_mint,
+        config.jito_sol_mint
+    );
+    
+    Ok(())
+}
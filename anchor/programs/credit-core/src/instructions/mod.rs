@@ -1,19 +1,124 @@
+#![allow(ambiguous_glob_reexports)]
+
 pub mod initialize;
 pub mod init_position;
 pub mod deposit_collateral_wsol;
 pub mod record_debt;
+pub mod borrow_usdc;
 pub mod repay_usdc;
-// pub mod liquidate;
+pub mod withdraw_collateral;
+pub mod liquidate;
 pub mod admin_set_params;
 pub mod admin_set_paused;
+pub mod init_vault;
+pub mod refresh_position;
+pub mod deposit_collateral_batch;
+pub mod admin_set_max_interest_rate;
+pub mod admin_set_pause_flags;
+pub mod accrue_interest;
+pub mod admin_socialize_bad_debt;
+pub mod admin_close_bad_debt_position;
+pub mod admin_set_collateral_type;
+pub mod migrate_position_mint;
+pub mod admin_fund_reserves;
+pub mod admin_set_subsidy;
+pub mod repay_batch;
+pub mod configure_auto_deleverage;
+pub mod trigger_auto_deleverage;
+pub mod deposit_multi_collateral;
+pub mod liquidate_multi;
+pub mod supply_usdc;
+pub mod withdraw_supply;
+pub mod admin_recover_token;
+pub mod max_withdrawable;
+pub mod debit_deposit;
+pub mod admin_set_debit_tier;
+pub mod admin_set_debit_tier_config;
+pub mod emit_liquidation_warning;
+pub mod debit_spend;
+pub mod debit_settle;
+pub mod admin_set_debit_limits;
+pub mod get_config;
+pub mod admin_set_borrow_index;
+pub mod admin_set_oracle_decimals;
+pub mod reconcile_collateral;
+pub mod set_webhook_id;
+pub mod withdraw_and_unwrap;
+pub mod liquidate_to_target;
+pub mod repay_with_collateral;
+pub mod admin_set_debt_asset;
+pub mod borrow_asset;
+pub mod repay_asset;
+pub mod configure_stop_loss;
+pub mod execute_stop_loss;
+pub mod enforce_buffer;
+pub mod deposit_and_borrow;
+pub mod get_liquidation_price;
+pub mod apply_pending_params;
+pub mod init_and_deposit;
+pub mod admin_shutdown;
+pub mod get_fee_breakdown;
+pub mod link_credit_position;
+pub mod merge_positions;
 
 pub use initialize::*;
 pub use init_position::*;
 pub use deposit_collateral_wsol::*;
 pub use record_debt::*;
+pub use borrow_usdc::*;
 pub use repay_usdc::*;
-// pub use liquidate::*;
+pub use withdraw_collateral::*;
+pub use liquidate::*;
 pub use admin_set_params::*;
-pub use admin_set_paused::*; 
+pub use admin_set_paused::*;
+pub use init_vault::*;
+pub use refresh_position::*;
+pub use deposit_collateral_batch::*;
+pub use admin_set_max_interest_rate::*;
+pub use admin_set_pause_flags::*;
+pub use accrue_interest::*;
+pub use admin_socialize_bad_debt::*;
+pub use admin_close_bad_debt_position::*;
+pub use admin_set_collateral_type::*;
+pub use migrate_position_mint::*;
+pub use admin_fund_reserves::*;
+pub use admin_set_subsidy::*;
+pub use repay_batch::*;
+pub use configure_auto_deleverage::*;
+pub use trigger_auto_deleverage::*;
+pub use deposit_multi_collateral::*;
+pub use liquidate_multi::*;
+pub use supply_usdc::*;
+pub use withdraw_supply::*;
+pub use admin_recover_token::*;
+pub use max_withdrawable::*;
+pub use debit_deposit::*;
+pub use admin_set_debit_tier::*;
+pub use admin_set_debit_tier_config::*;
+pub use emit_liquidation_warning::*;
+pub use debit_spend::*;
+pub use debit_settle::*;
+pub use admin_set_debit_limits::*;
+pub use get_config::*;
 
 
+pub use admin_set_borrow_index::*;
+pub use admin_set_oracle_decimals::*;
+pub use reconcile_collateral::*;
+pub use set_webhook_id::*;
+pub use withdraw_and_unwrap::*;
+pub use repay_with_collateral::*;
+pub use admin_set_debt_asset::*;
+pub use borrow_asset::*;
+pub use repay_asset::*;
+pub use configure_stop_loss::*;
+pub use execute_stop_loss::*;
+pub use enforce_buffer::*;
+pub use deposit_and_borrow::*;
+pub use get_liquidation_price::*;
+pub use apply_pending_params::*;
+pub use init_and_deposit::*;
+pub use admin_shutdown::*;
+pub use get_fee_breakdown::*;
+pub use link_credit_position::*;
+pub use merge_positions::*;
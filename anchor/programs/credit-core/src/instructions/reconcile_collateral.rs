@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::Config;
+
+#[derive(Accounts)]
+pub struct ReconcileCollateral<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = config.admin == admin.key() @ CreditError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub admin: Signer<'info>,
+}
+
+/// Reset `total_collateral` to what the collateral vaults actually hold
+///
+/// `remaining_accounts` holds the WSOL vault followed by the vault of every other
+/// registered collateral type, in slot order; a vault that was never created
+/// counts as empty. Collateral owned by the protocol (`reserve_collateral`) is not
+/// user collateral and is excluded.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, ReconcileCollateral<'info>>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+    
+    // (mint, protocol-owned amount) for every vault that backs total_collateral
+    let wsol_reserve = config.collateral_type(&config.wsol_mint)
+        .map(|collateral| collateral.reserve_collateral)
+        .unwrap_or(0);
+    let mut vaults = vec![(config.wsol_mint, wsol_reserve)];
+    vaults.extend(
+        config.collateral_types
+            .iter()
+            .filter(|collateral| collateral.is_active() && collateral.mint != config.wsol_mint)
+            .map(|collateral| (collateral.mint, collateral.reserve_collateral))
+    );
+    require!(
+        ctx.remaining_accounts.len() == vaults.len(),
+        CreditError::InvalidParameter
+    );
+    
+    let mut reconciled_total: u64 = 0;
+    for ((mint, reserve_collateral), vault_info) in vaults.iter().zip(ctx.remaining_accounts.iter()) {
+        let (expected_vault, _) = Pubkey::find_program_address(
+            &[VAULT_SEED, mint.as_ref()],
+            ctx.program_id
+        );
+        require_keys_eq!(vault_info.key(), expected_vault, CreditError::InvalidAuthority);
+        
+        let vault_balance = if vault_info.data_is_empty() {
+            0
+        } else {
+            Account::<TokenAccount>::try_from(vault_info)?.amount
+        };
+        let user_collateral = vault_balance.saturating_sub(*reserve_collateral);
+        reconciled_total = reconciled_total
+            .checked_add(user_collateral)
+            .ok_or(CreditError::MathOverflow)?;
+    }
+    
+    let previous_total = config.total_collateral;
+    config.total_collateral = reconciled_total;
+    
+    msg!("Reconciled total collateral from {} to {}", previous_total, reconciled_total);
+    
+    emit!(CollateralReconciled {
+        admin: ctx.accounts.admin.key(),
+        previous_total,
+        reconciled_total,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct CollateralReconciled {
+    pub admin: Pubkey,
+    pub previous_total: u64,
+    pub reconciled_total: u64,
+    pub timestamp: i64,
+}
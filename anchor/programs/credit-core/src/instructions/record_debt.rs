@@ -1,6 +1,5 @@
 use anchor_lang::prelude::*;
 use crate::state::{Config, UserPosition};
-use crate::utils::calculate_borrow_index;
 use crate::constants::*;
 
 #[derive(Accounts)]
@@ -29,65 +28,58 @@ pub fn handler(ctx: Context<RecordDebt>, usdc_amount: u64) -> Result<()> {
     let user_position = &mut ctx.accounts.user_position;
     let clock = Clock::get()?;
 
+    // New debt is blocked by the global pause as well as the borrow flag
+    require!(!config.is_paused_for(PAUSE_BORROWS), crate::errors::CreditError::ProtocolPaused);
+    require!(
+        user_position.meets_min_age(config.min_position_age_secs, clock.unix_timestamp),
+        crate::errors::CreditError::PositionTooNew
+    );
+
     // Update global interest index
-    config.global_borrow_index = calculate_borrow_index(
-        config.last_update_timestamp,
-        clock.unix_timestamp,
-        config.global_borrow_index,
-        config.interest_rate_bps
-    )?;
-    config.global_borrow_index = calculate_borrow_index(
-        config.last_update_timestamp,
-        clock.unix_timestamp,
-        config.global_borrow_index,
-        config.interest_rate_bps
-    )?;
-    config.global_borrow_index = calculate_borrow_index(
-        config.last_update_timestamp,
-        clock.unix_timestamp,
-        config.global_borrow_index,
-        config.interest_rate_bps
-    )?;
-    config.global_borrow_index = calculate_borrow_index(
-        config.last_update_timestamp,
-        clock.unix_timestamp,
-        config.global_borrow_index,
-        config.interest_rate_bps
-    )?;
-    config.global_borrow_index = calculate_borrow_index(
-        config.last_update_timestamp,
-        clock.unix_timestamp,
-        config.global_borrow_index,
-        config.interest_rate_bps
-    )?;
-    config.global_borrow_index = calculate_borrow_index(
-        config.last_update_timestamp,
-        clock.unix_timestamp,
-        config.global_borrow_index,
-        config.interest_rate_bps
-    )?;
-    config.last_update_timestamp = clock.unix_timestamp;
+    config.accrue_interest(clock.unix_timestamp)?;
 
     // Update user's existing debt with interest
-    if user_position.debt_usdc > 0 {
-        user_position.debt_usdc = user_position.calculate_debt_with_interest(config.global_borrow_index)?;
-    }
+    user_position.settle_interest(config, clock.unix_timestamp)?;
+    user_position.select_debt_mint(Pubkey::default())?;
 
     // Add new debt
+    if user_position.debt_usdc == 0 {
+        user_position.borrow_start_timestamp = clock.unix_timestamp;
+    }
     user_position.debt_usdc = user_position.debt_usdc
         .checked_add(usdc_amount)
         .ok_or(crate::errors::CreditError::MathOverflow)?;
     
     // Update borrow index snapshot
-    user_position.borrow_index_snapshot = config.global_borrow_index;
+    user_position.borrow_index_snapshot = config.borrow_index_for(&user_position.collateral_mint);
     user_position.last_update_timestamp = clock.unix_timestamp;
 
-    // Update global debt
+    // Settling above already moved the global total by the accrued interest,
+    // so only the new principal is added here
     config.total_debt_usdc = config.total_debt_usdc
         .checked_add(usdc_amount)
         .ok_or(crate::errors::CreditError::MathOverflow)?;
 
     msg!("Recorded {} USDC debt for user: {}", usdc_amount, ctx.accounts.owner.key());
 
+    emit!(DebtRecorded {
+        user: user_position.owner,
+        event_seq: user_position.next_event_seq(),
+        webhook_id: user_position.webhook_id,
+        amount: usdc_amount,
+        new_total_debt: user_position.debt_usdc,
+        timestamp: clock.unix_timestamp,
+    });
+
     Ok(())
 }
+
+#[event]
+pub struct DebtRecorded {
+    pub user: Pubkey,
+    pub event_seq: u64,
+    pub webhook_id: [u8; 32],
+    pub amount: u64,
+    pub new_total_debt: u64,
+    pub timestamp: i64,
+}
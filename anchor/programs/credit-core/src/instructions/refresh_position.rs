@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, UserPosition};
+use crate::utils::{get_price_with_fallback, calculate_health_factor, sum_collateral_values};
+use crate::constants::*;
+
+#[derive(Accounts)]
+pub struct RefreshPosition<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, user_position.owner.as_ref()],
+        bump,
+        constraint = user_position.is_initialized @ crate::errors::CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Pyth oracle account
+    pub sol_usd_oracle: UncheckedAccount<'info>,
+}
+
+/// Permissionless: recompute a position's stored credit limit and cached
+/// health factor at current prices so clients can read them directly
+///
+/// Returns the health factor as return data, scaled so that
+/// `HEALTH_FACTOR_SCALE` (10000) is 1.0. `remaining_accounts` holds the debt
+/// asset's feed when it is oracle-priced, then the oracles of the position's
+/// slot collateral as `UserPosition::collateral_values` expects them.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, RefreshPosition<'info>>) -> Result<u64> {
+    let config = &mut ctx.accounts.config;
+    let user_position = &mut ctx.accounts.user_position;
+    let clock = Clock::get()?;
+
+    // Update global interest index
+    config.accrue_interest(clock.unix_timestamp)?;
+
+    // Bring the position's debt up to date, valued in USDC for the health factor
+    user_position.settle_interest(config, clock.unix_timestamp)?;
+    let (debt_oracle, collateral_oracles) = config.split_debt_oracle(&user_position.debt_mint, ctx.remaining_accounts);
+    let debt_value = user_position.debt_value(
+        config,
+        user_position.debt_usdc,
+        debt_oracle,
+        &clock,
+        config.borrow_max_staleness_slots
+    )?;
+
+    config.check_oracle(&user_position.collateral_mint, ctx.accounts.sol_usd_oracle.key)?;
+
+    let oracle_price = get_price_with_fallback(
+        &ctx.accounts.sol_usd_oracle.to_account_info(),
+        &clock,
+        config.borrow_max_staleness_slots,
+        config.expected_quote_decimals(&ctx.accounts.sol_usd_oracle.key())
+    )?
+    .with_ema(config.uses_ema_for(EMA_FOR_BORROWS))?;
+
+    // Borrow power uses the same confidence-adjusted price as deposits
+    let collateral_price = oracle_price.collateral_price(config.max_confidence_bps, config.borrow_valuation_mode);
+    let credit_values = user_position.collateral_values(
+        config,
+        collateral_price,
+        collateral_oracles,
+        &clock,
+        config.borrow_max_staleness_slots,
+        |price| Ok(price
+            .with_ema(config.uses_ema_for(EMA_FOR_BORROWS))?
+            .collateral_price(config.max_confidence_bps, config.borrow_valuation_mode))
+    )?;
+
+    // Health is reported as liquidations would judge it
+    let health_values = user_position.collateral_values(
+        config,
+        oracle_price.valued_at(config.liquidation_valuation_mode),
+        collateral_oracles,
+        &clock,
+        config.borrow_max_staleness_slots,
+        |price| Ok(price
+            .with_ema(config.uses_ema_for(EMA_FOR_BORROWS))?
+            .valued_at(config.liquidation_valuation_mode))
+    )?;
+    let health_factor = calculate_health_factor(
+        sum_collateral_values(&health_values)?,
+        debt_value,
+        config.liquidation_threshold_bps
+    )?;
+
+    user_position.update_credit_limit(
+        config.weighted_credit_limit(&credit_values)?,
+        CREDIT_LIMIT_REFRESH,
+        collateral_price,
+        clock.unix_timestamp
+    );
+    user_position.last_health_factor = health_factor;
+    user_position.last_health_timestamp = clock.unix_timestamp;
+    user_position.last_update_slot = clock.slot;
+    user_position.last_update_timestamp = clock.unix_timestamp;
+
+    msg!(
+        "Refreshed position {} - Debt: {}, Credit Limit: {}, Health Factor: {}",
+        user_position.owner,
+        user_position.debt_usdc,
+        user_position.credit_limit,
+        health_factor
+    );
+
+    Ok(health_factor)
+}
@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::state::{Config, UserPosition};
+use crate::constants::*;
+use crate::errors::CreditError;
+
+#[derive(Accounts)]
+pub struct RepayAsset<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, owner.key().as_ref()],
+        bump,
+        constraint = user_position.owner == owner.key() @ CreditError::Unauthorized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Owner's token account for the debt asset
+    #[account(
+        mut,
+        constraint = user_asset_account.owner == owner.key() @ CreditError::Unauthorized,
+        constraint = user_asset_account.mint == user_position.debt_mint @ CreditError::InvalidMint
+    )]
+    pub user_asset_account: Account<'info, TokenAccount>,
+
+    /// Program vault for the debt asset
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user_position.debt_mint.as_ref()],
+        bump,
+        token::mint = user_position.debt_mint
+    )]
+    pub asset_vault: Account<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Repay up to `amount` of debt owed in a non-USDC asset (USDC debt uses `repay_usdc`)
+pub fn handler(ctx: Context<RepayAsset>, amount: u64) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let user_position = &mut ctx.accounts.user_position;
+    let clock = Clock::get()?;
+
+    require!(!config.repays_paused(), CreditError::ProtocolPaused);
+    require!(!user_position.owes_usdc(), CreditError::DebtMintMismatch);
+
+    config.accrue_interest(clock.unix_timestamp)?;
+    let current_debt = user_position.settle_interest(config, clock.unix_timestamp)?;
+
+    let repay_amount = amount.min(current_debt);
+    require!(repay_amount > 0, CreditError::AmountTooSmall);
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.user_asset_account.to_account_info(),
+        to: ctx.accounts.asset_vault.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::transfer(CpiContext::new(cpi_program, cpi_accounts), repay_amount)?;
+
+    user_position.debt_usdc = current_debt - repay_amount;
+    user_position.last_update_timestamp = clock.unix_timestamp;
+
+    let total_debt = config.debt_total_mut(&user_position.debt_mint)?;
+    *total_debt = total_debt.saturating_sub(repay_amount);
+
+    msg!(
+        "Repaid {} of {} for user: {}, remaining debt: {}",
+        repay_amount,
+        user_position.debt_mint,
+        user_position.owner,
+        user_position.debt_usdc
+    );
+
+    emit!(AssetRepaid {
+        user: user_position.owner,
+        event_seq: user_position.next_event_seq(),
+        webhook_id: user_position.webhook_id,
+        debt_mint: user_position.debt_mint,
+        amount: repay_amount,
+        remaining_debt: user_position.debt_usdc,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct AssetRepaid {
+    pub user: Pubkey,
+    pub event_seq: u64,
+    pub webhook_id: [u8; 32],
+    pub debt_mint: Pubkey,
+    pub amount: u64,
+    pub remaining_debt: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,144 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::state::{Config, UserPosition};
+use crate::errors::CreditError;
+use crate::constants::*;
+use super::repay_usdc::DebtRepaid;
+
+#[derive(Accounts)]
+pub struct RepayBatch<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Payer's USDC token account funding every repayment in the batch
+    #[account(
+        mut,
+        constraint = payer_usdc_account.owner == payer.key() @ CreditError::Unauthorized,
+        constraint = payer_usdc_account.mint == config.usdc_mint @ CreditError::InvalidMint
+    )]
+    pub payer_usdc_account: Account<'info, TokenAccount>,
+
+    /// Program's USDC treasury account
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, config.usdc_mint.as_ref()],
+        bump,
+        token::mint = config.usdc_mint
+    )]
+    pub treasury_usdc_account: Account<'info, TokenAccount>,
+
+    pub payer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Repay debt on several positions in one transaction
+///
+/// `remaining_accounts` holds one writable user position per entry in
+/// `amounts`. Each position accrues interest and is repaid up to its debt;
+/// positions without debt are skipped. The payer funds the total actually repaid
+/// plus any prepayment fees, which go to reserves.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RepayBatch<'info>>,
+    amounts: Vec<u64>,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    // Repays stay open under the global pause unless it is configured to cover them
+    require!(!config.repays_paused(), CreditError::ProtocolPaused);
+    require!(
+        !amounts.is_empty() && amounts.len() <= MAX_BATCH_REPAYS,
+        CreditError::InvalidParameter
+    );
+    require!(
+        ctx.remaining_accounts.len() == amounts.len(),
+        CreditError::InvalidParameter
+    );
+
+    // Update global interest index once for the whole batch
+    config.accrue_interest(clock.unix_timestamp)?;
+
+    let mut total_repaid: u64 = 0;
+    let mut total_fees: u64 = 0;
+    for (position_info, amount) in ctx.remaining_accounts.iter().zip(amounts.iter()) {
+        let mut user_position = Account::<UserPosition>::try_from(position_info)?;
+        let (expected_position, _) = Pubkey::find_program_address(
+            &[USER_POSITION_SEED, user_position.owner.as_ref()],
+            ctx.program_id
+        );
+        require_keys_eq!(position_info.key(), expected_position, CreditError::Unauthorized);
+        require!(user_position.is_initialized, CreditError::PositionNotInitialized);
+        require!(user_position.owes_usdc(), CreditError::DebtMintMismatch);
+
+        let current_debt = user_position.settle_interest(config, clock.unix_timestamp)?;
+        if current_debt == 0 {
+            msg!("Skipping position of {} with no debt", user_position.owner);
+            user_position.exit(ctx.program_id)?;
+            continue;
+        }
+
+        let repay_amount = (*amount).min(current_debt);
+        let prepayment_fee = user_position.prepayment_fee(config, repay_amount, clock.unix_timestamp)?;
+        total_fees = total_fees
+            .checked_add(prepayment_fee)
+            .ok_or(CreditError::MathOverflow)?;
+        user_position.debt_usdc = current_debt - repay_amount;
+        user_position.lifetime_repayments = user_position.lifetime_repayments
+            .checked_add(repay_amount)
+            .ok_or(CreditError::MathOverflow)?;
+        user_position.last_update_timestamp = clock.unix_timestamp;
+
+        emit!(DebtRepaid {
+            user: user_position.owner,
+            event_seq: user_position.next_event_seq(),
+            webhook_id: user_position.webhook_id,
+            amount: repay_amount,
+            prepayment_fee,
+            remaining_debt: user_position.debt_usdc,
+            timestamp: clock.unix_timestamp,
+        });
+        user_position.exit(ctx.program_id)?;
+
+        total_repaid = total_repaid
+            .checked_add(repay_amount)
+            .ok_or(CreditError::MathOverflow)?;
+
+        msg!("Repaid {} USDC for user: {}", repay_amount, user_position.owner);
+    }
+
+    // The payer covers the repayments plus any prepayment fees
+    let total_paid = total_repaid
+        .checked_add(total_fees)
+        .ok_or(CreditError::MathOverflow)?;
+    if total_paid > 0 {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.payer_usdc_account.to_account_info(),
+                    to: ctx.accounts.treasury_usdc_account.to_account_info(),
+                    authority: ctx.accounts.payer.to_account_info(),
+                }
+            ),
+            total_paid
+        )?;
+    }
+
+    let config = &mut ctx.accounts.config;
+    config.total_debt_usdc = config.total_debt_usdc.saturating_sub(total_repaid);
+    config.total_reserves_usdc = config.total_reserves_usdc
+        .checked_add(total_fees)
+        .ok_or(CreditError::MathOverflow)?;
+    config.prepayment_fees_usdc = config.prepayment_fees_usdc
+        .checked_add(total_fees)
+        .ok_or(CreditError::MathOverflow)?;
+
+    msg!("Batch repaid {} USDC across {} positions", total_repaid, amounts.len());
+
+    Ok(())
+}
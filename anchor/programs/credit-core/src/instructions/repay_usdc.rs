@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 use crate::state::{Config, UserPosition};
-use crate::utils::calculate_borrow_index;
 use crate::constants::*;
 
 #[derive(Accounts)]
@@ -20,70 +20,84 @@ pub struct RepayUsdc<'info> {
     )]
     pub config: Account<'info, Config>,
 
-    /// User's USDC token account (simplified for testing)
-    /// CHECK: Simplified for testing
-    pub user_usdc_account: UncheckedAccount<'info>,
+    /// Owner's USDC token account paying the debt and any prepayment fee
+    #[account(
+        mut,
+        constraint = user_usdc_account.owner == owner.key() @ crate::errors::CreditError::Unauthorized,
+        constraint = user_usdc_account.mint == config.usdc_mint @ crate::errors::CreditError::InvalidMint
+    )]
+    pub user_usdc_account: Account<'info, TokenAccount>,
 
-    /// Treasury USDC account (simplified for testing)
-    /// CHECK: Simplified for testing
-    pub treasury_usdc_account: UncheckedAccount<'info>,
+    /// Program USDC treasury, owned by the vault authority PDA
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, config.usdc_mint.as_ref()],
+        bump,
+        token::mint = config.usdc_mint,
+        constraint = treasury_usdc_account.owner
+            == Pubkey::find_program_address(&[VAULT_AUTHORITY_SEED], &crate::ID).0
+            @ crate::errors::CreditError::InvalidAuthority,
+        constraint = treasury_usdc_account.delegate.is_none() @ crate::errors::CreditError::VaultDelegateSet
+    )]
+    pub treasury_usdc_account: Account<'info, TokenAccount>,
 
     #[account(mut)]
     pub owner: Signer<'info>,
 
-    /// CHECK: Token program
-    pub token_program: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
 }
 
-pub fn handler(ctx: Context<RepayUsdc>, usdc_amount: u64) -> Result<()> {
+/// Repay up to `usdc_amount`; with `repay_all` the whole debt accrued at execution
+/// is repaid instead, and `usdc_amount` caps the total charged (debt plus any fee)
+pub fn handler(ctx: Context<RepayUsdc>, usdc_amount: u64, repay_all: bool) -> Result<()> {
     let config = &mut ctx.accounts.config;
     let user_position = &mut ctx.accounts.user_position;
     let clock = Clock::get()?;
 
+    // Repays stay open under the global pause so borrowers can always reduce
+    // debt, unless the admin has configured the pause to cover them too
+    require!(!config.repays_paused(), crate::errors::CreditError::ProtocolPaused);
+    require!(user_position.owes_usdc(), crate::errors::CreditError::DebtMintMismatch);
+
     // Update global interest index
-    config.global_borrow_index = calculate_borrow_index(
-        config.last_update_timestamp,
-        clock.unix_timestamp,
-        config.global_borrow_index,
-        config.interest_rate_bps
-    )?;
-    config.global_borrow_index = calculate_borrow_index(
-        config.last_update_timestamp,
-        clock.unix_timestamp,
-        config.global_borrow_index,
-        config.interest_rate_bps
-    )?;
-    config.global_borrow_index = calculate_borrow_index(
-        config.last_update_timestamp,
-        clock.unix_timestamp,
-        config.global_borrow_index,
-        config.interest_rate_bps
-    )?;
-    config.global_borrow_index = calculate_borrow_index(
-        config.last_update_timestamp,
-        clock.unix_timestamp,
-        config.global_borrow_index,
-        config.interest_rate_bps
-    )?;
-    config.global_borrow_index = calculate_borrow_index(
-        config.last_update_timestamp,
-        clock.unix_timestamp,
-        config.global_borrow_index,
-        config.interest_rate_bps
-    )?;
-    config.global_borrow_index = calculate_borrow_index(
-        config.last_update_timestamp,
-        clock.unix_timestamp,
-        config.global_borrow_index,
-        config.interest_rate_bps
-    )?;
-    config.last_update_timestamp = clock.unix_timestamp;
+    config.accrue_interest(clock.unix_timestamp)?;
 
     // Calculate current debt with interest
-    let current_debt = user_position.calculate_debt_with_interest(config.global_borrow_index)?;
+    let current_debt = user_position.settle_interest(config, clock.unix_timestamp)?;
     
     // Ensure not overpaying
-    let repay_amount = usdc_amount.min(current_debt);
+    let repay_amount = if repay_all { current_debt } else { usdc_amount.min(current_debt) };
+    
+    // Repaying early within the minimum loan term carries a fee to reserves
+    let prepayment_fee = user_position.prepayment_fee(config, repay_amount, clock.unix_timestamp)?;
+    
+    let total_charged = repay_amount
+        .checked_add(prepayment_fee)
+        .ok_or(crate::errors::CreditError::MathOverflow)?;
+    
+    // Interest accrued since the client read its debt must not exceed the caller's cap
+    if repay_all {
+        require!(
+            total_charged <= usdc_amount,
+            crate::errors::CreditError::SlippageExceeded
+        );
+    }
+
+    // Move the repayment and the fee into the treasury before booking either
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.user_usdc_account.to_account_info(),
+        to: ctx.accounts.treasury_usdc_account.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::transfer(CpiContext::new(cpi_program, cpi_accounts), total_charged)?;
+
+    config.total_reserves_usdc = config.total_reserves_usdc
+        .checked_add(prepayment_fee)
+        .ok_or(crate::errors::CreditError::MathOverflow)?;
+    config.prepayment_fees_usdc = config.prepayment_fees_usdc
+        .checked_add(prepayment_fee)
+        .ok_or(crate::errors::CreditError::MathOverflow)?;
 
     // Update user debt
     user_position.debt_usdc = current_debt
@@ -91,7 +105,7 @@ pub fn handler(ctx: Context<RepayUsdc>, usdc_amount: u64) -> Result<()> {
         .ok_or(crate::errors::CreditError::MathOverflow)?;
     
     // Update borrow index snapshot
-    user_position.borrow_index_snapshot = config.global_borrow_index;
+    user_position.borrow_index_snapshot = config.borrow_index_for(&user_position.collateral_mint);
     user_position.last_update_timestamp = clock.unix_timestamp;
 
     // Update global debt
@@ -99,7 +113,32 @@ pub fn handler(ctx: Context<RepayUsdc>, usdc_amount: u64) -> Result<()> {
         .saturating_sub(repay_amount);
 
     msg!("Repaid {} USDC for user: {}", repay_amount, ctx.accounts.owner.key());
-    msg!("Remaining debt: {} USDC", user_position.debt_usdc);
+    msg!("Remaining debt: {} USDC, Prepayment fee: {} USDC", user_position.debt_usdc, prepayment_fee);
+
+    emit!(DebtRepaid {
+        user: user_position.owner,
+        event_seq: user_position.next_event_seq(),
+        webhook_id: user_position.webhook_id,
+        amount: repay_amount,
+        prepayment_fee,
+        remaining_debt: user_position.debt_usdc,
+        timestamp: clock.unix_timestamp,
+    });
+
+    #[cfg(feature = "verbose-state")]
+    user_position.log_state(None, config.liquidation_threshold_bps);
 
     Ok(())
 }
+
+#[event]
+pub struct DebtRepaid {
+    pub user: Pubkey,
+    pub event_seq: u64,
+    pub webhook_id: [u8; 32],
+    pub amount: u64,
+    /// Early repayment fee transferred to the treasury on top of the repayment and booked to reserves
+    pub prepayment_fee: u64,
+    pub remaining_debt: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,186 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::{Config, UserPosition};
+use crate::utils::{get_price_with_fallback, calculate_collateral_value, calculate_max_borrow, usdc_to_collateral};
+
+#[derive(Accounts)]
+pub struct RepayWithCollateral<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, owner.key().as_ref()],
+        bump,
+        constraint = user_position.owner == owner.key() @ CreditError::Unauthorized,
+        constraint = user_position.is_initialized @ CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+    
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// Pyth oracle for SOL/USD price
+    /// CHECK: Validated in handler
+    pub sol_usd_oracle: UncheckedAccount<'info>,
+    
+    pub owner: Signer<'info>,
+}
+
+/// Repay debt by selling collateral to the protocol at the oracle price
+///
+/// Reserves pay for the collateral, which stays in the vault as reserve
+/// collateral, so no tokens move. Deleveraging is allowed below the borrow
+/// buffer as long as it doesn't lower the health factor.
+pub fn handler(ctx: Context<RepayWithCollateral>, collateral_amount: u64) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let user_position = &mut ctx.accounts.user_position;
+    let clock = Clock::get()?;
+    
+    require!(!config.repays_paused(), CreditError::ProtocolPaused);
+    require!(user_position.owes_usdc(), CreditError::DebtMintMismatch);
+    require!(collateral_amount > 0, CreditError::AmountTooSmall);
+    require!(
+        collateral_amount <= user_position.collateral_amount,
+        CreditError::InsufficientCollateral
+    );
+    
+    config.accrue_interest(clock.unix_timestamp)?;
+    let current_debt = user_position.settle_interest(config, clock.unix_timestamp)?;
+    require!(current_debt > 0, CreditError::RepayExceedsDebt);
+    
+    config.check_oracle(&user_position.collateral_mint, ctx.accounts.sol_usd_oracle.key)?;
+    
+    let price = get_price_with_fallback(
+        &ctx.accounts.sol_usd_oracle.to_account_info(),
+        &clock,
+        config.borrow_max_staleness_slots,
+        config.expected_quote_decimals(&ctx.accounts.sol_usd_oracle.key())
+    )?
+    .with_ema(config.uses_ema_for(EMA_FOR_WITHDRAWALS))?
+    .collateral_price(config.max_confidence_bps, config.borrow_valuation_mode);
+    
+    let health_before = user_position.calculate_health_factor(
+        price,
+        config.liquidation_threshold_bps,
+        current_debt
+    )?;
+    let (repay_amount, collateral_sold) = sell_collateral_to_reserves(
+        config,
+        user_position,
+        current_debt,
+        collateral_amount,
+        price,
+        &clock
+    )?;
+    let health_after = user_position.calculate_health_factor(
+        price,
+        config.liquidation_threshold_bps,
+        user_position.debt_usdc
+    )?;
+    require!(
+        config.health_after_allowed(Some(health_before), health_after),
+        CreditError::HealthFactorTooLow
+    );
+    
+    msg!(
+        "Repaid {} USDC with {} collateral for {}, health factor {} -> {}",
+        repay_amount,
+        collateral_sold,
+        user_position.owner,
+        health_before,
+        health_after
+    );
+    
+    emit!(DebtRepaidWithCollateral {
+        user: user_position.owner,
+        event_seq: user_position.next_event_seq(),
+        webhook_id: user_position.webhook_id,
+        repay_amount,
+        collateral_sold,
+        oracle_price: price,
+        remaining_debt: user_position.debt_usdc,
+        remaining_collateral: user_position.collateral_amount,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    #[cfg(feature = "verbose-state")]
+    user_position.log_state(Some(price), config.liquidation_threshold_bps);
+    
+    Ok(())
+}
+
+/// Sell up to `collateral_amount` to reserves at `price` against the settled
+/// `current_debt`, returning the USDC repaid and the collateral sold
+///
+/// Reserves pay down the debt and the collateral stays in the vault as
+/// reserve collateral; the caller checks the resulting health.
+pub(crate) fn sell_collateral_to_reserves(
+    config: &mut Config,
+    user_position: &mut UserPosition,
+    current_debt: u64,
+    collateral_amount: u64,
+    price: u64,
+    clock: &Clock,
+) -> Result<(u64, u64)> {
+    // Never sell more collateral than the debt is worth
+    let collateral_value = calculate_collateral_value(collateral_amount, price)?;
+    let (repay_amount, collateral_sold) = if collateral_value > current_debt {
+        (current_debt, usdc_to_collateral(current_debt, price)?.min(collateral_amount))
+    } else {
+        (collateral_value, collateral_amount)
+    };
+    require!(repay_amount > 0, CreditError::AmountTooSmall);
+    require!(
+        config.total_reserves_usdc >= repay_amount,
+        CreditError::InsufficientReserves
+    );
+    
+    let new_debt = current_debt - repay_amount;
+    let new_collateral = user_position.collateral_amount
+        .checked_sub(collateral_sold)
+        .ok_or(CreditError::InsufficientCollateral)?;
+    
+    // Reserves buy the collateral; it stays in the vault on the protocol's books
+    config.total_reserves_usdc -= repay_amount;
+    config.total_debt_usdc = config.total_debt_usdc.saturating_sub(repay_amount);
+    config.total_collateral = config.total_collateral
+        .checked_sub(collateral_sold)
+        .ok_or(CreditError::MathOverflow)?;
+    let collateral = config
+        .collateral_type_mut(&user_position.collateral_mint)
+        .ok_or(CreditError::InvalidCollateralMint)?;
+    collateral.reserve_collateral = collateral.reserve_collateral
+        .checked_add(collateral_sold)
+        .ok_or(CreditError::MathOverflow)?;
+    
+    user_position.debt_usdc = new_debt;
+    user_position.collateral_amount = new_collateral;
+    user_position.borrow_index_snapshot = config.borrow_index_for(&user_position.collateral_mint);
+    user_position.lifetime_repayments = user_position.lifetime_repayments
+        .checked_add(repay_amount)
+        .ok_or(CreditError::MathOverflow)?;
+    user_position.last_update_slot = clock.slot;
+    user_position.last_update_timestamp = clock.unix_timestamp;
+    
+    let collateral_value_usd = calculate_collateral_value(new_collateral, price)?;
+    user_position.credit_limit = calculate_max_borrow(collateral_value_usd, config.credit_limit_ltv_bps())?;
+    
+    Ok((repay_amount, collateral_sold))
+}
+
+#[event]
+pub struct DebtRepaidWithCollateral {
+    pub user: Pubkey,
+    pub event_seq: u64,
+    pub webhook_id: [u8; 32],
+    pub repay_amount: u64,
+    pub collateral_sold: u64,
+    /// Price the collateral was sold to reserves at (6 decimals)
+    pub oracle_price: u64,
+    pub remaining_debt: u64,
+    pub remaining_collateral: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::UserPosition;
+
+#[derive(Accounts)]
+pub struct SetWebhookId<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, owner.key().as_ref()],
+        bump,
+        constraint = user_position.owner == owner.key() @ CreditError::Unauthorized,
+        constraint = user_position.is_initialized @ CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+    
+    pub owner: Signer<'info>,
+}
+
+/// Set the opaque identifier position events carry for notification routing
+///
+/// The program never interprets it; all zeros clears it.
+pub fn handler(ctx: Context<SetWebhookId>, webhook_id: [u8; 32]) -> Result<()> {
+    let user_position = &mut ctx.accounts.user_position;
+    user_position.webhook_id = webhook_id;
+    
+    msg!("Updated webhook id for position of {}", user_position.owner);
+    
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::{Config, SupplierPosition};
+
+#[derive(Accounts)]
+pub struct SupplyUsdc<'info> {
+    #[account(
+        init_if_needed,
+        payer = supplier,
+        space = SupplierPosition::LEN,
+        seeds = [SUPPLIER_POSITION_SEED, supplier.key().as_ref()],
+        bump
+    )]
+    pub supplier_position: Account<'info, SupplierPosition>,
+    
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = !config.is_paused_for(PAUSE_DEPOSITS) @ CreditError::ProtocolPaused
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// Supplier's USDC token account
+    #[account(
+        mut,
+        constraint = supplier_usdc_account.owner == supplier.key() @ CreditError::Unauthorized,
+        constraint = supplier_usdc_account.mint == config.usdc_mint @ CreditError::InvalidMint
+    )]
+    pub supplier_usdc_account: Account<'info, TokenAccount>,
+    
+    /// Program's USDC treasury account
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, config.usdc_mint.as_ref()],
+        bump,
+        token::mint = config.usdc_mint
+    )]
+    pub treasury_usdc_account: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub supplier: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Lend USDC to the treasury for borrowers to draw on
+pub fn handler(ctx: Context<SupplyUsdc>, amount: u64) -> Result<()> {
+    require!(amount > 0, CreditError::AmountTooSmall);
+    
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.supplier_usdc_account.to_account_info(),
+        to: ctx.accounts.treasury_usdc_account.to_account_info(),
+        authority: ctx.accounts.supplier.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::transfer(CpiContext::new(cpi_program, cpi_accounts), amount)?;
+    
+    let supplier_position = &mut ctx.accounts.supplier_position;
+    supplier_position.owner = ctx.accounts.supplier.key();
+    supplier_position.supplied_usdc = supplier_position.supplied_usdc
+        .checked_add(amount)
+        .ok_or(CreditError::MathOverflow)?;
+    supplier_position.last_update_timestamp = Clock::get()?.unix_timestamp;
+    
+    let config = &mut ctx.accounts.config;
+    config.total_supplied_usdc = config.total_supplied_usdc
+        .checked_add(amount)
+        .ok_or(CreditError::MathOverflow)?;
+    
+    msg!("Supplied {} USDC from {}, total supplied: {}", amount, supplier_position.owner, config.total_supplied_usdc);
+    
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::{Config, UserPosition};
+use crate::utils::{get_price_with_fallback, calculate_collateral_value};
+
+#[derive(Accounts)]
+pub struct TriggerAutoDeleverage<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, user_position.owner.as_ref()],
+        bump,
+        constraint = user_position.is_initialized @ CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+    
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// Pyth oracle for SOL/USD price
+    /// CHECK: Validated in handler
+    pub sol_usd_oracle: UncheckedAccount<'info>,
+}
+
+/// Permissionless: once a position's health factor falls below its opted-in
+/// threshold, repay debt from the owner's buffer until health is back at the
+/// threshold (or the buffer runs out)
+pub fn handler(ctx: Context<TriggerAutoDeleverage>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let user_position = &mut ctx.accounts.user_position;
+    let clock = Clock::get()?;
+    
+    // Buffer repayments are repays, so they follow the repay pause only
+    require!(!config.repays_paused(), CreditError::ProtocolPaused);
+    require!(user_position.owes_usdc(), CreditError::DebtMintMismatch);
+    
+    let threshold_bps = user_position.auto_deleverage_threshold_bps;
+    require!(
+        threshold_bps > 0 && user_position.deleverage_buffer_usdc > 0,
+        CreditError::AutoDeleverageNotTriggered
+    );
+    
+    config.accrue_interest(clock.unix_timestamp)?;
+    let current_debt = user_position.settle_interest(config, clock.unix_timestamp)?;
+    
+    config.check_oracle(&user_position.collateral_mint, ctx.accounts.sol_usd_oracle.key)?;
+    
+    // Keepers act on the same price bound as liquidations
+    let sol_price = get_price_with_fallback(
+        &ctx.accounts.sol_usd_oracle.to_account_info(),
+        &clock,
+        config.liquidation_max_staleness_slots,
+        config.expected_quote_decimals(&ctx.accounts.sol_usd_oracle.key())
+    )?
+    .with_ema(config.uses_ema_for(EMA_FOR_LIQUIDATIONS))?
+    .price;
+    
+    let health_factor = user_position.calculate_health_factor(
+        sol_price,
+        config.liquidation_threshold_bps,
+        current_debt
+    )?;
+    require!(
+        health_factor < threshold_bps as u64,
+        CreditError::AutoDeleverageNotTriggered
+    );
+    
+    // Debt at which the health factor is exactly the threshold
+    let collateral_value = calculate_collateral_value(user_position.collateral_amount, sol_price)?;
+    let target_debt = (collateral_value as u128)
+        .checked_mul(config.liquidation_threshold_bps as u128)
+        .ok_or(CreditError::MathOverflow)?
+        .checked_div(threshold_bps as u128)
+        .ok_or(CreditError::MathOverflow)? as u64;
+    
+    let repay_amount = current_debt
+        .saturating_sub(target_debt)
+        .min(user_position.deleverage_buffer_usdc);
+    
+    // The buffer already sits in the treasury, so repaying is pure accounting
+    user_position.debt_usdc = current_debt - repay_amount;
+    user_position.deleverage_buffer_usdc -= repay_amount;
+    user_position.lifetime_repayments = user_position.lifetime_repayments
+        .checked_add(repay_amount)
+        .ok_or(CreditError::MathOverflow)?;
+    user_position.last_update_slot = clock.slot;
+    user_position.last_update_timestamp = clock.unix_timestamp;
+    
+    config.total_debt_usdc = config.total_debt_usdc.saturating_sub(repay_amount);
+    config.total_deleverage_buffers_usdc = config.total_deleverage_buffers_usdc.saturating_sub(repay_amount);
+    
+    let new_health_factor = user_position.calculate_health_factor(
+        sol_price,
+        config.liquidation_threshold_bps,
+        user_position.debt_usdc
+    )?;
+    user_position.last_health_factor = new_health_factor;
+    user_position.last_health_timestamp = clock.unix_timestamp;
+    
+    msg!(
+        "Auto-deleveraged {}: repaid {} USDC from buffer, health factor {} -> {}",
+        user_position.owner,
+        repay_amount,
+        health_factor,
+        new_health_factor
+    );
+    
+    emit!(AutoDeleverageTriggered {
+        user: user_position.owner,
+        event_seq: user_position.next_event_seq(),
+        webhook_id: user_position.webhook_id,
+        repay_amount,
+        remaining_buffer: user_position.deleverage_buffer_usdc,
+        health_factor_before: health_factor,
+        health_factor_after: new_health_factor,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct AutoDeleverageTriggered {
+    pub user: Pubkey,
+    pub event_seq: u64,
+    pub webhook_id: [u8; 32],
+    pub repay_amount: u64,
+    pub remaining_buffer: u64,
+    pub health_factor_before: u64,
+    pub health_factor_after: u64,
+    pub timestamp: i64,
+}
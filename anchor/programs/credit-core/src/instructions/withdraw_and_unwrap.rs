@@ -0,0 +1,148 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer};
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::{Config, UserPosition};
+use super::withdraw_collateral::record_withdrawal;
+
+#[derive(Accounts)]
+pub struct WithdrawAndUnwrap<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, owner.key().as_ref()],
+        bump,
+        constraint = user_position.owner == owner.key() @ CreditError::Unauthorized,
+        constraint = user_position.is_initialized @ CreditError::PositionNotInitialized,
+        constraint = user_position.collateral_mint == config.wsol_mint @ CreditError::InvalidCollateralMint
+    )]
+    pub user_position: Account<'info, UserPosition>,
+    
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// User's WSOL account receiving the part kept wrapped
+    #[account(
+        mut,
+        constraint = user_wsol_account.owner == owner.key() @ CreditError::Unauthorized,
+        constraint = user_wsol_account.mint == config.wsol_mint @ CreditError::InvalidCollateralMint
+    )]
+    pub user_wsol_account: Account<'info, TokenAccount>,
+    
+    /// Short-lived WSOL account the unwrapped part passes through; closed to the
+    /// owner before the instruction ends, which delivers it as native SOL
+    #[account(
+        init,
+        payer = owner,
+        seeds = [UNWRAP_SEED, owner.key().as_ref()],
+        bump,
+        token::mint = wsol_mint,
+        token::authority = owner
+    )]
+    pub unwrap_account: Account<'info, TokenAccount>,
+    
+    /// Program's WSOL vault
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, config.wsol_mint.as_ref()],
+        bump,
+        token::mint = config.wsol_mint,
+        token::authority = vault_authority,
+        constraint = vault_wsol.delegate.is_none() @ CreditError::VaultDelegateSet
+    )]
+    pub vault_wsol: Account<'info, TokenAccount>,
+    
+    /// PDA authority for the vault
+    /// CHECK: This is the PDA that has authority over the vault
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    #[account(address = config.wsol_mint @ CreditError::InvalidCollateralMint)]
+    pub wsol_mint: Account<'info, Mint>,
+    
+    /// Pyth oracle for SOL/USD price
+    /// CHECK: Validated in handler
+    pub sol_usd_oracle: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Withdraw `amount` of WSOL collateral, delivering `unwrap_amount` of it as
+/// native SOL and the rest to the owner's WSOL account
+pub fn handler(ctx: Context<WithdrawAndUnwrap>, amount: u64, unwrap_amount: u64) -> Result<()> {
+    require!(unwrap_amount <= amount, CreditError::InvalidParameter);
+    
+    record_withdrawal(
+        &mut ctx.accounts.config,
+        &mut ctx.accounts.user_position,
+        &ctx.accounts.sol_usd_oracle.to_account_info(),
+        ctx.remaining_accounts.first(),
+        amount
+    )?;
+    
+    let vault_authority_bump = ctx.bumps.vault_authority;
+    let vault_authority_seeds = &[VAULT_AUTHORITY_SEED, &[vault_authority_bump]];
+    let signer_seeds = &[&vault_authority_seeds[..]];
+    
+    // Part kept wrapped
+    let wrapped_amount = amount - unwrap_amount;
+    if wrapped_amount > 0 {
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault_wsol.to_account_info(),
+                    to: ctx.accounts.user_wsol_account.to_account_info(),
+                    authority: ctx.accounts.vault_authority.to_account_info(),
+                },
+                signer_seeds
+            ),
+            wrapped_amount
+        )?;
+    }
+    
+    // Part unwrapped: closing the WSOL account pays out its lamports (the
+    // withdrawn SOL plus the rent the owner just paid) as native SOL
+    if unwrap_amount > 0 {
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault_wsol.to_account_info(),
+                    to: ctx.accounts.unwrap_account.to_account_info(),
+                    authority: ctx.accounts.vault_authority.to_account_info(),
+                },
+                signer_seeds
+            ),
+            unwrap_amount
+        )?;
+    }
+    token::close_account(CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.unwrap_account.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        }
+    ))?;
+    
+    msg!(
+        "Withdrew {} WSOL for {}: {} unwrapped to SOL, {} kept wrapped",
+        amount,
+        ctx.accounts.owner.key(),
+        unwrap_amount,
+        wrapped_amount
+    );
+    
+    Ok(())
+}
@@ -0,0 +1,233 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::constants::*;
+use crate::state::{Config, UserPosition};
+use crate::utils::{get_price_with_fallback, calculate_collateral_value, calculate_max_borrow};
+
+#[derive(Accounts)]
+pub struct WithdrawCollateral<'info> {
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, owner.key().as_ref()],
+        bump,
+        constraint = user_position.owner == owner.key() @ crate::errors::CreditError::Unauthorized,
+        constraint = user_position.is_initialized @ crate::errors::CreditError::PositionNotInitialized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+    
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// User's collateral token account
+    #[account(
+        mut,
+        constraint = user_collateral_account.owner == owner.key() @ crate::errors::CreditError::Unauthorized,
+        constraint = user_collateral_account.mint == user_position.collateral_mint @ crate::errors::CreditError::InvalidCollateralMint
+    )]
+    pub user_collateral_account: Account<'info, TokenAccount>,
+    
+    /// Optional destination in place of the owner's account (e.g. a cold wallet)
+    #[account(
+        mut,
+        constraint = recipient_collateral_account.key() != vault_collateral_account.key() @ crate::errors::CreditError::ReceivingAccountIsVault,
+        constraint = recipient_collateral_account.mint == user_position.collateral_mint @ crate::errors::CreditError::InvalidCollateralMint
+    )]
+    pub recipient_collateral_account: Option<Account<'info, TokenAccount>>,
+    
+    /// Program's vault for the position's collateral mint
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user_position.collateral_mint.as_ref()],
+        bump,
+        token::mint = user_position.collateral_mint,
+        token::authority = vault_authority,
+        constraint = vault_collateral_account.delegate.is_none() @ crate::errors::CreditError::VaultDelegateSet
+    )]
+    pub vault_collateral_account: Account<'info, TokenAccount>,
+    
+    /// PDA authority for the vault
+    /// CHECK: This is the PDA that has authority over the vault
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    /// Pyth oracle for SOL/USD price
+    /// CHECK: Validated in handler
+    pub sol_usd_oracle: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Withdraw collateral to the owner's account, or to `recipient_collateral_account`
+/// when one is passed (the owner still signs)
+pub fn handler(ctx: Context<WithdrawCollateral>, amount: u64) -> Result<()> {
+    record_withdrawal(
+        &mut ctx.accounts.config,
+        &mut ctx.accounts.user_position,
+        &ctx.accounts.sol_usd_oracle.to_account_info(),
+        ctx.remaining_accounts.first(),
+        amount
+    )?;
+    
+    // Transfer collateral from vault to user
+    let vault_authority_bump = ctx.bumps.vault_authority;
+    let vault_authority_seeds = &[VAULT_AUTHORITY_SEED, &[vault_authority_bump]];
+    let signer_seeds = &[&vault_authority_seeds[..]];
+    let destination = match &ctx.accounts.recipient_collateral_account {
+        Some(recipient) => recipient.to_account_info(),
+        None => ctx.accounts.user_collateral_account.to_account_info(),
+    };
+    
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.vault_collateral_account.to_account_info(),
+        to: destination.clone(),
+        authority: ctx.accounts.vault_authority.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, amount)?;
+    
+    msg!("Withdrew {} collateral for user: {} to {}", amount, ctx.accounts.owner.key(), destination.key());
+    
+    Ok(())
+}
+
+/// Check and account for a collateral withdrawal, leaving the token transfer
+/// out of the vault to the caller
+///
+/// `debt_oracle` prices debt owed in an oracle-priced asset (the first
+/// remaining account of the calling instruction).
+pub(crate) fn record_withdrawal(
+    config: &mut Config,
+    user_position: &mut UserPosition,
+    sol_usd_oracle: &AccountInfo,
+    debt_oracle: Option<&AccountInfo>,
+    amount: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    // Check withdrawals are not paused
+    require!(!config.is_paused_for(PAUSE_WITHDRAWALS), crate::errors::CreditError::ProtocolPaused);
+    
+    // Validate withdrawal amount
+    require!(amount > 0, crate::errors::CreditError::AmountTooSmall);
+    require!(
+        amount <= user_position.collateral_amount, 
+        crate::errors::CreditError::InsufficientCollateral
+    );
+    
+    // Update global interest index (once per instruction)
+    config.accrue_interest(clock.unix_timestamp)?;
+    
+    // Update user's debt with latest interest
+    let current_debt = user_position.settle_interest(config, clock.unix_timestamp)?;
+    
+    // Calculate remaining collateral after withdrawal
+    let remaining_collateral = user_position.collateral_amount
+        .checked_sub(amount)
+        .ok_or(crate::errors::CreditError::MathOverflow)?;
+    
+    config.check_oracle(&user_position.collateral_mint, sol_usd_oracle.key)?;
+    
+    // Fetch the price once; it feeds both the health check and the credit limit
+    let sol_price = get_price_with_fallback(
+        sol_usd_oracle,
+        &clock,
+        config.borrow_max_staleness_slots,
+        config.expected_quote_decimals(&sol_usd_oracle.key())
+    )?
+    .with_ema(config.uses_ema_for(EMA_FOR_WITHDRAWALS))?
+    .collateral_price(config.max_confidence_bps, config.borrow_valuation_mode);
+    
+    // Credit held for linked debit spends becomes debt on settlement, so it
+    // counts against the collateral left behind
+    let committed_debt = current_debt
+        .checked_add(user_position.pending_credit_usdc)
+        .ok_or(crate::errors::CreditError::MathOverflow)?;
+    
+    // If there's debt, check health factor after withdrawal
+    if committed_debt > 0 {
+        // Create temporary position to check health
+        let mut temp_position = user_position.clone();
+        temp_position.collateral_amount = remaining_collateral;
+        
+        let debt_value = user_position.debt_value(
+            config,
+            committed_debt,
+            debt_oracle,
+            &clock,
+            config.borrow_max_staleness_slots
+        )?;
+        let health_factor = temp_position.calculate_health_factor(
+            sol_price,
+            config.liquidation_threshold_bps,
+            debt_value
+        )?;
+        
+        // Require health factor to stay above the configured buffer
+        require!(
+            config.health_after_allowed(None, health_factor),
+            crate::errors::CreditError::HealthFactorTooLow
+        );
+    }
+    
+    // Update position
+    user_position.collateral_amount = remaining_collateral;
+    user_position.last_update_slot = clock.slot;
+    user_position.last_update_timestamp = clock.unix_timestamp;
+    
+    // Update global collateral tracking
+    config.total_collateral = config.total_collateral
+        .checked_sub(amount)
+        .ok_or(crate::errors::CreditError::MathOverflow)?;
+    
+    // Update credit limit from the price fetched above
+    let collateral_value_usd = calculate_collateral_value(remaining_collateral, sol_price)?;
+    user_position.update_credit_limit(
+        calculate_max_borrow(collateral_value_usd, config.credit_limit_ltv_bps())?,
+        CREDIT_LIMIT_WITHDRAWAL,
+        sol_price,
+        clock.unix_timestamp
+    );
+    
+    msg!("Remaining collateral: {}, Credit limit: {} USDC", 
+        user_position.collateral_amount, 
+        user_position.credit_limit
+    );
+    
+    emit!(CollateralWithdrawn {
+        user: user_position.owner,
+        event_seq: user_position.next_event_seq(),
+        webhook_id: user_position.webhook_id,
+        amount,
+        remaining_collateral: user_position.collateral_amount,
+        credit_limit: user_position.credit_limit,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    #[cfg(feature = "verbose-state")]
+    user_position.log_state(Some(sol_price), config.liquidation_threshold_bps);
+    
+    Ok(())
+}
+
+#[event]
+pub struct CollateralWithdrawn {
+    pub user: Pubkey,
+    pub event_seq: u64,
+    pub webhook_id: [u8; 32],
+    pub amount: u64,
+    pub remaining_collateral: u64,
+    pub credit_limit: u64,
+    pub timestamp: i64,
+} 
\ No newline at end of file
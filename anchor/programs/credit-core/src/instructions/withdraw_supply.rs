@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::constants::*;
+use crate::errors::CreditError;
+use crate::state::{Config, SupplierPosition};
+
+#[derive(Accounts)]
+pub struct WithdrawSupply<'info> {
+    #[account(
+        mut,
+        seeds = [SUPPLIER_POSITION_SEED, supplier.key().as_ref()],
+        bump,
+        constraint = supplier_position.owner == supplier.key() @ CreditError::Unauthorized
+    )]
+    pub supplier_position: Account<'info, SupplierPosition>,
+    
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = !config.is_paused_for(PAUSE_WITHDRAWALS) @ CreditError::ProtocolPaused
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// Supplier's USDC token account
+    #[account(
+        mut,
+        constraint = supplier_usdc_account.owner == supplier.key() @ CreditError::Unauthorized,
+        constraint = supplier_usdc_account.mint == config.usdc_mint @ CreditError::InvalidMint
+    )]
+    pub supplier_usdc_account: Account<'info, TokenAccount>,
+    
+    /// Program's USDC treasury account
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, config.usdc_mint.as_ref()],
+        bump,
+        token::mint = config.usdc_mint,
+        token::authority = vault_authority
+    )]
+    pub treasury_usdc_account: Account<'info, TokenAccount>,
+    
+    /// PDA authority for the treasury
+    /// CHECK: This is the PDA that has authority over the treasury
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    pub supplier: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+/// Withdraw supplied USDC, down to the treasury's reserve floor
+///
+/// A request larger than the liquidity above the floor is partially filled;
+/// one that cannot withdraw anything fails.
+pub fn handler(ctx: Context<WithdrawSupply>, amount: u64) -> Result<()> {
+    require!(amount > 0, CreditError::AmountTooSmall);
+    
+    let config = &ctx.accounts.config;
+    let requested = amount.min(ctx.accounts.supplier_position.supplied_usdc);
+    let available = config
+        .lendable_liquidity(&config.usdc_mint, ctx.accounts.treasury_usdc_account.amount)?
+        .saturating_sub(config.reserve_floor_usdc()?);
+    let withdraw_amount = requested.min(available);
+    require!(withdraw_amount > 0, CreditError::ReserveRatioBreached);
+    
+    let vault_authority_bump = ctx.bumps.vault_authority;
+    let vault_authority_seeds = &[VAULT_AUTHORITY_SEED, &[vault_authority_bump]];
+    let signer_seeds = &[&vault_authority_seeds[..]];
+    
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.treasury_usdc_account.to_account_info(),
+        to: ctx.accounts.supplier_usdc_account.to_account_info(),
+        authority: ctx.accounts.vault_authority.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds), withdraw_amount)?;
+    
+    let supplier_position = &mut ctx.accounts.supplier_position;
+    supplier_position.supplied_usdc -= withdraw_amount;
+    supplier_position.last_update_timestamp = Clock::get()?.unix_timestamp;
+    
+    let config = &mut ctx.accounts.config;
+    config.total_supplied_usdc = config.total_supplied_usdc.saturating_sub(withdraw_amount);
+    
+    if withdraw_amount < amount {
+        msg!("Withdrawal of {} USDC capped at {} by the reserve floor", amount, withdraw_amount);
+    }
+    msg!("Withdrew {} USDC supply for {}", withdraw_amount, supplier_position.owner);
+    
+    Ok(())
+}
@@ -2,6 +2,8 @@ use anchor_lang::prelude::*;
 
 declare_id!("DzAXxi4XR4wc8ywFXXHfckEPx1neccaRWDjv7o4CCtE4");
 
+#[cfg(feature = "client")]
+pub mod client;
 pub mod constants;
 pub mod errors;
 pub mod instructions;
@@ -10,6 +12,7 @@ pub mod state;
 pub mod utils;
 
 use instructions::*;
+use state::DebitTierLimits;
 
 #[program]
 pub mod credit_core {
@@ -26,8 +29,12 @@ pub mod credit_core {
     }
 
     /// Deposit collateral (WSOL) into user's position
-    pub fn deposit_collateral_wsol(ctx: Context<DepositCollateralWsol>, amount: u64) -> Result<()> {
-        instructions::deposit_collateral_wsol::handler(ctx, amount)
+    pub fn deposit_collateral_wsol(
+        ctx: Context<DepositCollateralWsol>,
+        amount: u64,
+        client_version: u8,
+    ) -> Result<()> {
+        instructions::deposit_collateral_wsol::handler(ctx, amount, client_version)
     }
 
     /// Record debt for a user (for testing/admin)
@@ -35,12 +42,33 @@ pub mod credit_core {
         instructions::record_debt::handler(ctx, usdc_amount)
     }
 
+    /// Borrow USDC from the treasury against deposited collateral
+    /// (multi-collateral slot oracles in remaining accounts)
+    pub fn borrow_usdc<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BorrowUsdc<'info>>,
+        amount_usdc: u64,
+        client_version: u8,
+    ) -> Result<()> {
+        instructions::borrow_usdc::handler(ctx, amount_usdc, client_version)
+    }
+
     /// Repay USDC debt (simplified version)
-    pub fn repay_usdc(ctx: Context<RepayUsdc>, usdc_amount: u64) -> Result<()> {
-        instructions::repay_usdc::handler(ctx, usdc_amount)
+    pub fn repay_usdc(ctx: Context<RepayUsdc>, usdc_amount: u64, repay_all: bool) -> Result<()> {
+        instructions::repay_usdc::handler(ctx, usdc_amount, repay_all)
+    }
+
+    /// Withdraw collateral while keeping the position above the health buffer
+    pub fn withdraw_collateral(ctx: Context<WithdrawCollateral>, amount: u64) -> Result<()> {
+        instructions::withdraw_collateral::handler(ctx, amount)
     }
 
-    /// Admin function to update protocol parameters
+    /// Liquidate an unhealthy position (repay debt, seize collateral plus bonus);
+    /// the protocol liquidator repays from reserves instead
+    pub fn liquidate(ctx: Context<Liquidate>, repay_amount: u64) -> Result<()> {
+        instructions::liquidate::handler(ctx, repay_amount)
+    }
+
+    /// Admin function to update protocol parameters (queued while a params timelock is set)
     pub fn admin_set_params(ctx: Context<AdminSetParams>, params: UpdateParams) -> Result<()> {
         instructions::admin_set_params::handler(ctx, params)
     }
@@ -49,4 +77,284 @@ pub mod credit_core {
     pub fn admin_set_paused(ctx: Context<AdminSetPaused>, paused: bool) -> Result<()> {
         instructions::admin_set_paused::handler(ctx, paused)
     }
+
+    /// Admin function to create a program vault for a protocol mint
+    pub fn init_vault(ctx: Context<InitVault>) -> Result<()> {
+        instructions::init_vault::handler(ctx)
+    }
+
+    /// Recompute a position's credit limit and cached health factor (permissionless),
+    /// returning the health factor in bps (10000 = 1.0)
+    pub fn refresh_position<'info>(ctx: Context<'_, '_, 'info, 'info, RefreshPosition<'info>>) -> Result<u64> {
+        instructions::refresh_position::handler(ctx)
+    }
+
+    /// Deposit WSOL collateral from multiple token accounts (pairs in remaining accounts)
+    pub fn deposit_collateral_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DepositCollateralBatch<'info>>,
+        amounts: Vec<u64>,
+        client_version: u8,
+    ) -> Result<()> {
+        instructions::deposit_collateral_batch::handler(ctx, amounts, client_version)
+    }
+
+    /// Admin function to lower the interest rate ceiling, or queue/apply a timelocked raise
+    pub fn admin_set_max_interest_rate(ctx: Context<AdminSetMaxInterestRate>, max_interest_rate_bps: u16) -> Result<()> {
+        instructions::admin_set_max_interest_rate::handler(ctx, max_interest_rate_bps)
+    }
+
+    /// Admin function to pause individual operations; returns the new pause flags
+    pub fn admin_set_pause_flags(ctx: Context<AdminSetPauseFlags>, pause_flags: u8) -> Result<u8> {
+        instructions::admin_set_pause_flags::handler(ctx, pause_flags)
+    }
+
+    /// Accrue interest into the global borrow index and mark healthy collateral oracles (permissionless)
+    pub fn accrue_interest(ctx: Context<AccrueInterest>) -> Result<()> {
+        instructions::accrue_interest::handler(ctx)
+    }
+
+    /// Admin function to write off the remaining debt of a position with no collateral
+    pub fn admin_socialize_bad_debt(ctx: Context<AdminSocializeBadDebt>) -> Result<()> {
+        instructions::admin_socialize_bad_debt::handler(ctx)
+    }
+
+    /// Admin function to close an empty position whose debt was socialized
+    pub fn admin_close_bad_debt_position(ctx: Context<AdminCloseBadDebtPosition>) -> Result<()> {
+        instructions::admin_close_bad_debt_position::handler(ctx)
+    }
+
+    /// Admin function to register a collateral mint or update its parameters
+    pub fn admin_set_collateral_type(
+        ctx: Context<AdminSetCollateralType>,
+        mint: Pubkey,
+        params: CollateralTypeParams,
+    ) -> Result<()> {
+        instructions::admin_set_collateral_type::handler(ctx, mint, params)
+    }
+
+    /// Move a position from a migrated-away jitoSOL mint to the current one
+    pub fn migrate_position_mint(ctx: Context<MigratePositionMint>) -> Result<()> {
+        instructions::migrate_position_mint::handler(ctx)
+    }
+
+    /// Admin function to add USDC to protocol reserves
+    pub fn admin_fund_reserves(ctx: Context<AdminFundReserves>, amount: u64) -> Result<()> {
+        instructions::admin_fund_reserves::handler(ctx, amount)
+    }
+
+    /// Admin function to subsidize a position's interest from reserves until an expiry
+    pub fn admin_set_subsidy(ctx: Context<AdminSetSubsidy>, subsidy_bps: u16, expiry_timestamp: i64) -> Result<()> {
+        instructions::admin_set_subsidy::handler(ctx, subsidy_bps, expiry_timestamp)
+    }
+
+    /// Repay USDC debt on multiple positions (positions in remaining accounts)
+    pub fn repay_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RepayBatch<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        instructions::repay_batch::handler(ctx, amounts)
+    }
+
+    /// Opt in to (or out of) auto-deleverage and top up the position's repay buffer
+    pub fn configure_auto_deleverage(ctx: Context<ConfigureAutoDeleverage>, threshold_bps: u16, buffer_deposit: u64) -> Result<()> {
+        instructions::configure_auto_deleverage::handler(ctx, threshold_bps, buffer_deposit)
+    }
+
+    /// Repay debt from a position's buffer once it breaches its threshold (permissionless)
+    pub fn trigger_auto_deleverage(ctx: Context<TriggerAutoDeleverage>) -> Result<()> {
+        instructions::trigger_auto_deleverage::handler(ctx)
+    }
+
+    /// Deposit any registered collateral into the position's multi-collateral slots
+    pub fn deposit_multi_collateral(ctx: Context<DepositMultiCollateral>, amount: u64) -> Result<()> {
+        instructions::deposit_multi_collateral::handler(ctx, amount)
+    }
+
+    /// Liquidate a multi-collateral position in collateral priority order (slot accounts in remaining accounts)
+    pub fn liquidate_multi<'info>(
+        ctx: Context<'_, '_, 'info, 'info, LiquidateMulti<'info>>,
+        repay_amount: u64,
+    ) -> Result<()> {
+        instructions::liquidate_multi::handler(ctx, repay_amount)
+    }
+
+    /// Supply USDC to the treasury
+    pub fn supply_usdc(ctx: Context<SupplyUsdc>, amount: u64) -> Result<()> {
+        instructions::supply_usdc::handler(ctx, amount)
+    }
+
+    /// Withdraw supplied USDC, capped by the treasury reserve floor
+    pub fn withdraw_supply(ctx: Context<WithdrawSupply>, amount: u64) -> Result<()> {
+        instructions::withdraw_supply::handler(ctx, amount)
+    }
+
+    /// Admin function to move stray non-protocol tokens out of a program-owned account
+    pub fn admin_recover_token(ctx: Context<AdminRecoverToken>, amount: u64) -> Result<()> {
+        instructions::admin_recover_token::handler(ctx, amount)
+    }
+
+    /// View: largest collateral withdrawal that keeps the minimum borrow health factor
+    pub fn max_withdrawable(ctx: Context<MaxWithdrawable>) -> Result<u64> {
+        instructions::max_withdrawable::handler(ctx)
+    }
+
+    /// Deposit USDC into the caller's debit account, opening it on the basic tier
+    pub fn debit_deposit(ctx: Context<DebitDeposit>, amount: u64) -> Result<()> {
+        instructions::debit_deposit::handler(ctx, amount)
+    }
+
+    /// Admin function to assign a debit tier and apply its default limits
+    pub fn admin_set_debit_tier(ctx: Context<AdminSetDebitTier>, tier: u8) -> Result<()> {
+        instructions::admin_set_debit_tier::handler(ctx, tier)
+    }
+
+    /// Admin function to set a debit tier's default limits
+    pub fn admin_set_debit_tier_config(
+        ctx: Context<AdminSetDebitTierConfig>,
+        tier: u8,
+        limits: DebitTierLimits,
+    ) -> Result<()> {
+        instructions::admin_set_debit_tier_config::handler(ctx, tier, limits)
+    }
+
+    /// Permissionless keeper call to warn of a position nearing liquidation
+    pub fn emit_liquidation_warning(ctx: Context<EmitLiquidationWarning>) -> Result<()> {
+        instructions::emit_liquidation_warning::handler(ctx)
+    }
+
+    /// Admin function to reserve debit funds for an authorized card spend
+    pub fn debit_spend(ctx: Context<DebitSpend>, amount: u64) -> Result<()> {
+        instructions::debit_spend::handler(ctx, amount)
+    }
+
+    /// Admin function to settle a reserved debit spend against the account's limits
+    pub fn debit_settle(ctx: Context<DebitSettle>, amount: u64) -> Result<()> {
+        instructions::debit_settle::handler(ctx, amount)
+    }
+
+    /// Admin function to override a debit account's limits, optionally resetting spend
+    pub fn admin_set_debit_limits(ctx: Context<AdminSetDebitLimits>, params: DebitLimitsParams) -> Result<()> {
+        instructions::admin_set_debit_limits::handler(ctx, params)
+    }
+
+    /// View: versioned config snapshot with rate, utilization and index accrued to now
+    pub fn get_config(ctx: Context<GetConfig>) -> Result<ConfigView> {
+        instructions::get_config::handler(ctx)
+    }
+
+    /// Emergency admin function to correct the global borrow index (requires confirmation)
+    pub fn admin_set_borrow_index(ctx: Context<AdminSetBorrowIndex>, new_index: u128, confirm: bool) -> Result<()> {
+        instructions::admin_set_borrow_index::handler(ctx, new_index, confirm)
+    }
+
+    /// Admin function to require (or stop requiring) a price feed's quote decimals
+    pub fn admin_set_oracle_decimals(
+        ctx: Context<AdminSetOracleDecimals>,
+        oracle: Pubkey,
+        expected_quote_decimals: Option<u8>,
+    ) -> Result<()> {
+        instructions::admin_set_oracle_decimals::handler(ctx, oracle, expected_quote_decimals)
+    }
+
+    /// Admin function to reset total collateral from the vault balances (vaults in remaining accounts)
+    pub fn reconcile_collateral<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReconcileCollateral<'info>>,
+    ) -> Result<()> {
+        instructions::reconcile_collateral::handler(ctx)
+    }
+
+    /// Set the identifier position events carry for the owner's notifications
+    pub fn set_webhook_id(ctx: Context<SetWebhookId>, webhook_id: [u8; 32]) -> Result<()> {
+        instructions::set_webhook_id::handler(ctx, webhook_id)
+    }
+
+    /// Withdraw WSOL collateral, delivering part or all of it as native SOL
+    pub fn withdraw_and_unwrap(ctx: Context<WithdrawAndUnwrap>, amount: u64, unwrap_amount: u64) -> Result<()> {
+        instructions::withdraw_and_unwrap::handler(ctx, amount, unwrap_amount)
+    }
+
+    /// Liquidate exactly enough debt to bring an unhealthy position to a target health factor
+    pub fn liquidate_to_target(ctx: Context<Liquidate>, target_health_bps: u64) -> Result<()> {
+        instructions::liquidate_to_target::handler(ctx, target_health_bps)
+    }
+
+    /// Repay debt by selling collateral to protocol reserves at the oracle price
+    pub fn repay_with_collateral(ctx: Context<RepayWithCollateral>, collateral_amount: u64) -> Result<()> {
+        instructions::repay_with_collateral::handler(ctx, collateral_amount)
+    }
+
+    /// Admin function to register a borrowable debt asset and set its price feed
+    pub fn admin_set_debt_asset(ctx: Context<AdminSetDebtAsset>, oracle: Pubkey) -> Result<()> {
+        instructions::admin_set_debt_asset::handler(ctx, oracle)
+    }
+
+    /// Borrow a registered debt asset instead of USDC (asset oracle in remaining accounts)
+    pub fn borrow_asset<'info>(ctx: Context<'_, '_, 'info, 'info, BorrowAsset<'info>>, amount: u64) -> Result<()> {
+        instructions::borrow_asset::handler(ctx, amount)
+    }
+
+    /// Repay debt owed in a registered debt asset
+    pub fn repay_asset(ctx: Context<RepayAsset>, amount: u64) -> Result<()> {
+        instructions::repay_asset::handler(ctx, amount)
+    }
+
+    /// Set the health factor at which keepers sell the position's collateral to repay debt
+    pub fn configure_stop_loss(ctx: Context<ConfigureStopLoss>, stop_loss_health_bps: u16) -> Result<()> {
+        instructions::configure_stop_loss::handler(ctx, stop_loss_health_bps)
+    }
+
+    /// Permissionless: sell collateral to reserves for a position below its stop-loss
+    pub fn execute_stop_loss(ctx: Context<ExecuteStopLoss>) -> Result<()> {
+        instructions::execute_stop_loss::handler(ctx)
+    }
+
+    /// Permissionless: flag (or clear) a position below the borrow buffer, blocking new borrows
+    pub fn enforce_buffer(ctx: Context<EnforceBuffer>) -> Result<bool> {
+        instructions::enforce_buffer::handler(ctx)
+    }
+
+    /// Deposit WSOL and borrow USDC against it, sized at the pessimistic end of the oracle band
+    pub fn deposit_and_borrow(
+        ctx: Context<DepositAndBorrow>,
+        deposit_amount: u64,
+        max_borrow_usdc: u64,
+        client_version: u8,
+    ) -> Result<u64> {
+        instructions::deposit_and_borrow::handler(ctx, deposit_amount, max_borrow_usdc, client_version)
+    }
+
+    /// View: collateral price at which the position's health factor reaches 1.0 (0 = no debt)
+    pub fn get_liquidation_price(ctx: Context<GetLiquidationPrice>) -> Result<u64> {
+        instructions::get_liquidation_price::handler(ctx)
+    }
+
+    /// Commit parameter changes queued by admin_set_params once their timelock elapses
+    pub fn apply_pending_params(ctx: Context<ApplyPendingParams>) -> Result<()> {
+        instructions::apply_pending_params::handler(ctx)
+    }
+
+    /// Deposit WSOL collateral, creating the caller's position first if needed
+    pub fn init_and_deposit(ctx: Context<InitAndDeposit>, amount: u64, client_version: u8) -> Result<()> {
+        instructions::init_and_deposit::handler(ctx, amount, client_version)
+    }
+
+    /// Emergency: permanently block deposits, borrows and liquidations, leaving only exits
+    pub fn admin_shutdown(ctx: Context<AdminShutdown>, confirmation: [u8; 8]) -> Result<()> {
+        instructions::admin_shutdown::handler(ctx, confirmation)
+    }
+
+    /// View: lifetime protocol fees by source and the current USDC reserves
+    pub fn get_fee_breakdown(ctx: Context<GetFeeBreakdown>) -> Result<FeeBreakdown> {
+        instructions::get_fee_breakdown::handler(ctx)
+    }
+
+    /// Link the caller's credit position to cover debit spends beyond the balance
+    pub fn link_credit_position(ctx: Context<LinkCreditPosition>, linked: bool) -> Result<()> {
+        instructions::link_credit_position::handler(ctx, linked)
+    }
+
+    /// Merge the position of a second wallet the caller controls into their own
+    pub fn merge_positions(ctx: Context<MergePositions>) -> Result<()> {
+        instructions::merge_positions::handler(ctx)
+    }
 }
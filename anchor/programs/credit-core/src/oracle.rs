@@ -1,15 +1,170 @@
 use anchor_lang::prelude::*;
+use crate::constants::{MAX_ORACLE_EXPONENT_ADJUSTMENT, USDC_DECIMALS, VALUATION_ASK, VALUATION_BID, VALUATION_MID};
 use crate::errors::CreditError;
 
+/// Pyth oracle program (devnet)
+pub const PYTH_PROGRAM_ID: &str = "gSbePebfvPy7tRqimPoVecS2UsBvYv46ynrzWocc92s";
+
+/// Pyth account header: magic number, layout version and account type, the
+/// only layout the fixed offsets below are valid for
+const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+const PYTH_VERSION_2: u32 = 2;
+const PYTH_ACCOUNT_TYPE_PRICE: u32 = 3;
+
+/// Aggregate price read from a Pyth price account
+#[derive(Clone, Copy, Debug)]
+pub struct PythPrice {
+    /// Price in USDC decimals (6)
+    pub price: u64,
+    
+    /// Confidence interval in USDC decimals (6)
+    pub confidence: u64,
+    
+    /// Slot the aggregate price was published in
+    pub publish_slot: u64,
+    
+    /// Exponentially-weighted moving average price in USDC decimals (6)
+    pub ema_price: u64,
+    
+    /// EMA confidence interval in USDC decimals (6)
+    pub ema_confidence: u64,
+}
+
+impl PythPrice {
+    /// Confidence interval relative to price in basis points
+    pub fn confidence_bps(&self) -> u64 {
+        if self.price == 0 {
+            return u64::MAX;
+        }
+        ((self.confidence as u128) * 10_000 / (self.price as u128)) as u64
+    }
+    
+    /// This price with the EMA substituted for the spot aggregate when `use_ema` is set
+    pub fn with_ema(self, use_ema: bool) -> Result<PythPrice> {
+        if !use_ema {
+            return Ok(self);
+        }
+        require!(self.ema_price > 0, CreditError::InvalidOracle);
+        Ok(PythPrice {
+            price: self.ema_price,
+            confidence: self.ema_confidence,
+            ..self
+        })
+    }
+    
+    /// Point of the confidence band selected by a VALUATION_* mode
+    pub fn valued_at(&self, valuation_mode: u8) -> u64 {
+        match valuation_mode {
+            VALUATION_BID => self.price.saturating_sub(self.confidence),
+            VALUATION_ASK => self.price.saturating_add(self.confidence),
+            _ => self.price,
+        }
+    }
+    
+    /// Price to value collateral at for borrow power: the configured point of the
+    /// band, or for mid valuation the midpoint while the confidence band is tight
+    /// and price minus confidence once it is too wide
+    pub fn collateral_price(&self, max_confidence_bps: u16, valuation_mode: u8) -> u64 {
+        if valuation_mode != VALUATION_MID {
+            self.valued_at(valuation_mode)
+        } else if self.confidence_bps() <= max_confidence_bps as u64 {
+            self.price
+        } else {
+            msg!("Oracle confidence too wide, using conservative price");
+            self.price.saturating_sub(self.confidence)
+        }
+    }
+    
+    /// This price carried through an exchange rate quoted in USDC decimals, e.g. a
+    /// jitoSOL/SOL rate of 1.08 (1_080_000) turning a SOL/USD price into jitoSOL/USD
+    ///
+    /// The confidence band widens by both feeds' bands and the older publish slot is kept.
+    pub fn through_rate(self, rate: &PythPrice) -> Result<PythPrice> {
+        require!(rate.price > 0 && rate.ema_price > 0, CreditError::InvalidOracle);
+        
+        let confidence = apply_rate(self.confidence, rate.price)?
+            .checked_add(apply_rate(self.price, rate.confidence)?)
+            .ok_or(CreditError::MathOverflow)?;
+        let ema_confidence = apply_rate(self.ema_confidence, rate.ema_price)?
+            .checked_add(apply_rate(self.ema_price, rate.ema_confidence)?)
+            .ok_or(CreditError::MathOverflow)?;
+        
+        Ok(PythPrice {
+            price: apply_rate(self.price, rate.price)?,
+            confidence,
+            publish_slot: self.publish_slot.min(rate.publish_slot),
+            ema_price: apply_rate(self.ema_price, rate.ema_price)?,
+            ema_confidence,
+        })
+    }
+}
+
+/// Multiply a USDC-decimal value by a rate in USDC decimals
+fn apply_rate(value: u64, rate: u64) -> Result<u64> {
+    let scaled = (value as u128)
+        .checked_mul(rate as u128)
+        .ok_or(CreditError::MathOverflow)?
+        / 10u128.pow(USDC_DECIMALS as u32);
+    u64::try_from(scaled).map_err(|_| error!(CreditError::MathOverflow))
+}
+
+/// Whether the account is owned by the Pyth oracle program
+pub fn is_pyth_account(price_account: &AccountInfo) -> bool {
+    let pyth_program: Pubkey = PYTH_PROGRAM_ID.parse().unwrap();
+    *price_account.owner == pyth_program
+}
+
 /// Custom Pyth price reader - avoiding SDK dependency conflicts
 /// This directly parses Pyth oracle account data
-pub fn get_pyth_price(price_account: &AccountInfo) -> Result<i64> {
-    // Verify account is owned by Pyth (hardcoded devnet address)
-    let pyth_program: Pubkey = "gSbePebfvPy7tRqimPoVecS2UsBvYv46ynrzWocc92s".parse().unwrap();
+///
+/// With `expected_quote_decimals` set, the feed's exponent must imply exactly
+/// that precision rather than being trusted to pick the scale.
+pub fn get_pyth_price(
+    price_account: &AccountInfo,
+    clock: &Clock,
+    max_staleness_slots: u64,
+    expected_quote_decimals: Option<u8>,
+) -> Result<PythPrice> {
+    let price = read_pyth_price(price_account, clock, max_staleness_slots, expected_quote_decimals)?;
     
-    require_keys_eq!(
-        *price_account.owner,
-        pyth_program,
+    // Sanity check: SOL should be between $10 and $10,000
+    require!(
+        price.price > 10_000_000 && price.price < 10_000_000_000,
+        CreditError::InvalidOracle
+    );
+    
+    msg!("Pyth SOL/USD price: ${}", price.price as f64 / 1_000_000.0);
+    
+    Ok(price)
+}
+
+/// Read an exchange-rate feed (e.g. jitoSOL/SOL) in USDC decimals
+///
+/// Same validation as `get_pyth_price` except the SOL/USD sanity bound, which
+/// a rate near 1.0 would never pass.
+pub fn get_pyth_rate(
+    rate_account: &AccountInfo,
+    clock: &Clock,
+    max_staleness_slots: u64,
+    expected_quote_decimals: Option<u8>,
+) -> Result<PythPrice> {
+    let rate = read_pyth_price(rate_account, clock, max_staleness_slots, expected_quote_decimals)?;
+    
+    msg!("Pyth exchange rate: {}", rate.price as f64 / 1_000_000.0);
+    
+    Ok(rate)
+}
+
+/// Parse and validate a Pyth price account, scaled to USDC decimals
+fn read_pyth_price(
+    price_account: &AccountInfo,
+    clock: &Clock,
+    max_staleness_slots: u64,
+    expected_quote_decimals: Option<u8>,
+) -> Result<PythPrice> {
+    // Verify account is owned by Pyth (hardcoded devnet address)
+    require!(
+        is_pyth_account(price_account),
         CreditError::InvalidOracle
     );
     
@@ -17,7 +172,23 @@ pub fn get_pyth_price(price_account: &AccountInfo) -> Result<i64> {
     
     // Minimum size check for Pyth price account
     require!(
-        data.len() >= 216,  // Need at least 216 bytes for price at offset 208
+        data.len() >= 240,  // Need at least 240 bytes for publish slot at offset 232
+        CreditError::InvalidOracle
+    );
+    
+    // Refuse to read offsets out of anything but a V2 price account, so a
+    // layout change upstream fails loudly instead of yielding garbage prices
+    let header_word = |offset: usize| -> Result<u32> {
+        Ok(u32::from_le_bytes(
+            data[offset..offset + 4]
+                .try_into()
+                .map_err(|_| error!(CreditError::InvalidOracle))?
+        ))
+    };
+    require!(
+        header_word(0)? == PYTH_MAGIC
+            && header_word(4)? == PYTH_VERSION_2
+            && header_word(8)? == PYTH_ACCOUNT_TYPE_PRICE,
         CreditError::InvalidOracle
     );
     
@@ -29,12 +200,52 @@ pub fn get_pyth_price(price_account: &AccountInfo) -> Result<i64> {
             .map_err(|_| error!(CreditError::InvalidOracle))?
     );
     
+    let conf_raw = u64::from_le_bytes(
+        data[216..224]
+            .try_into()
+            .map_err(|_| error!(CreditError::InvalidOracle))?
+    );
+    
+    // EMA price at 48 and EMA confidence at 72, in the same exponent
+    let ema_price_raw = i64::from_le_bytes(
+        data[48..56]
+            .try_into()
+            .map_err(|_| error!(CreditError::InvalidOracle))?
+    );
+    
+    let ema_conf_raw = u64::from_le_bytes(
+        data[72..80]
+            .try_into()
+            .map_err(|_| error!(CreditError::InvalidOracle))?
+    );
+    
     let expo = i32::from_le_bytes(
         data[20..24]
             .try_into()
             .map_err(|_| error!(CreditError::InvalidOracle))?
     );
     
+    // Aggregate publish slot (offset 232)
+    let publish_slot = u64::from_le_bytes(
+        data[232..240]
+            .try_into()
+            .map_err(|_| error!(CreditError::InvalidOracle))?
+    );
+    
+    // A feed quoting at another precision than configured is misdescribed, not rescalable
+    if let Some(decimals) = expected_quote_decimals {
+        require!(
+            expo == -(decimals as i32),
+            CreditError::OracleDecimalsMismatch
+        );
+    }
+    
+    // A zero or negative aggregate must never reach the exponent math or valuation
+    require!(
+        price_raw > 0,
+        CreditError::InvalidOracle
+    );
+    
     // Price status (offset 200) - 1 = Trading
     let status = data[200];
     require!(
@@ -42,27 +253,54 @@ pub fn get_pyth_price(price_account: &AccountInfo) -> Result<i64> {
         CreditError::StaleOracle
     );
     
+    // Reject prices older than the caller's staleness bound
+    require!(
+        clock.slot.saturating_sub(publish_slot) <= max_staleness_slots,
+        CreditError::StaleOracle
+    );
+    
     // Convert to USDC price (6 decimals)
-    // price_raw is in 10^expo, we want 10^6
-    let adjustment = 6 - (-expo);  // expo is negative
+    // price_raw is in 10^expo, we want 10^6; a feed needing more than
+    // MAX_ORACLE_EXPONENT_ADJUSTMENT powers of ten either way is rejected
+    let adjustment = expo
+        .checked_add(USDC_DECIMALS as i32)
+        .filter(|adjustment| adjustment.abs() <= MAX_ORACLE_EXPONENT_ADJUSTMENT)
+        .ok_or(error!(CreditError::OracleExponentOutOfRange))?;
+    
+    let price_usdc = scale_to_usdc(price_raw as u64, adjustment)?;
+    
+    let conf_usdc = scale_to_usdc(conf_raw, adjustment)?;
     
-    let price_usdc = if adjustment >= 0 {
-        price_raw
-            .checked_mul(10_i64.pow(adjustment as u32))
+    // A non-positive EMA is kept as 0 and only rejected if a caller selects it
+    let ema_price_usdc = scale_to_usdc(ema_price_raw.max(0) as u64, adjustment)?;
+    let ema_conf_usdc = scale_to_usdc(ema_conf_raw, adjustment)?;
+    
+    Ok(PythPrice {
+        price: price_usdc,
+        confidence: conf_usdc,
+        publish_slot,
+        ema_price: ema_price_usdc,
+        ema_confidence: ema_conf_usdc,
+    })
+}
+
+/// Rescale an unsigned Pyth value by `adjustment` powers of ten to USDC decimals
+///
+/// The arithmetic runs in i128 so no in-range adjustment can overflow before the
+/// result is checked against u64.
+fn scale_to_usdc(value: u64, adjustment: i32) -> Result<u64> {
+    let factor = 10_i128
+        .checked_pow(adjustment.unsigned_abs())
+        .ok_or(error!(CreditError::MathOverflow))?;
+    let scaled = if adjustment >= 0 {
+        (value as i128)
+            .checked_mul(factor)
             .ok_or(error!(CreditError::MathOverflow))?
     } else {
-        price_raw
-            .checked_div(10_i64.pow((-adjustment) as u32))
+        (value as i128)
+            .checked_div(factor)
             .ok_or(error!(CreditError::MathOverflow))?
     };
     
-    // Sanity check: SOL should be between $10 and $10,000
-    require!(
-        price_usdc > 10_000_000 && price_usdc < 10_000_000_000,
-        CreditError::InvalidOracle
-    );
-    
-    msg!("Pyth SOL/USD price: ${}", price_usdc as f64 / 1_000_000.0);
-    
-    Ok(price_usdc)
+    u64::try_from(scaled).map_err(|_| error!(CreditError::MathOverflow))
 }
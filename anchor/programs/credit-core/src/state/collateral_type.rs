@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+
+/// Per-collateral risk parameters, stored in `Config::collateral_types`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct CollateralType {
+    /// Collateral mint (default pubkey = empty slot)
+    pub mint: Pubkey,
+    
+    /// Share of the liquidation bonus kept by the protocol (basis points of the bonus)
+    pub protocol_liquidation_share_bps: u16,
+    
+    /// Seized collateral owned by the protocol, held in the collateral vault (9 decimals)
+    pub reserve_collateral: u64,
+    
+    /// USD price feed for this collateral
+    pub oracle: Pubkey,
+    
+    /// Seizure order in multi-collateral liquidations (lowest is seized first)
+    pub liquidation_priority: u8,
+    
+    /// Last slot a keeper found the oracle fresh and within the confidence bound (0 = never)
+    pub last_healthy_oracle_slot: u64,
+    
+    /// Borrow rate for positions backed by this collateral (basis points, 0 = global rate)
+    pub interest_rate_bps: u16,
+    
+    /// Borrow index for positions backed by this collateral (27 decimals)
+    pub borrow_index: u128,
+    
+    /// Exchange-rate feed against the asset `oracle` prices (e.g. jitoSOL/SOL),
+    /// for collateral that appreciates against it (default pubkey = priced by `oracle` alone)
+    pub exchange_rate_oracle: Pubkey,
+    
+    /// Liquidation bonus for seizing this collateral (basis points, 0 = global bonus)
+    pub liquidation_bonus_bps: u16,
+    
+    /// Loan-to-value counted toward borrow power for this collateral (basis points, 0 = global LTV)
+    pub ltv_bps: u16,
+    
+    /// Mint decimals, used to value slot collateral in USDC terms
+    pub decimals: u8,
+}
+
+impl CollateralType {
+    pub const LEN: usize = 32 + // mint
+        2 + // protocol_liquidation_share_bps
+        8 + // reserve_collateral
+        32 + // oracle
+        1 + // liquidation_priority
+        8 + // last_healthy_oracle_slot
+        2 + // interest_rate_bps
+        16 + // borrow_index
+        32 + // exchange_rate_oracle
+        2 + // liquidation_bonus_bps
+        2 + // ltv_bps
+        1; // decimals
+    
+    /// Whether this slot holds a registered collateral
+    pub fn is_active(&self) -> bool {
+        self.mint != Pubkey::default()
+    }
+    
+    /// Whether the oracle was seen healthy within `window_slots` of `slot` (0 window = unchecked)
+    pub fn oracle_recently_healthy(&self, slot: u64, window_slots: u64) -> bool {
+        window_slots == 0
+            || (self.last_healthy_oracle_slot != 0
+                && slot.saturating_sub(self.last_healthy_oracle_slot) <= window_slots)
+    }
+    
+    /// Whether this collateral is priced through an exchange rate against `oracle`'s asset
+    pub fn has_exchange_rate(&self) -> bool {
+        self.exchange_rate_oracle != Pubkey::default()
+    }
+    
+    /// Borrow rate charged on this collateral, falling back to the global rate
+    pub fn effective_interest_rate_bps(&self, global_rate_bps: u16) -> u16 {
+        if self.interest_rate_bps == 0 { global_rate_bps } else { self.interest_rate_bps }
+    }
+    
+    /// Liquidation bonus paid on this collateral, falling back to the global bonus
+    pub fn effective_liquidation_bonus_bps(&self, global_bonus_bps: u16) -> u16 {
+        if self.liquidation_bonus_bps == 0 { global_bonus_bps } else { self.liquidation_bonus_bps }
+    }
+}
@@ -1,4 +1,8 @@
 use anchor_lang::prelude::*;
+use crate::constants::{BPS_PRECISION, MAX_COLLATERAL_TYPES, MAX_DEBT_ASSETS, MAX_DEBIT_TIERS, MAX_ORACLE_DECIMALS, MAX_PROTOCOL_OWNERS, MIN_LIQUIDATOR_BONUS_BPS, PAUSE_REPAYS, SHUTDOWN_BLOCKED_FLAGS, SOL_DECIMALS};
+use crate::state::{CollateralType, DebitTierLimits, DebtAsset, DebtValuation, OracleDecimals};
+use crate::oracle::{get_pyth_rate, PythPrice};
+use crate::utils::{calculate_borrow_index, calculate_collateral_value, calculate_weighted_max_borrow, checked_index_growth, compound_borrow_index, get_price_with_fallback};
 
 /// Global protocol configuration
 /// Stores risk parameters and oracle addresses
@@ -48,6 +52,174 @@ pub struct Config {
     /// Total collateral deposited (9 decimals for SOL-based tokens)
     pub total_collateral: u64,
     
+    /// Maximum oracle age in slots when valuing collateral for borrow power
+    pub borrow_max_staleness_slots: u64,
+    
+    /// Maximum oracle age in slots when checking liquidation eligibility
+    /// (tighter than the borrow bound to avoid liquidating on stale dips)
+    pub liquidation_max_staleness_slots: u64,
+    
+    /// Maximum oracle confidence interval relative to price (basis points)
+    pub max_confidence_bps: u16,
+    
+    /// Hard ceiling on interest_rate_bps for admin updates
+    pub max_interest_rate_bps: u16,
+    
+    /// Raised ceiling waiting out the timelock (0 = none queued)
+    pub pending_max_interest_rate_bps: u16,
+    
+    /// Timestamp after which the pending ceiling can be applied
+    pub max_interest_rate_unlock_timestamp: i64,
+    
+    /// Per-operation pause bitmask (see PAUSE_* constants), on top of `paused`
+    pub pause_flags: u8,
+    
+    /// Partial liquidations leaving less collateral than this become full liquidations
+    pub min_residual_collateral: u64,
+    
+    /// Interest compounds once per this many seconds; closer accruals are deferred (0 = every call)
+    pub min_accrual_interval_secs: i64,
+    
+    /// Health factors within this many bps below 1.0 are treated as rounding and not liquidatable
+    pub liquidation_health_epsilon_bps: u16,
+    
+    /// Debt written off from positions with no collateral left (6 decimals)
+    pub total_bad_debt_usdc: u64,
+    
+    /// Registered collateral types and their per-collateral parameters
+    pub collateral_types: [CollateralType; MAX_COLLATERAL_TYPES],
+    
+    /// jitoSOL mint replaced by the last mint migration; positions on it may migrate
+    pub previous_jito_sol_mint: Pubkey,
+    
+    /// Protocol-owned USDC held in the treasury vault (6 decimals)
+    pub total_reserves_usdc: u64,
+    
+    /// Seconds between a position's first deposit and its first borrow (0 disables)
+    pub min_position_age_secs: i64,
+    
+    /// USDC supplied to the treasury by suppliers (6 decimals)
+    pub total_supplied_usdc: u64,
+    
+    /// Treasury liquidity kept through supply withdrawals, as a share of total debt (basis points)
+    pub min_reserve_ratio_bps: u16,
+    
+    /// Ceiling on the oracle value of all deposited collateral in USDC (0 = no ceiling)
+    pub max_total_collateral_value_usdc: u64,
+    
+    /// Health factor (bps) a position must keep after borrowing or withdrawing
+    pub min_borrow_health_factor_bps: u16,
+    
+    /// Default debit spending limits per tier, applied when a tier is assigned
+    pub tier_config: [DebitTierLimits; MAX_DEBIT_TIERS],
+    
+    /// Health factor (bps) below which keepers may emit liquidation warnings
+    pub warning_threshold_bps: u16,
+    
+    /// Backstop authority whose liquidations are repaid from reserves (default pubkey = none)
+    pub protocol_liquidator: Pubkey,
+    
+    /// Fee on debt repaid within min_loan_term_secs of the borrow (basis points, 0 disables)
+    pub prepayment_fee_bps: u16,
+    
+    /// Seconds after a borrow during which repayments are charged the prepayment fee
+    pub min_loan_term_secs: i64,
+    
+    /// Share of a position's collateral a single liquidation may seize (basis points)
+    pub max_liquidation_collateral_fraction_bps: u16,
+    
+    /// Whether time under the global pause is excluded from interest accrual
+    pub pause_interest_accrual: bool,
+    
+    /// Start of the current accrual-excluded pause (0 = accruing)
+    pub paused_since: i64,
+    
+    /// Total seconds excluded from interest accrual across past pauses
+    pub total_paused_secs: i64,
+    
+    /// Headroom below ltv_max_bps when setting stored credit limits (basis points)
+    pub credit_limit_buffer_bps: u16,
+    
+    /// Partial liquidations leaving less debt than this (but some) become full liquidations (6 decimals, 0 disables)
+    pub min_debt_after_liquidation: u64,
+    
+    /// Lowest client version accepted by borrow/deposit instructions (0 = unchecked)
+    pub min_client_version: u8,
+    
+    /// Per-oracle quote precision overrides; feeds with another exponent are rejected
+    pub oracle_decimals: [OracleDecimals; MAX_ORACLE_DECIMALS],
+    
+    /// Deposits need the collateral's oracle seen healthy within this many slots (0 = unchecked)
+    pub oracle_health_window_slots: u64,
+    
+    /// Protocol-held position owners only the protocol liquidator may liquidate (default pubkey = empty)
+    pub protocol_owners: [Pubkey; MAX_PROTOCOL_OWNERS],
+    
+    /// Operations priced at the oracle EMA rather than spot (EMA_FOR_* bitmask)
+    pub ema_price_flags: u8,
+    
+    /// Reserved debit funds below this left after a settle are released to available (6 decimals)
+    pub debit_dust_threshold: u64,
+    
+    /// Pending debit reservations one account may hold at once
+    pub max_active_reservations: u16,
+    
+    /// Whether the global pause also blocks repayments (open by default)
+    pub pause_blocks_repays: bool,
+    
+    /// Band point collateral is valued at for borrow power (VALUATION_*)
+    pub borrow_valuation_mode: u8,
+    
+    /// Band point collateral is valued at for liquidation eligibility (VALUATION_*)
+    pub liquidation_valuation_mode: u8,
+    
+    /// Oldest accrual a borrow may build on without a prior accrual (0 = unchecked)
+    pub max_borrow_index_age_secs: i64,
+    
+    /// Share of idle treasury USDC one position may draw on top of its debt (bps, 0 = uncapped)
+    pub max_borrow_liquidity_share_bps: u16,
+    
+    /// Deposits within this long of the last credit-limit computation keep it (0 = always recompute)
+    pub credit_limit_refresh_interval_secs: i64,
+    
+    /// Price move since the last computation that forces a recompute anyway (bps)
+    pub credit_limit_refresh_price_bps: u16,
+    
+    /// Registered assets positions may borrow instead of USDC
+    pub debt_assets: [DebtAsset; MAX_DEBT_ASSETS],
+    
+    /// Most any one position may owe, whatever its collateral (6 decimals, 0 = uncapped)
+    pub max_single_position_debt_usdc: u64,
+    
+    /// Rebate from reserves to a liquidator who clears a position's whole debt (bps of the repayment)
+    pub full_liquidation_rebate_bps: u16,
+    
+    /// Slots a parameter change waits in `PendingParams` before it can be applied (0 = immediate)
+    pub params_timelock_slots: u64,
+    
+    /// Largest factor one accrual may grow a borrow index by (WAD precision, 0 = unbounded)
+    pub max_index_growth_wad: u128,
+    
+    /// One-way kill switch: permanently blocks deposits, borrows and liquidations
+    pub shutdown: bool,
+    
+    /// Reject oracle accounts other than `sol_usd_oracle`/`jito_sol_usd_oracle` for the asset priced
+    /// (on from `initialize`; switching it off is only meant for localnet fixtures)
+    pub enforce_configured_oracles: bool,
+    
+    /// Lifetime prepayment fees paid into reserves (USDC, 6 decimals)
+    pub prepayment_fees_usdc: u64,
+    
+    /// Lifetime value of the protocol's share of liquidation bonuses, kept as
+    /// collateral reserves and valued at the seizure price (USDC, 6 decimals)
+    pub liquidation_fees_usdc: u64,
+    
+    /// Auto-deleverage buffers held in the treasury for their positions (6 decimals)
+    pub total_deleverage_buffers_usdc: u64,
+    
+    /// Debit balances held in the treasury, available plus reserved (6 decimals)
+    pub total_debit_balances_usdc: u64,
+    
     /// Reserved space for future upgrades
     pub _reserved: [u64; 16],
 }
@@ -64,9 +236,541 @@ impl Config {
         32 + // jito_sol_usd_oracle
         32 + // usdc_mint
         32 + // jito_sol_mint
-        32 + // wsol_mint        16 + // global_borrow_index
+        32 + // wsol_mint
+        16 + // global_borrow_index
         8 + // last_update_timestamp
         8 + // total_debt_usdc
         8 + // total_collateral
+        8 + // borrow_max_staleness_slots
+        8 + // liquidation_max_staleness_slots
+        2 + // max_confidence_bps
+        2 + // max_interest_rate_bps
+        2 + // pending_max_interest_rate_bps
+        8 + // max_interest_rate_unlock_timestamp
+        1 + // pause_flags
+        8 + // min_residual_collateral
+        8 + // min_accrual_interval_secs
+        2 + // liquidation_health_epsilon_bps
+        8 + // total_bad_debt_usdc
+        (CollateralType::LEN * MAX_COLLATERAL_TYPES) + // collateral_types
+        32 + // previous_jito_sol_mint
+        8 + // total_reserves_usdc
+        8 + // min_position_age_secs
+        8 + // total_supplied_usdc
+        2 + // min_reserve_ratio_bps
+        8 + // max_total_collateral_value_usdc
+        2 + // min_borrow_health_factor_bps
+        (DebitTierLimits::LEN * MAX_DEBIT_TIERS) + // tier_config
+        2 + // warning_threshold_bps
+        32 + // protocol_liquidator
+        2 + // prepayment_fee_bps
+        8 + // min_loan_term_secs
+        2 + // max_liquidation_collateral_fraction_bps
+        1 + // pause_interest_accrual
+        8 + // paused_since
+        8 + // total_paused_secs
+        2 + // credit_limit_buffer_bps
+        8 + // min_debt_after_liquidation
+        1 + // min_client_version
+        (OracleDecimals::LEN * MAX_ORACLE_DECIMALS) + // oracle_decimals
+        8 + // oracle_health_window_slots
+        (32 * MAX_PROTOCOL_OWNERS) + // protocol_owners
+        1 + // ema_price_flags
+        8 + // debit_dust_threshold
+        2 + // max_active_reservations
+        1 + // pause_blocks_repays
+        1 + // borrow_valuation_mode
+        1 + // liquidation_valuation_mode
+        8 + // max_borrow_index_age_secs
+        2 + // max_borrow_liquidity_share_bps
+        8 + // credit_limit_refresh_interval_secs
+        2 + // credit_limit_refresh_price_bps
+        (DebtAsset::LEN * MAX_DEBT_ASSETS) + // debt_assets
+        8 + // max_single_position_debt_usdc
+        2 + // full_liquidation_rebate_bps
+        8 + // params_timelock_slots
+        16 + // max_index_growth_wad
+        1 + // shutdown
+        1 + // enforce_configured_oracles
+        8 + // prepayment_fees_usdc
+        8 + // liquidation_fees_usdc
+        8 + // total_deleverage_buffers_usdc
+        8 + // total_debit_balances_usdc
         (8 * 16); // _reserved
-} 
+    
+    /// Configured limits for a debit tier (None for unknown or unconfigured tiers)
+    pub fn tier_limits(&self, tier: u8) -> Option<&DebitTierLimits> {
+        self.tier_config
+            .get(tier as usize)
+            .filter(|limits| limits.is_valid())
+    }
+    
+    /// Registered collateral type for a mint
+    pub fn collateral_type(&self, mint: &Pubkey) -> Option<&CollateralType> {
+        self.collateral_types
+            .iter()
+            .find(|collateral| collateral.is_active() && collateral.mint == *mint)
+    }
+    
+    /// Whether `mint`'s registered oracle was marked healthy within the health window;
+    /// a mint with no registered collateral type has no health record to gate on
+    pub fn collateral_oracle_healthy(&self, mint: &Pubkey, slot: u64) -> bool {
+        match self.collateral_type(mint) {
+            Some(collateral) => collateral.oracle_recently_healthy(slot, self.oracle_health_window_slots),
+            None => true,
+        }
+    }
+    
+    /// Liquidation bonus for seizing a mint, the global bonus unless its collateral type overrides it
+    pub fn liquidation_bonus_bps_for(&self, mint: &Pubkey) -> u16 {
+        self.collateral_type(mint)
+            .map_or(self.liquidation_bonus_bps, |collateral| {
+                collateral.effective_liquidation_bonus_bps(self.liquidation_bonus_bps)
+            })
+    }
+    
+    /// Mutable registered collateral type for a mint
+    pub fn collateral_type_mut(&mut self, mint: &Pubkey) -> Option<&mut CollateralType> {
+        self.collateral_types
+            .iter_mut()
+            .find(|collateral| collateral.is_active() && collateral.mint == *mint)
+    }
+    
+    /// Register a collateral mint in the first free slot (no-op if already registered)
+    pub fn register_collateral_type(&mut self, mint: Pubkey) -> Result<&mut CollateralType> {
+        let index = match self.collateral_types.iter().position(|c| c.is_active() && c.mint == mint) {
+            Some(index) => index,
+            None => {
+                let index = self.collateral_types
+                    .iter()
+                    .position(|c| !c.is_active())
+                    .ok_or(crate::errors::CreditError::CollateralTypesFull)?;
+                // Start at the global index so positions already on this mint
+                // keep their debt when they switch to the collateral's index;
+                // SOL decimals until admin_set_collateral_type reads the mint
+                self.collateral_types[index] = CollateralType {
+                    mint,
+                    borrow_index: self.global_borrow_index,
+                    decimals: SOL_DECIMALS,
+                    ..CollateralType::default()
+                };
+                index
+            }
+        };
+        
+        Ok(&mut self.collateral_types[index])
+    }
+    
+    /// Registered debt asset for a mint
+    pub fn debt_asset(&self, mint: &Pubkey) -> Option<&DebtAsset> {
+        self.debt_assets
+            .iter()
+            .find(|asset| asset.is_active() && asset.mint == *mint)
+    }
+    
+    /// Register a debt asset in the first free slot, or return the existing entry
+    pub fn register_debt_asset(&mut self, mint: Pubkey) -> Result<&mut DebtAsset> {
+        let index = match self.debt_assets.iter().position(|a| a.is_active() && a.mint == mint) {
+            Some(index) => index,
+            None => {
+                let index = self.debt_assets
+                    .iter()
+                    .position(|a| !a.is_active())
+                    .ok_or(crate::errors::CreditError::DebtAssetsFull)?;
+                self.debt_assets[index] = DebtAsset { mint, ..DebtAsset::default() };
+                index
+            }
+        };
+        
+        Ok(&mut self.debt_assets[index])
+    }
+    
+    /// Outstanding debt total for a position's debt mint (default pubkey = USDC)
+    pub fn debt_total_mut(&mut self, debt_mint: &Pubkey) -> Result<&mut u64> {
+        if *debt_mint == Pubkey::default() {
+            return Ok(&mut self.total_debt_usdc);
+        }
+        self.debt_assets
+            .iter_mut()
+            .find(|asset| asset.is_active() && asset.mint == *debt_mint)
+            .map(|asset| &mut asset.total_debt)
+            .ok_or(error!(crate::errors::CreditError::InvalidMint))
+    }
+    
+    /// How debt owed in `debt_mint` (default pubkey = USDC) is valued in USDC
+    ///
+    /// Oracle-priced assets read `debt_oracle`, which must be the asset's feed.
+    pub fn debt_valuation(
+        &self,
+        debt_mint: &Pubkey,
+        debt_oracle: Option<&AccountInfo>,
+        clock: &Clock,
+        max_staleness_slots: u64,
+    ) -> Result<DebtValuation> {
+        if *debt_mint == Pubkey::default() {
+            return Ok(DebtValuation::USDC);
+        }
+        let asset = self.debt_asset(debt_mint).ok_or(crate::errors::CreditError::InvalidMint)?;
+        if asset.is_stable() {
+            return Ok(DebtValuation { price: DebtValuation::USDC.price, decimals: asset.decimals });
+        }
+        
+        let oracle = debt_oracle.ok_or(crate::errors::CreditError::InvalidOracle)?;
+        require_keys_eq!(oracle.key(), asset.oracle, crate::errors::CreditError::InvalidOracle);
+        let price = get_price_with_fallback(
+            oracle,
+            clock,
+            max_staleness_slots,
+            self.expected_quote_decimals(&oracle.key())
+        )?
+        .price;
+        
+        Ok(DebtValuation { price, decimals: asset.decimals })
+    }
+    
+    /// Split an instruction's remaining accounts into the debt asset's feed and
+    /// the rest: the feed leads only for debt owed in an oracle-priced asset
+    pub fn split_debt_oracle<'a, 'info>(
+        &self,
+        debt_mint: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> (Option<&'a AccountInfo<'info>>, &'a [AccountInfo<'info>]) {
+        let oracle_priced = self.debt_asset(debt_mint).is_some_and(|asset| !asset.is_stable());
+        match accounts.split_first() {
+            Some((debt_oracle, rest)) if oracle_priced => (Some(debt_oracle), rest),
+            _ => (None, accounts),
+        }
+    }
+    
+    /// Price of a registered collateral read from `oracle`, its registered feed
+    ///
+    /// Collateral with an exchange-rate oracle (e.g. jitoSOL/SOL) is worth the
+    /// `oracle` price times the rate read from `rate_oracle`, which must be that
+    /// feed. The rate is always read from Pyth, never from the devnet mock.
+    pub fn collateral_type_price(
+        &self,
+        collateral_type: &CollateralType,
+        oracle: &AccountInfo,
+        rate_oracle: Option<&AccountInfo>,
+        clock: &Clock,
+        max_staleness_slots: u64,
+    ) -> Result<PythPrice> {
+        let price = get_price_with_fallback(
+            oracle,
+            clock,
+            max_staleness_slots,
+            self.expected_quote_decimals(&oracle.key())
+        )?;
+        if !collateral_type.has_exchange_rate() {
+            return Ok(price);
+        }
+        
+        let rate_oracle = rate_oracle.ok_or(crate::errors::CreditError::InvalidOracle)?;
+        require_keys_eq!(
+            rate_oracle.key(),
+            collateral_type.exchange_rate_oracle,
+            crate::errors::CreditError::InvalidOracle
+        );
+        let rate = get_pyth_rate(
+            rate_oracle,
+            clock,
+            max_staleness_slots,
+            self.expected_quote_decimals(&rate_oracle.key())
+        )?;
+        
+        price.through_rate(&rate)
+    }
+    
+    /// Borrow index for positions backed by a mint (the global index if unregistered)
+    pub fn borrow_index_for(&self, mint: &Pubkey) -> u128 {
+        self.collateral_type(mint)
+            .map(|collateral| collateral.borrow_index)
+            .unwrap_or(self.global_borrow_index)
+    }
+    
+    /// Whether the protocol accounts for balances of this mint (USDC or any collateral)
+    pub fn is_protocol_mint(&self, mint: &Pubkey) -> bool {
+        *mint == self.usdc_mint
+            || *mint == self.wsol_mint
+            || *mint == self.jito_sol_mint
+            || *mint == self.previous_jito_sol_mint
+            || self.collateral_type(mint).is_some()
+    }
+    
+    /// Whether liquidators keep at least the minimum bonus after the protocol share
+    pub fn liquidator_bonus_sufficient(liquidation_bonus_bps: u16, protocol_share_bps: u16) -> bool {
+        if protocol_share_bps == 0 {
+            return true;
+        }
+        let liquidator_bonus_bps = (liquidation_bonus_bps as u64)
+            * (BPS_PRECISION - protocol_share_bps as u64)
+            / BPS_PRECISION;
+        liquidator_bonus_bps >= MIN_LIQUIDATOR_BONUS_BPS as u64
+    }
+    
+    /// Whether total collateral valued at `price` stays within the TVL ceiling
+    pub fn within_collateral_cap(&self, total_collateral: u64, price: u64) -> Result<bool> {
+        if self.max_total_collateral_value_usdc == 0 {
+            return Ok(true);
+        }
+        Ok(calculate_collateral_value(total_collateral, price)? <= self.max_total_collateral_value_usdc)
+    }
+    
+    /// USDC in the treasury that belongs to owners rather than to lenders:
+    /// deleverage buffers, debit balances and protocol reserves
+    pub fn owner_held_usdc(&self) -> Result<u64> {
+        self.total_reserves_usdc
+            .checked_add(self.total_deleverage_buffers_usdc)
+            .and_then(|held| held.checked_add(self.total_debit_balances_usdc))
+            .ok_or(error!(crate::errors::CreditError::MathOverflow))
+    }
+    
+    /// Part of a vault's `balance` that may be lent out or withdrawn by suppliers
+    ///
+    /// Owner-held balances are all USDC, so only the treasury is reduced by them;
+    /// every borrow path and supply withdrawals measure liquidity through this.
+    pub fn lendable_liquidity(&self, mint: &Pubkey, balance: u64) -> Result<u64> {
+        if *mint != self.usdc_mint {
+            return Ok(balance);
+        }
+        Ok(balance.saturating_sub(self.owner_held_usdc()?))
+    }
+    
+    /// Lendable USDC that supply withdrawals must leave behind to back outstanding debt
+    pub fn reserve_floor_usdc(&self) -> Result<u64> {
+        let debt_floor = (self.total_debt_usdc as u128)
+            .checked_mul(self.min_reserve_ratio_bps as u128)
+            .ok_or(crate::errors::CreditError::MathOverflow)?
+            .checked_div(BPS_PRECISION as u128)
+            .ok_or(crate::errors::CreditError::MathOverflow)?;
+        
+        Ok(debt_floor as u64)
+    }
+    
+    /// Whether an operation may leave a position at `health_after`: at or above
+    /// the borrow buffer, or, for a risk-reducing operation that passes the
+    /// `health_before` it started from, no worse than that
+    pub fn health_after_allowed(&self, health_before: Option<u64>, health_after: u64) -> bool {
+        health_after >= self.min_borrow_health_factor_bps as u64
+            || health_before.is_some_and(|before| health_after >= before)
+    }
+    
+    /// LTV used for stored credit limits: the max LTV less the safety buffer
+    pub fn credit_limit_ltv_bps(&self) -> u16 {
+        self.ltv_max_bps.saturating_sub(self.credit_limit_buffer_bps)
+    }
+    
+    /// Credit limit LTV for one collateral mint: its collateral type's LTV if
+    /// set, otherwise the global LTV, less the same credit limit buffer
+    pub fn credit_limit_ltv_bps_for(&self, mint: &Pubkey) -> u16 {
+        let ltv_bps = self.collateral_type(mint)
+            .map(|collateral| collateral.ltv_bps)
+            .filter(|&ltv_bps| ltv_bps > 0)
+            .unwrap_or(self.ltv_max_bps);
+        ltv_bps.saturating_sub(self.credit_limit_buffer_bps)
+    }
+    
+    /// Credit limit over per-collateral USD values, each counted at its own
+    /// credit limit LTV
+    pub fn weighted_credit_limit(&self, values: &[(Pubkey, u64)]) -> Result<u64> {
+        let weighted: Vec<(u64, u16)> = values
+            .iter()
+            .map(|(mint, value)| (*value, self.credit_limit_ltv_bps_for(mint)))
+            .collect();
+        calculate_weighted_max_borrow(&weighted)
+    }
+    
+    /// Whether `liquidator` is the configured protocol backstop
+    pub fn is_protocol_liquidator(&self, liquidator: &Pubkey) -> bool {
+        self.protocol_liquidator != Pubkey::default() && *liquidator == self.protocol_liquidator
+    }
+    
+    /// Whether `owner` may be liquidated by `liquidator`; protocol-held positions
+    /// are reserved for the protocol liquidator
+    pub fn may_liquidate(&self, owner: &Pubkey, liquidator: &Pubkey) -> bool {
+        let protocol_owned = *owner != Pubkey::default() && self.protocol_owners.contains(owner);
+        !protocol_owned || self.is_protocol_liquidator(liquidator)
+    }
+    
+    /// Expected quote decimals configured for a price feed, if any
+    pub fn expected_quote_decimals(&self, oracle: &Pubkey) -> Option<u8> {
+        self.oracle_decimals
+            .iter()
+            .find(|entry| entry.is_active() && entry.oracle == *oracle)
+            .map(|entry| entry.expected_quote_decimals)
+    }
+    
+    /// Whether a client built for `client_version` may send borrow/deposit instructions
+    pub fn accepts_client_version(&self, client_version: u8) -> bool {
+        self.min_client_version == 0 || client_version >= self.min_client_version
+    }
+    
+    /// Configured USD feed for single-collateral positions in `mint` (the SOL feed unless jitoSOL)
+    pub fn configured_oracle(&self, mint: &Pubkey) -> Pubkey {
+        if *mint != self.wsol_mint && (*mint == self.jito_sol_mint || *mint == self.previous_jito_sol_mint) {
+            self.jito_sol_usd_oracle
+        } else {
+            self.sol_usd_oracle
+        }
+    }
+    
+    /// Reject an oracle account other than the configured feed for `mint`, unless
+    /// enforcement was switched off
+    pub fn check_oracle(&self, mint: &Pubkey, oracle: &Pubkey) -> Result<()> {
+        if self.enforce_configured_oracles {
+            require_keys_eq!(*oracle, self.configured_oracle(mint), crate::errors::CreditError::InvalidOracle);
+        }
+        Ok(())
+    }
+    
+    /// Whether an operation values collateral at the oracle EMA
+    pub fn uses_ema_for(&self, flag: u8) -> bool {
+        self.ema_price_flags & flag != 0
+    }
+    
+    /// Whether an operation is blocked, by the global pause, its own flag or a shutdown
+    pub fn is_paused_for(&self, flag: u8) -> bool {
+        self.paused || self.pause_flags & flag != 0 || (self.shutdown && flag & SHUTDOWN_BLOCKED_FLAGS != 0)
+    }
+    
+    /// Whether repayments are blocked: by their own flag, or by the global pause
+    /// only when configured to (reducing debt otherwise stays open while paused)
+    pub fn repays_paused(&self) -> bool {
+        self.pause_flags & PAUSE_REPAYS != 0 || (self.paused && self.pause_blocks_repays)
+    }
+    
+    /// Whether a position may owe `debt_value` (USDC) under the per-position debt cap
+    pub fn within_position_debt_cap(&self, debt_value: u64) -> bool {
+        self.max_single_position_debt_usdc == 0 || debt_value <= self.max_single_position_debt_usdc
+    }
+    
+    /// Most a position's debt may reach given the USDC left to lend: its current
+    /// debt plus the configured share of `available`, the vault's
+    /// `lendable_liquidity` (None when uncapped)
+    pub fn liquidity_credit_limit(&self, current_debt: u64, available: u64) -> Result<Option<u64>> {
+        if self.max_borrow_liquidity_share_bps == 0 {
+            return Ok(None);
+        }
+        
+        let share = (available as u128)
+            .checked_mul(self.max_borrow_liquidity_share_bps as u128)
+            .ok_or(crate::errors::CreditError::MathOverflow)?
+            / BPS_PRECISION as u128;
+        
+        Ok(Some(current_debt.saturating_add(share as u64)))
+    }
+    
+    /// Whether the borrow index was accrued recently enough to borrow against
+    pub fn borrow_index_fresh(&self, current_timestamp: i64) -> bool {
+        self.max_borrow_index_age_secs == 0
+            || current_timestamp.saturating_sub(self.last_update_timestamp) <= self.max_borrow_index_age_secs
+    }
+    
+    /// Accrue interest into the global and per-collateral borrow indices up to the given timestamp
+    ///
+    /// With an accrual interval set, only whole intervals are compounded and the
+    /// remainder is carried to the next call, so the index depends on elapsed time
+    /// alone rather than on how often this is called.
+    pub fn accrue_interest(&mut self, current_timestamp: i64) -> Result<()> {
+        if self.paused_since != 0 {
+            // Borrowers aren't charged for protocol-side downtime
+            return Ok(());
+        }
+        
+        let global_rate_bps = self.interest_rate_bps;
+        let max_growth_wad = self.max_index_growth_wad;
+        
+        if self.min_accrual_interval_secs == 0 {
+            self.global_borrow_index = checked_index_growth(
+                self.global_borrow_index,
+                calculate_borrow_index(
+                    self.last_update_timestamp,
+                    current_timestamp,
+                    self.global_borrow_index,
+                    global_rate_bps
+                )?,
+                max_growth_wad
+            )?;
+            for collateral in self.collateral_types.iter_mut().filter(|c| c.is_active()) {
+                collateral.borrow_index = checked_index_growth(
+                    collateral.borrow_index,
+                    calculate_borrow_index(
+                        self.last_update_timestamp,
+                        current_timestamp,
+                        collateral.borrow_index,
+                        collateral.effective_interest_rate_bps(global_rate_bps)
+                    )?,
+                    max_growth_wad
+                )?;
+            }
+            self.last_update_timestamp = current_timestamp;
+            return Ok(());
+        }
+        
+        let elapsed = current_timestamp.saturating_sub(self.last_update_timestamp);
+        let periods = elapsed / self.min_accrual_interval_secs;
+        if periods == 0 {
+            // Too soon, merge into the next accrual
+            return Ok(());
+        }
+        
+        self.global_borrow_index = checked_index_growth(
+            self.global_borrow_index,
+            compound_borrow_index(
+                self.global_borrow_index,
+                global_rate_bps,
+                self.min_accrual_interval_secs as u64,
+                periods as u64
+            )?,
+            max_growth_wad
+        )?;
+        for collateral in self.collateral_types.iter_mut().filter(|c| c.is_active()) {
+            collateral.borrow_index = checked_index_growth(
+                collateral.borrow_index,
+                compound_borrow_index(
+                    collateral.borrow_index,
+                    collateral.effective_interest_rate_bps(global_rate_bps),
+                    self.min_accrual_interval_secs as u64,
+                    periods as u64
+                )?,
+                max_growth_wad
+            )?;
+        }
+        self.last_update_timestamp += periods * self.min_accrual_interval_secs;
+        
+        Ok(())
+    }
+    
+    /// Stop accruing interest for a global pause, settling the index up to its start
+    pub fn begin_accrual_pause(&mut self, current_timestamp: i64) -> Result<()> {
+        if !self.pause_interest_accrual || self.paused_since != 0 {
+            return Ok(());
+        }
+        
+        self.accrue_interest(current_timestamp)?;
+        self.paused_since = current_timestamp;
+        
+        Ok(())
+    }
+    
+    /// Resume accruing interest, moving the accrual clock past the paused window
+    /// (an interval remainder carried into the pause is kept)
+    pub fn end_accrual_pause(&mut self, current_timestamp: i64) -> Result<()> {
+        if self.paused_since == 0 {
+            return Ok(());
+        }
+        
+        let paused_secs = current_timestamp.saturating_sub(self.paused_since);
+        self.last_update_timestamp = self.last_update_timestamp
+            .checked_add(paused_secs)
+            .ok_or(crate::errors::CreditError::MathOverflow)?;
+        self.total_paused_secs = self.total_paused_secs
+            .checked_add(paused_secs)
+            .ok_or(crate::errors::CreditError::MathOverflow)?;
+        self.paused_since = 0;
+        
+        msg!("Excluded {} paused seconds from interest accrual", paused_secs);
+        
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::state::DebitTierLimits;
 
 /// Debit account for USDC spending
 /// Separate from credit positions - no debt, just balance
@@ -46,6 +47,21 @@ pub struct DebitAccount {
     /// Account initialized
     pub is_initialized: bool,
     
+    /// Limit tier (index into Config::tier_config)
+    pub tier: u8,
+    
+    /// Largest single spend
+    pub per_tx_limit: u64,
+    
+    /// Pending reservations not yet settled
+    pub active_reservations: u16,
+    
+    /// Owner's credit position that covers spends beyond the debit balance (default = none)
+    pub linked_position: Pubkey,
+    
+    /// Portion of pending reservations held against the linked credit line (6 decimals)
+    pub credit_reserved: u64,
+    
     /// Reserved space for future upgrades
     pub _reserved: [u64; 16],
 }
@@ -66,17 +82,32 @@ impl DebitAccount {
         8 + // last_monthly_reset
         1 + // status
         1 + // is_initialized
+        1 + // tier
+        8 + // per_tx_limit
+        2 + // active_reservations
+        32 + // linked_position
+        8 + // credit_reserved
         (8 * 16); // _reserved
         
     /// Check if spending is allowed
     pub fn can_spend(&self, amount: u64, current_timestamp: i64) -> Result<bool> {
+        // Check available balance
+        if self.usdc_available < amount {
+            return Ok(false);
+        }
+        
+        self.within_limits(amount, current_timestamp)
+    }
+    
+    /// Check status and spending limits, whatever funds the spend
+    pub fn within_limits(&self, amount: u64, current_timestamp: i64) -> Result<bool> {
         // Check status
         if self.status != 1 {
             return Ok(false);
         }
         
-        // Check available balance
-        if self.usdc_available < amount {
+        // Check single transaction limit
+        if amount > self.per_tx_limit {
             return Ok(false);
         }
         
@@ -105,6 +136,19 @@ impl DebitAccount {
         Ok(true)
     }
     
+    /// Whether a credit position is linked to cover spends beyond the balance
+    pub fn has_linked_position(&self) -> bool {
+        self.linked_position != Pubkey::default()
+    }
+    
+    /// Assign a tier and take its default limits
+    pub fn apply_tier(&mut self, tier: u8, limits: &DebitTierLimits) {
+        self.tier = tier;
+        self.daily_limit = limits.daily_limit;
+        self.monthly_limit = limits.monthly_limit;
+        self.per_tx_limit = limits.per_tx_limit;
+    }
+    
     /// Check if daily limit needs reset
     pub fn needs_daily_reset(&self, current_timestamp: i64) -> bool {
         const SECONDS_PER_DAY: i64 = 86400;
@@ -123,7 +167,7 @@ impl DebitAccount {
     pub fn reserve_funds(&mut self, amount: u64) -> Result<()> {
         require!(
             self.usdc_available >= amount,
-            error!(crate::errors::CreditError::InsufficientBalance)
+            crate::errors::CreditError::InsufficientBalance
         );
         
         self.usdc_available = self.usdc_available
@@ -134,24 +178,71 @@ impl DebitAccount {
             .checked_add(amount)
             .ok_or(error!(crate::errors::CreditError::MathOverflow))?;
             
+        self.active_reservations = self.active_reservations
+            .checked_add(1)
+            .ok_or(error!(crate::errors::CreditError::MathOverflow))?;
+            
+        Ok(())
+    }
+    
+    /// Reserve a spend that drains the debit balance, holding the shortfall
+    /// against the linked credit line as one authorization
+    pub fn reserve_with_credit(&mut self, from_debit: u64, from_credit: u64) -> Result<()> {
+        require!(
+            self.usdc_available >= from_debit,
+            crate::errors::CreditError::InsufficientBalance
+        );
+        
+        self.usdc_available = self.usdc_available
+            .checked_sub(from_debit)
+            .ok_or(error!(crate::errors::CreditError::MathOverflow))?;
+            
+        self.usdc_reserved = self.usdc_reserved
+            .checked_add(from_debit)
+            .ok_or(error!(crate::errors::CreditError::MathOverflow))?;
+            
+        self.credit_reserved = self.credit_reserved
+            .checked_add(from_credit)
+            .ok_or(error!(crate::errors::CreditError::MathOverflow))?;
+            
+        self.active_reservations = self.active_reservations
+            .checked_add(1)
+            .ok_or(error!(crate::errors::CreditError::MathOverflow))?;
+            
         Ok(())
     }
     
     /// Commit reserved funds (transaction settled)
-    pub fn commit_reserved(&mut self, amount: u64, current_timestamp: i64) -> Result<()> {
+    ///
+    /// Draws on the debit reserve first and the credit hold for the rest;
+    /// returns the part that came from the linked credit line.
+    pub fn commit_reserved(&mut self, amount: u64, current_timestamp: i64) -> Result<u64> {
+        let from_debit = amount.min(self.usdc_reserved);
+        let from_credit = amount - from_debit;
         require!(
-            self.usdc_reserved >= amount,
-            error!(crate::errors::CreditError::InsufficientBalance)
+            self.credit_reserved >= from_credit,
+            crate::errors::CreditError::InsufficientBalance
         );
         
         self.usdc_reserved = self.usdc_reserved
-            .checked_sub(amount)
+            .checked_sub(from_debit)
+            .ok_or(error!(crate::errors::CreditError::MathOverflow))?;
+            
+        self.credit_reserved = self.credit_reserved
+            .checked_sub(from_credit)
             .ok_or(error!(crate::errors::CreditError::MathOverflow))?;
             
         self.lifetime_spent = self.lifetime_spent
             .checked_add(amount)
             .ok_or(error!(crate::errors::CreditError::MathOverflow))?;
             
+        // Each settlement closes one authorization; nothing is pending once the reserve is empty
+        self.active_reservations = if self.usdc_reserved == 0 && self.credit_reserved == 0 {
+            0
+        } else {
+            self.active_reservations.saturating_sub(1)
+        };
+            
         // Update daily spent (with reset if needed)
         if self.needs_daily_reset(current_timestamp) {
             self.daily_spent = amount;
@@ -172,26 +263,46 @@ impl DebitAccount {
                 .ok_or(error!(crate::errors::CreditError::MathOverflow))?;
         }
         
-        Ok(())
+        Ok(from_credit)
     }
     
     /// Release reserved funds (transaction declined/cancelled)
     pub fn release_reserved(&mut self, amount: u64) -> Result<()> {
         require!(
             self.usdc_reserved >= amount,
-            error!(crate::errors::CreditError::InsufficientBalance)
+            crate::errors::CreditError::InsufficientBalance
         );
         
         self.usdc_reserved = self.usdc_reserved
             .checked_sub(amount)
             .ok_or(error!(crate::errors::CreditError::MathOverflow))?;
             
+        if self.usdc_reserved == 0 && self.credit_reserved == 0 {
+            self.active_reservations = 0;
+        }
+            
         self.usdc_available = self.usdc_available
             .checked_add(amount)
             .ok_or(error!(crate::errors::CreditError::MathOverflow))?;
             
         Ok(())
     }
+    
+    /// Release credit held for a spend (settled under its authorization)
+    pub fn release_credit(&mut self, amount: u64) -> Result<()> {
+        require!(
+            self.credit_reserved >= amount,
+            crate::errors::CreditError::InsufficientBalance
+        );
+        
+        self.credit_reserved = self.credit_reserved
+            .checked_sub(amount)
+            .ok_or(error!(crate::errors::CreditError::MathOverflow))?;
+            
+        if self.usdc_reserved == 0 && self.credit_reserved == 0 {
+            self.active_reservations = 0;
+        }
+            
+        Ok(())
+    }
 }
-
-
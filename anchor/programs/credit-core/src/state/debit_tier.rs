@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+/// Default spending limits for a debit tier, stored in `Config::tier_config`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct DebitTierLimits {
+    /// Daily spending limit (6 decimals)
+    pub daily_limit: u64,
+    
+    /// Monthly spending limit (6 decimals)
+    pub monthly_limit: u64,
+    
+    /// Largest single spend (6 decimals)
+    pub per_tx_limit: u64,
+}
+
+impl DebitTierLimits {
+    pub const LEN: usize = 8 + // daily_limit
+        8 + // monthly_limit
+        8; // per_tx_limit
+    
+    /// Whether the limits are internally consistent
+    pub fn is_valid(&self) -> bool {
+        self.per_tx_limit > 0
+            && self.per_tx_limit <= self.daily_limit
+            && self.daily_limit <= self.monthly_limit
+    }
+}
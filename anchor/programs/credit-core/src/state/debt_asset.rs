@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use crate::constants::USDC_DECIMALS;
+
+/// A borrowable asset other than USDC, stored in `Config::debt_assets`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct DebtAsset {
+    /// Debt mint (default pubkey = empty slot)
+    pub mint: Pubkey,
+    
+    /// USD price feed for the asset (default pubkey = a $1 stablecoin)
+    pub oracle: Pubkey,
+    
+    /// Mint decimals, used to value debt in USDC terms
+    pub decimals: u8,
+    
+    /// Outstanding debt owed in this asset, in its own base units
+    pub total_debt: u64,
+}
+
+impl DebtAsset {
+    pub const LEN: usize = 32 + // mint
+        32 + // oracle
+        1 + // decimals
+        8; // total_debt
+    
+    /// Whether this slot holds a registered debt asset
+    pub fn is_active(&self) -> bool {
+        self.mint != Pubkey::default()
+    }
+    
+    /// Whether the asset is valued at $1 rather than through an oracle
+    pub fn is_stable(&self) -> bool {
+        self.oracle == Pubkey::default()
+    }
+}
+
+/// Price and decimals a position's debt is valued at in USDC
+#[derive(Clone, Copy)]
+pub struct DebtValuation {
+    /// USD price of one whole unit (6 decimals)
+    pub price: u64,
+    pub decimals: u8,
+}
+
+impl DebtValuation {
+    /// Debt owed in USDC, valued one-to-one
+    pub const USDC: DebtValuation = DebtValuation {
+        price: 1_000_000,
+        decimals: USDC_DECIMALS,
+    };
+    
+    /// USDC value (6 decimals) of `amount` base units, rounded down
+    pub fn value(&self, amount: u64) -> Result<u64> {
+        let value = (amount as u128)
+            .checked_mul(self.price as u128)
+            .ok_or(crate::errors::CreditError::MathOverflow)?
+            / 10u128.pow(self.decimals as u32);
+        
+        u64::try_from(value).map_err(|_| error!(crate::errors::CreditError::MathOverflow))
+    }
+    
+    /// Base units worth `value` USDC, rounded up so repayments cover the value
+    pub fn amount_for(&self, value: u64) -> Result<u64> {
+        require!(self.price > 0, crate::errors::CreditError::InvalidOracle);
+        let scaled = (value as u128)
+            .checked_mul(10u128.pow(self.decimals as u32))
+            .ok_or(crate::errors::CreditError::MathOverflow)?;
+        let amount = scaled
+            .checked_add(self.price as u128 - 1)
+            .ok_or(crate::errors::CreditError::MathOverflow)?
+            / self.price as u128;
+        
+        u64::try_from(amount).map_err(|_| error!(crate::errors::CreditError::MathOverflow))
+    }
+}
@@ -1,5 +1,21 @@
 pub mod config;
 pub mod user_position;
+pub mod collateral_type;
+pub mod position_collateral;
+pub mod supplier_position;
+pub mod debit_account;
+pub mod debit_tier;
+pub mod oracle_decimals;
+pub mod debt_asset;
+pub mod pending_params;
 
 pub use config::*;
-pub use user_position::*; 
\ No newline at end of file
+pub use user_position::*;
+pub use collateral_type::*;
+pub use position_collateral::*;
+pub use supplier_position::*;
+pub use debit_account::*;
+pub use debit_tier::*;
+pub use oracle_decimals::*;
+pub use debt_asset::*;
+pub use pending_params::*; 
\ No newline at end of file
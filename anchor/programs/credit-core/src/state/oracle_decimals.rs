@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+/// Expected quote precision for one price feed, stored in `Config::oracle_decimals`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct OracleDecimals {
+    /// Price feed account (default pubkey = empty slot)
+    pub oracle: Pubkey,
+    
+    /// Decimals the feed must quote in, i.e. the negated Pyth exponent
+    pub expected_quote_decimals: u8,
+}
+
+impl OracleDecimals {
+    pub const LEN: usize = 32 + // oracle
+        1; // expected_quote_decimals
+    
+    /// Whether this slot holds an override
+    pub fn is_active(&self) -> bool {
+        self.oracle != Pubkey::default()
+    }
+}
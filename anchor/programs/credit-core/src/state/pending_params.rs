@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+use crate::instructions::UpdateParams;
+
+/// Parameter changes queued by `admin_set_params` under a params timelock
+#[account]
+pub struct PendingParams {
+    /// Slot the changes were queued in
+    pub queued_slot: u64,
+    
+    /// First slot the changes can be applied in (0 = nothing queued)
+    pub effective_slot: u64,
+    
+    /// The queued changes, validated when applied
+    pub params: UpdateParams,
+}
+
+impl PendingParams {
+    pub const LEN: usize = 8 + // discriminator
+        8 + // queued_slot
+        8 + // effective_slot
+        UpdateParams::MAX_LEN; // params
+    
+    /// Whether changes are queued
+    pub fn is_queued(&self) -> bool {
+        self.effective_slot != 0
+    }
+}
@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+/// Collateral held by a multi-collateral position, stored in `UserPosition::collaterals`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct PositionCollateral {
+    /// Collateral mint (default pubkey = empty slot)
+    pub mint: Pubkey,
+    
+    /// Amount deposited (9 decimals)
+    pub amount: u64,
+}
+
+impl PositionCollateral {
+    pub const LEN: usize = 32 + // mint
+        8; // amount
+    
+    /// Whether this slot holds a collateral mint
+    pub fn is_active(&self) -> bool {
+        self.mint != Pubkey::default()
+    }
+}
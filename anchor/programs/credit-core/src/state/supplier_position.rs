@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+/// USDC a supplier has lent to the treasury
+#[account]
+pub struct SupplierPosition {
+    /// Owner of this position (supplier's wallet)
+    pub owner: Pubkey,
+    
+    /// USDC supplied and not yet withdrawn (6 decimals)
+    pub supplied_usdc: u64,
+    
+    /// Last update timestamp
+    pub last_update_timestamp: i64,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u64; 8],
+}
+
+impl SupplierPosition {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        8 + // supplied_usdc
+        8 + // last_update_timestamp
+        (8 * 8); // _reserved
+}
@@ -1,4 +1,8 @@
 use anchor_lang::prelude::*;
+use crate::state::{Config, PositionCollateral};
+use crate::constants::{BPS_PRECISION, MAX_POSITION_COLLATERALS};
+use crate::oracle::PythPrice;
+use crate::utils::{calculate_collateral_value, calculate_max_borrow, calculate_token_value, health_factor_from_value};
 
 /// Individual user's credit position
 /// Tracks collateral, debt, and borrowing state
@@ -13,7 +17,7 @@ pub struct UserPosition {
     /// Amount of collateral deposited (9 decimals)
     pub collateral_amount: u64,
     
-    /// USDC debt amount (6 decimals)
+    /// Debt amount in base units of `debt_mint` (USDC, 6 decimals, by default)
     pub debt_usdc: u64,
     
     /// User's borrow index snapshot for interest calculation
@@ -40,6 +44,63 @@ pub struct UserPosition {
     /// Credit limit in USDC (can be different from max LTV * collateral)
     pub credit_limit: u64,
     
+    /// Health factor cached by the last refresh (4 decimal precision, 10000 = 1.0)
+    pub last_health_factor: u64,
+    
+    /// Timestamp of the last cached health factor
+    pub last_health_timestamp: i64,
+    
+    /// Debt moved to the protocol's bad debt after collateral ran out (6 decimals)
+    pub bad_debt_usdc: u64,
+    
+    /// Share of accrued interest covered by protocol reserves (basis points)
+    pub subsidy_bps: u16,
+    
+    /// Timestamp after which the interest subsidy stops applying
+    pub subsidy_expiry_timestamp: i64,
+    
+    /// Timestamp interest was last settled into debt_usdc
+    pub last_interest_timestamp: i64,
+    
+    /// Timestamp of the first collateral deposit (0 until one is made)
+    pub first_deposit_timestamp: i64,
+    
+    /// Health factor (bps) below which keepers may repay from the buffer (0 disables)
+    pub auto_deleverage_threshold_bps: u16,
+    
+    /// USDC pre-funded by the owner for auto-deleverage, held in the treasury (6 decimals)
+    pub deleverage_buffer_usdc: u64,
+    
+    /// Sequence number of the last position event, bumped by every collateral or debt change
+    pub event_seq: u64,
+    
+    /// Multi-collateral holdings; used instead of collateral_mint/collateral_amount
+    pub collaterals: [PositionCollateral; MAX_POSITION_COLLATERALS],
+    
+    /// Timestamp of the borrow that took debt up from zero (0 while debt-free)
+    pub borrow_start_timestamp: i64,
+    
+    /// Opaque owner-set identifier copied into position events for notification routing
+    pub webhook_id: [u8; 32],
+    
+    /// Collateral price the credit limit was last computed at on deposit (6 decimals)
+    pub credit_limit_price: u64,
+    
+    /// When the credit limit was last computed on deposit
+    pub credit_limit_timestamp: i64,
+    
+    /// Registered debt asset the position borrowed (default pubkey = USDC)
+    pub debt_mint: Pubkey,
+    
+    /// Health factor (bps) at which keepers sell collateral to repay debt (0 = off)
+    pub stop_loss_health_bps: u16,
+    
+    /// Set by `enforce_buffer` while the position sits below the borrow buffer; blocks new borrows
+    pub buffer_breached: bool,
+    
+    /// Credit held for linked debit card authorizations not yet settled (USDC, 6 decimals)
+    pub pending_credit_usdc: u64,
+    
     /// Reserved space for future upgrades
     pub _reserved: [u64; 16],
 }
@@ -58,6 +119,25 @@ impl UserPosition {
         4 + // liquidation_count
         1 + // is_initialized
         8 + // credit_limit
+        8 + // last_health_factor
+        8 + // last_health_timestamp
+        8 + // bad_debt_usdc
+        2 + // subsidy_bps
+        8 + // subsidy_expiry_timestamp
+        8 + // last_interest_timestamp
+        8 + // first_deposit_timestamp
+        2 + // auto_deleverage_threshold_bps
+        8 + // deleverage_buffer_usdc
+        8 + // event_seq
+        (PositionCollateral::LEN * MAX_POSITION_COLLATERALS) + // collaterals
+        8 + // borrow_start_timestamp
+        32 + // webhook_id
+        8 + // credit_limit_price
+        8 + // credit_limit_timestamp
+        32 + // debt_mint
+        2 + // stop_loss_health_bps
+        1 + // buffer_breached
+        8 + // pending_credit_usdc
         (8 * 16); // _reserved
         
     /// Calculate current debt with accrued interest
@@ -77,6 +157,371 @@ impl UserPosition {
         Ok(debt_with_interest as u64)
     }
     
+    /// Add `debt` snapshotted at `borrow_index_snapshot` to the position's own
+    ///
+    /// The combined snapshot is the debt-weighted harmonic blend of the two, so
+    /// the sum accrues from `current_borrow_index` onward exactly as the two
+    /// debts would have separately: it is set so the combined principal is
+    /// worth what both are owed at the current index.
+    pub fn absorb_debt(&mut self, debt: u64, borrow_index_snapshot: u128, current_borrow_index: u128) -> Result<()> {
+        if debt == 0 {
+            return Ok(());
+        }
+        if self.debt_usdc == 0 {
+            self.debt_usdc = debt;
+            self.borrow_index_snapshot = borrow_index_snapshot;
+            return Ok(());
+        }
+        
+        let owed = (self.calculate_debt_with_interest(current_borrow_index)? as u128)
+            .checked_add(
+                (debt as u128)
+                    .checked_mul(current_borrow_index)
+                    .ok_or(error!(crate::errors::CreditError::MathOverflow))?
+                    .checked_div(borrow_index_snapshot)
+                    .ok_or(error!(crate::errors::CreditError::MathOverflow))?
+            )
+            .ok_or(error!(crate::errors::CreditError::MathOverflow))?;
+        let combined = self.debt_usdc
+            .checked_add(debt)
+            .ok_or(crate::errors::CreditError::MathOverflow)?;
+        
+        // combined * current / snapshot == owed
+        self.borrow_index_snapshot = (combined as u128)
+            .checked_mul(current_borrow_index)
+            .ok_or(error!(crate::errors::CreditError::MathOverflow))?
+            .checked_div(owed)
+            .ok_or(error!(crate::errors::CreditError::MathOverflow))?;
+        self.debt_usdc = combined;
+        
+        Ok(())
+    }
+    
+    /// Whether the position's debt is owed in USDC
+    pub fn owes_usdc(&self) -> bool {
+        self.debt_mint == Pubkey::default()
+    }
+    
+    /// Mint the position's debt is owed in
+    pub fn owed_mint<'a>(&'a self, usdc_mint: &'a Pubkey) -> &'a Pubkey {
+        if self.owes_usdc() { usdc_mint } else { &self.debt_mint }
+    }
+    
+    /// USDC value of `debt` owed in the position's debt mint (see `Config::debt_valuation`)
+    pub fn debt_value(
+        &self,
+        config: &Config,
+        debt: u64,
+        debt_oracle: Option<&AccountInfo>,
+        clock: &Clock,
+        max_staleness_slots: u64,
+    ) -> Result<u64> {
+        if debt == 0 {
+            return Ok(0);
+        }
+        config
+            .debt_valuation(&self.debt_mint, debt_oracle, clock, max_staleness_slots)?
+            .value(debt)
+    }
+    
+    /// Credit left under `credit_limit` once the position's (settled) debt is counted
+    pub fn available_credit(
+        &self,
+        config: &Config,
+        credit_limit: u64,
+        debt_oracle: Option<&AccountInfo>,
+        clock: &Clock,
+        max_staleness_slots: u64,
+    ) -> Result<u64> {
+        let debt_value = self.debt_value(config, self.debt_usdc, debt_oracle, clock, max_staleness_slots)?;
+        Ok(credit_limit.saturating_sub(debt_value))
+    }
+    
+    /// Borrow in `debt_mint` (default pubkey = USDC): a debt-free position may
+    /// switch assets, one with settled debt has to keep borrowing the same one
+    pub fn select_debt_mint(&mut self, debt_mint: Pubkey) -> Result<()> {
+        if self.debt_usdc == 0 {
+            self.debt_mint = debt_mint;
+        }
+        require_keys_eq!(self.debt_mint, debt_mint, crate::errors::CreditError::DebtMintMismatch);
+        Ok(())
+    }
+    
+    /// Every collateral the position holds in either representation: the legacy
+    /// `collateral_mint`/`collateral_amount` pair, then the multi-collateral slots
+    pub fn collateral_iter(&self) -> impl Iterator<Item = PositionCollateral> + '_ {
+        let legacy = PositionCollateral {
+            mint: self.collateral_mint,
+            amount: self.collateral_amount,
+        };
+        std::iter::once(legacy)
+            .filter(|entry| entry.amount > 0)
+            .chain(self.collaterals.iter().copied().filter(|entry| entry.is_active() && entry.amount > 0))
+    }
+    
+    /// USD value (6 decimals) of each collateral the position holds, in
+    /// `collateral_iter` order
+    ///
+    /// The legacy pair is valued at `legacy_price`, read by the caller from the
+    /// feed `Config::check_oracle` accepts for `collateral_mint`. Slot collateral
+    /// is priced on its own registered oracle at its mint's decimals: `oracles`
+    /// holds the oracle of every occupied slot in slot order, followed by the
+    /// exchange-rate feed of each held collateral priced through one. `select`
+    /// applies the caller's EMA and confidence-band choice to each slot's feed.
+    pub fn collateral_values(
+        &self,
+        config: &Config,
+        legacy_price: u64,
+        oracles: &[AccountInfo],
+        clock: &Clock,
+        max_staleness_slots: u64,
+        select: impl Fn(PythPrice) -> Result<u64>,
+    ) -> Result<Vec<(Pubkey, u64)>> {
+        // collateral_iter yields the legacy pair first, when it holds anything
+        let legacy_held = usize::from(self.collateral_amount > 0);
+        let slot_count = self.collateral_iter().count() - legacy_held;
+        require!(oracles.len() >= slot_count, crate::errors::CreditError::InvalidOracle);
+        let (slot_oracles, mut rate_oracles) = oracles.split_at(slot_count);
+        
+        let mut values = Vec::new();
+        for (index, entry) in self.collateral_iter().enumerate() {
+            if index < legacy_held {
+                values.push((entry.mint, calculate_collateral_value(entry.amount, legacy_price)?));
+                continue;
+            }
+            let oracle = &slot_oracles[index - legacy_held];
+            let collateral_type = config
+                .collateral_type(&entry.mint)
+                .ok_or(crate::errors::CreditError::InvalidCollateralMint)?;
+            require_keys_eq!(oracle.key(), collateral_type.oracle, crate::errors::CreditError::InvalidOracle);
+            
+            let rate_oracle = if collateral_type.has_exchange_rate() {
+                let (first, rest) = rate_oracles.split_first().ok_or(crate::errors::CreditError::InvalidOracle)?;
+                rate_oracles = rest;
+                Some(first)
+            } else {
+                None
+            };
+            let price = select(config.collateral_type_price(
+                collateral_type,
+                oracle,
+                rate_oracle,
+                clock,
+                max_staleness_slots
+            )?)?;
+            values.push((entry.mint, calculate_token_value(entry.amount, price, collateral_type.decimals)?));
+        }
+        
+        Ok(values)
+    }
+    
+    /// Whether any collateral is held in the multi-collateral slots
+    pub fn has_collateral_entries(&self) -> bool {
+        self.collaterals.iter().any(|entry| entry.is_active() && entry.amount > 0)
+    }
+    
+    /// Credit collateral to a mint's slot, taking the first free slot for a new mint
+    pub fn add_collateral(&mut self, mint: Pubkey, amount: u64) -> Result<()> {
+        let index = match self.collaterals.iter().position(|c| c.is_active() && c.mint == mint) {
+            Some(index) => index,
+            None => self.collaterals
+                .iter()
+                .position(|c| !c.is_active())
+                .ok_or(crate::errors::CreditError::PositionCollateralsFull)?,
+        };
+        
+        let entry = &mut self.collaterals[index];
+        entry.mint = mint;
+        entry.amount = entry.amount
+            .checked_add(amount)
+            .ok_or(crate::errors::CreditError::MathOverflow)?;
+        
+        Ok(())
+    }
+    
+    /// Credit limit after a deposit that brings collateral to `collateral_value_usd`
+    ///
+    /// Within the configured interval of the last computation, and while the price
+    /// stays within the configured move of the one it used, the cached limit is
+    /// kept: it only omits the new collateral, so it errs low until the next
+    /// recompute (borrows always recompute).
+    pub fn deposit_credit_limit(
+        &mut self,
+        config: &Config,
+        collateral_value_usd: u64,
+        price: u64,
+        current_timestamp: i64,
+    ) -> Result<u64> {
+        let interval = config.credit_limit_refresh_interval_secs;
+        if interval > 0
+            && self.credit_limit_price > 0
+            && current_timestamp.saturating_sub(self.credit_limit_timestamp) < interval
+        {
+            let price_move_bps = (price.abs_diff(self.credit_limit_price) as u128)
+                .checked_mul(BPS_PRECISION as u128)
+                .ok_or(error!(crate::errors::CreditError::MathOverflow))?
+                / self.credit_limit_price as u128;
+            if price_move_bps < config.credit_limit_refresh_price_bps as u128 {
+                msg!("Keeping cached credit limit");
+                return Ok(self.credit_limit);
+            }
+        }
+        
+        self.credit_limit_price = price;
+        self.credit_limit_timestamp = current_timestamp;
+        calculate_max_borrow(collateral_value_usd, config.credit_limit_ltv_bps())
+    }
+    
+    /// Store a recomputed credit limit, emitting `CreditLimitChanged` when it moves
+    /// (`reason` is one of the `CREDIT_LIMIT_*` constants)
+    pub fn update_credit_limit(&mut self, credit_limit: u64, reason: u8, oracle_price: u64, timestamp: i64) {
+        if credit_limit == self.credit_limit {
+            return;
+        }
+        
+        emit!(CreditLimitChanged {
+            user: self.owner,
+            old_credit_limit: self.credit_limit,
+            new_credit_limit: credit_limit,
+            reason,
+            oracle_price,
+            timestamp,
+        });
+        self.credit_limit = credit_limit;
+    }
+    
+    /// Advance the event sequence for an operation that emits a position event
+    pub fn next_event_seq(&mut self) -> u64 {
+        self.event_seq += 1;
+        self.event_seq
+    }
+    
+    /// Whether collateral has been held long enough to borrow against
+    pub fn meets_min_age(&self, min_age_secs: i64, current_timestamp: i64) -> bool {
+        if min_age_secs == 0 {
+            return true;
+        }
+        self.first_deposit_timestamp != 0
+            && current_timestamp.saturating_sub(self.first_deposit_timestamp) >= min_age_secs
+    }
+    
+    /// Fee owed on `repay_amount` repaid before the configured minimum loan
+    /// term has elapsed since the borrow
+    ///
+    /// Every early repayment is charged, not only the one that clears the
+    /// debt, and the fee rounds up so splitting a payoff into small repays
+    /// can't round it away.
+    pub fn prepayment_fee(
+        &self,
+        config: &Config,
+        repay_amount: u64,
+        current_timestamp: i64,
+    ) -> Result<u64> {
+        let early = self.borrow_start_timestamp != 0
+            && current_timestamp.saturating_sub(self.borrow_start_timestamp) < config.min_loan_term_secs;
+        if config.prepayment_fee_bps == 0 || repay_amount == 0 || !early {
+            return Ok(0);
+        }
+        
+        let fee = (repay_amount as u128)
+            .checked_mul(config.prepayment_fee_bps as u128)
+            .ok_or(error!(crate::errors::CreditError::MathOverflow))?
+            .checked_add(BPS_PRECISION as u128 - 1)
+            .ok_or(error!(crate::errors::CreditError::MathOverflow))?
+            / BPS_PRECISION as u128;
+        
+        Ok(fee as u64)
+    }
+    
+    /// Settle accrued interest into debt_usdc at the current borrow index of its collateral
+    ///
+    /// An active subsidy waives its share of the interest for the time it covered,
+    /// paid out of protocol reserves (never more than the reserves hold).
+    /// Returns the settled debt.
+    pub fn settle_interest(&mut self, config: &mut Config, current_timestamp: i64) -> Result<u64> {
+        // Positions accrue at the rate of the collateral backing them
+        let borrow_index = config.borrow_index_for(&self.collateral_mint);
+        let debt_with_interest = self.calculate_debt_with_interest(borrow_index)?;
+        let interest = debt_with_interest.saturating_sub(self.debt_usdc);
+        
+        // Subsidies are paid from USDC reserves, so only USDC debt gets them
+        let mut subsidy = 0u64;
+        if interest > 0 && self.subsidy_bps > 0 && self.owes_usdc() {
+            // Portion of the settlement window the subsidy was active for
+            let elapsed = current_timestamp.saturating_sub(self.last_interest_timestamp);
+            let covered = self.subsidy_expiry_timestamp
+                .saturating_sub(self.last_interest_timestamp)
+                .clamp(0, elapsed);
+            
+            let covered_interest = if elapsed == 0 {
+                if current_timestamp < self.subsidy_expiry_timestamp { interest as u128 } else { 0 }
+            } else {
+                (interest as u128)
+                    .checked_mul(covered as u128)
+                    .ok_or(error!(crate::errors::CreditError::MathOverflow))?
+                    / elapsed as u128
+            };
+            
+            subsidy = (covered_interest
+                .checked_mul(self.subsidy_bps as u128)
+                .ok_or(error!(crate::errors::CreditError::MathOverflow))?
+                / 10_000u128) as u64;
+            subsidy = subsidy.min(config.total_reserves_usdc);
+            config.total_reserves_usdc -= subsidy;
+        }
+        
+        // Keep the global debt total in step with the settled position, so
+        // later repayments and liquidations subtract from a total that includes it
+        let settled_debt = debt_with_interest - subsidy;
+        let total_debt = config.debt_total_mut(&self.debt_mint)?;
+        *total_debt = if settled_debt >= self.debt_usdc {
+            total_debt
+                .checked_add(settled_debt - self.debt_usdc)
+                .ok_or(error!(crate::errors::CreditError::MathOverflow))?
+        } else {
+            total_debt.saturating_sub(self.debt_usdc - settled_debt)
+        };
+        
+        self.debt_usdc = settled_debt;
+        self.borrow_index_snapshot = borrow_index;
+        self.last_interest_timestamp = current_timestamp;
+        
+        if subsidy > 0 {
+            msg!("Interest subsidy of {} USDC paid from reserves", subsidy);
+        }
+        
+        Ok(self.debt_usdc)
+    }
+    
+    /// Log every field of the position after a mutation (verbose-state builds only)
+    ///
+    /// The health factor is computed at `collateral_price` when the handler read one.
+    #[cfg(feature = "verbose-state")]
+    pub fn log_state(&self, collateral_price: Option<u64>, liquidation_threshold_bps: u16) {
+        let health_factor = collateral_price
+            .and_then(|price| self.calculate_health_factor(price, liquidation_threshold_bps, self.debt_usdc).ok());
+        msg!(
+            "Position state: owner={} collateral_mint={} collateral={} debt={} index_snapshot={} credit_limit={} health={:?}",
+            self.owner,
+            self.collateral_mint,
+            self.collateral_amount,
+            self.debt_usdc,
+            self.borrow_index_snapshot,
+            self.credit_limit,
+            health_factor
+        );
+        msg!(
+            "Position state: lifetime_borrows={} lifetime_repayments={} liquidations={} bad_debt={} event_seq={} updated_slot={} updated_at={}",
+            self.lifetime_borrows,
+            self.lifetime_repayments,
+            self.liquidation_count,
+            self.bad_debt_usdc,
+            self.event_seq,
+            self.last_update_slot,
+            self.last_update_timestamp
+        );
+    }
+    
     /// Check if position is healthy given current prices
     pub fn is_healthy(
         &self,
@@ -89,11 +534,11 @@ impl UserPosition {
         }
         
         // Calculate collateral value in USDC
-        // collateral_value = collateral_amount * price / 10^(collateral_decimals - usdc_decimals)
+        // collateral_value = collateral_amount * price / 10^collateral_decimals
         let collateral_value = (self.collateral_amount as u128)
             .checked_mul(collateral_price as u128)
             .ok_or(error!(crate::errors::CreditError::MathOverflow))?
-            .checked_div(1_000u128) // Convert 9 decimals to 6 decimals
+            .checked_div(1_000_000_000u128) // Price has 6 decimals, collateral 9
             .ok_or(error!(crate::errors::CreditError::MathOverflow))?;
             
         // Calculate liquidation value
@@ -106,7 +551,11 @@ impl UserPosition {
         Ok(liquidation_value >= current_debt as u128)
     }
     
-    /// Calculate health factor (collateral_value * liq_threshold / debt)
+    /// Health factor (collateral_value * liq_threshold / debt) of the legacy
+    /// collateral pair valued at `collateral_price`
+    ///
+    /// Slot collateral has to be priced on its own feeds (`collateral_values`),
+    /// so a position holding any is rejected rather than valued at this price.
     pub fn calculate_health_factor(
         &self,
         collateral_price: u64,
@@ -116,28 +565,28 @@ impl UserPosition {
         if current_debt == 0 {
             return Ok(u64::MAX); // Infinite health factor when no debt
         }
+        require!(!self.has_collateral_entries(), crate::errors::CreditError::SlotCollateralUnsupported);
         
-        // Calculate collateral value in USDC
+        // Calculate collateral value in USDC, kept in u128 so large positions
+        // aren't truncated before the health factor divide
         let collateral_value = (self.collateral_amount as u128)
             .checked_mul(collateral_price as u128)
             .ok_or(error!(crate::errors::CreditError::MathOverflow))?
-            .checked_div(1_000u128) // Convert 9 decimals to 6 decimals
-            .ok_or(error!(crate::errors::CreditError::MathOverflow))?;
-            
-        // Calculate liquidation value
-        let liquidation_value = collateral_value
-            .checked_mul(liquidation_threshold_bps as u128)
-            .ok_or(error!(crate::errors::CreditError::MathOverflow))?
-            .checked_div(10_000u128)
-            .ok_or(error!(crate::errors::CreditError::MathOverflow))?;
-            
-        // Health factor = liquidation_value / debt (with 4 decimal precision)
-        let health_factor = liquidation_value
-            .checked_mul(10_000u128)
-            .ok_or(error!(crate::errors::CreditError::MathOverflow))?
-            .checked_div(current_debt as u128)
+            .checked_div(1_000_000_000u128) // Price has 6 decimals, collateral 9
             .ok_or(error!(crate::errors::CreditError::MathOverflow))?;
             
-        Ok(health_factor as u64)
+        health_factor_from_value(collateral_value, current_debt, liquidation_threshold_bps)
     }
-} 
\ No newline at end of file
+}
+
+#[event]
+pub struct CreditLimitChanged {
+    pub user: Pubkey,
+    pub old_credit_limit: u64,
+    pub new_credit_limit: u64,
+    /// One of the `CREDIT_LIMIT_*` constants
+    pub reason: u8,
+    /// Collateral price the new limit was computed at (6 decimals)
+    pub oracle_price: u64,
+    pub timestamp: i64,
+}
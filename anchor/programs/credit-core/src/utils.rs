@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
-use crate::constants::*;
 use crate::errors::CreditError;
-use crate::oracle::get_pyth_price;
+use crate::constants::{BPS_PRECISION, HEALTH_FACTOR_SCALE, MAX_INTEREST_RATE_BPS, WAD_PRECISION};
+use crate::oracle::{get_pyth_price, is_pyth_account, PythPrice};
 
 /// Mock oracle price for devnet testing fallback
 /// Returns a hardcoded price for SOL/USD
@@ -11,27 +11,44 @@ pub fn get_mock_sol_price() -> Result<u64> {
     Ok(100_000_000) // $100.00
 }
 
-/// Get price with fallback to mock if oracle fails
+/// Get price with fallback to mock if no oracle is available
+/// A real Pyth feed that fails validation (e.g. stale) is an error, not a fallback
 pub fn get_price_with_fallback(
     price_account_info: &AccountInfo,
-) -> Result<u64> {
-    // Try real Pyth oracle first
-    get_pyth_price(price_account_info)
-        .map(|price| price as u64)
-        .or_else(|_| {
-            // Fallback to mock price if oracle fails
-            msg!("Warning: Using mock price due to oracle failure");
-            get_mock_sol_price()
-        })
+    clock: &Clock,
+    max_staleness_slots: u64,
+    expected_quote_decimals: Option<u8>,
+) -> Result<PythPrice> {
+    if !is_pyth_account(price_account_info) {
+        // Fallback to mock price when no Pyth feed is deployed (devnet/localnet)
+        msg!("Warning: Using mock price due to missing oracle");
+        return Ok(PythPrice {
+            price: get_mock_sol_price()?,
+            confidence: 0,
+            publish_slot: clock.slot,
+            ema_price: get_mock_sol_price()?,
+            ema_confidence: 0,
+        });
+    }
+    
+    get_pyth_price(price_account_info, clock, max_staleness_slots, expected_quote_decimals)
 }
 
 /// Calculate borrow index based on time elapsed
+///
+/// Rates are non-negative, so the index never shrinks: a zero rate or a clock
+/// that hasn't moved forward leaves it unchanged.
 pub fn calculate_borrow_index(
     last_update_timestamp: i64,
     current_timestamp: i64,
     borrow_index: u128,  // Changed to match Config type
     interest_rate_bps: u16,
 ) -> Result<u128> {      // Returns u128 to match Config
+    require!(interest_rate_bps <= MAX_INTEREST_RATE_BPS, CreditError::InvalidPercentage);
+    if interest_rate_bps == 0 || current_timestamp <= last_update_timestamp {
+        return Ok(borrow_index);
+    }
+    
     // Time elapsed in seconds
     let time_diff = current_timestamp
         .checked_sub(last_update_timestamp)
@@ -60,6 +77,118 @@ pub fn calculate_borrow_index(
     Ok(new_index)
 }
 
+/// Compound the borrow index once per period for a number of periods
+/// new_index = old_index * (1 + rate * period / seconds_per_year) ^ periods
+pub fn compound_borrow_index(
+    borrow_index: u128,
+    interest_rate_bps: u16,
+    period_secs: u64,
+    periods: u64,
+) -> Result<u128> {
+    require!(interest_rate_bps <= MAX_INTEREST_RATE_BPS, CreditError::InvalidPercentage);
+    if interest_rate_bps == 0 {
+        // No growth to compound
+        return Ok(borrow_index);
+    }
+    
+    let seconds_per_year = 365 * 24 * 60 * 60u64;
+    
+    // Per-period growth factor (WAD precision)
+    let period_rate = WAD_PRECISION
+        .checked_mul(interest_rate_bps as u128)
+        .ok_or(error!(CreditError::MathOverflow))?
+        .checked_mul(period_secs as u128)
+        .ok_or(error!(CreditError::MathOverflow))?
+        .checked_div(seconds_per_year as u128)
+        .ok_or(error!(CreditError::MathOverflow))?
+        .checked_div(10000) // Convert from basis points
+        .ok_or(error!(CreditError::MathOverflow))?;
+    let mut factor = WAD_PRECISION
+        .checked_add(period_rate)
+        .ok_or(error!(CreditError::MathOverflow))?;
+    
+    // Exponentiation by squaring keeps long idle gaps cheap
+    let mut growth = WAD_PRECISION;
+    let mut remaining = periods;
+    while remaining > 0 {
+        if remaining & 1 == 1 {
+            growth = wad_mul(growth, factor)?;
+        }
+        remaining >>= 1;
+        if remaining > 0 {
+            factor = wad_mul(factor, factor)?;
+        }
+    }
+    
+    wad_mul(borrow_index, growth)
+}
+
+/// Reject an accrual that grows an index by more than the `max_growth_wad`
+/// factor (0 = unbounded): a jump that large points at a corrupted timestamp
+/// or clock, so it fails loudly instead of inflating every debt
+pub fn checked_index_growth(old_index: u128, new_index: u128, max_growth_wad: u128) -> Result<u128> {
+    if max_growth_wad > 0 {
+        let ceiling = wad_mul(old_index, max_growth_wad)?;
+        if new_index > ceiling {
+            msg!("Borrow index jump from {} to {} exceeds ceiling {}", old_index, new_index, ceiling);
+            return err!(CreditError::IndexJumpTooLarge);
+        }
+    }
+    
+    Ok(new_index)
+}
+
+/// Multiply by a WAD-precision factor without overflowing on RAY-scale values
+fn wad_mul(value: u128, factor_wad: u128) -> Result<u128> {
+    let whole = (value / WAD_PRECISION)
+        .checked_mul(factor_wad)
+        .ok_or(error!(CreditError::MathOverflow))?;
+    let fraction = (value % WAD_PRECISION)
+        .checked_mul(factor_wad)
+        .ok_or(error!(CreditError::MathOverflow))?
+        / WAD_PRECISION;
+    
+    whole
+        .checked_add(fraction)
+        .ok_or(error!(CreditError::MathOverflow))
+}
+
+/// Value collateral (9 decimals) in USDC (6 decimals) at a 6-decimal price
+pub fn calculate_collateral_value(
+    collateral_amount: u64,
+    price: u64,
+) -> Result<u64> {
+    let value = (collateral_amount as u128)
+        .checked_mul(price as u128)
+        .ok_or(error!(CreditError::MathOverflow))?
+        .checked_div(1_000_000_000) // Convert from lamports to SOL
+        .ok_or(error!(CreditError::MathOverflow))?;
+    
+    u64::try_from(value).map_err(|_| error!(CreditError::MathOverflow))
+}
+
+/// Value `amount` of a token with `decimals` in USDC (6 decimals) at a 6-decimal price
+pub fn calculate_token_value(
+    amount: u64,
+    price: u64,
+    decimals: u8,
+) -> Result<u64> {
+    let value = (amount as u128)
+        .checked_mul(price as u128)
+        .ok_or(error!(CreditError::MathOverflow))?
+        .checked_div(10u128.pow(decimals as u32))
+        .ok_or(error!(CreditError::MathOverflow))?;
+    
+    u64::try_from(value).map_err(|_| error!(CreditError::MathOverflow))
+}
+
+/// Total of per-collateral USD values (see `UserPosition::collateral_values`)
+pub fn sum_collateral_values(values: &[(Pubkey, u64)]) -> Result<u64> {
+    values.iter().try_fold(0u64, |total, &(_, value)| {
+        total.checked_add(value).ok_or(error!(CreditError::MathOverflow))
+    })
+}
+
 /// Calculate maximum borrow amount based on collateral value and LTV
 pub fn calculate_max_borrow(
     collateral_value_usdc: u64,
@@ -74,7 +203,17 @@ pub fn calculate_max_borrow(
     Ok(max_borrow as u64)
 }
 
-/// Calculate health factor
+/// Borrow power summed over several collaterals, each `(value_usdc, ltv_bps)`
+/// taken at its own LTV
+pub fn calculate_weighted_max_borrow(collaterals: &[(u64, u16)]) -> Result<u64> {
+    collaterals.iter().try_fold(0u64, |total, &(collateral_value_usdc, ltv_bps)| {
+        total
+            .checked_add(calculate_max_borrow(collateral_value_usdc, ltv_bps)?)
+            .ok_or(error!(CreditError::MathOverflow))
+    })
+}
+
+/// Calculate health factor in the canonical scale (`HEALTH_FACTOR_SCALE` = 1.0)
 pub fn calculate_health_factor(
     collateral_value_usdc: u64,
     debt_usdc: u64,
@@ -85,19 +224,99 @@ pub fn calculate_health_factor(
         return Ok(u64::MAX);
     }
     
-    let liquidation_value = (collateral_value_usdc as u128)
+    health_factor_from_value(collateral_value_usdc as u128, debt_usdc, liquidation_threshold_bps)
+}
+
+/// Health factor in bps (10000 = 1.0) of a collateral value against non-zero debt
+///
+/// Divides once at the end so small positions keep their precision; the
+/// numerator stays below 2^128 for any u64 value, and health factors past
+/// u64::MAX (whale collateral against dust debt) saturate instead of wrapping.
+pub fn health_factor_from_value(
+    collateral_value_usdc: u128,
+    debt_usdc: u64,
+    liquidation_threshold_bps: u16,
+) -> Result<u64> {
+    // Health factor = collateral_value * threshold / debt
+    let numerator = collateral_value_usdc
         .checked_mul(liquidation_threshold_bps as u128)
         .ok_or(error!(CreditError::MathOverflow))?
+        .checked_mul(HEALTH_FACTOR_SCALE as u128)
+        .ok_or(error!(CreditError::MathOverflow))?;
+    let denominator = (debt_usdc as u128)
+        .checked_mul(BPS_PRECISION as u128)
+        .ok_or(error!(CreditError::MathOverflow))?;
+    let health_factor = numerator
+        .checked_div(denominator)
+        .ok_or(error!(CreditError::MathOverflow))?;
+    
+    Ok(u64::try_from(health_factor).unwrap_or(u64::MAX))
+}
+
+/// Calculate the liquidation bonus owed on a repaid amount
+pub fn calculate_liquidation_bonus(
+    repay_amount_usdc: u64,
+    liquidation_bonus_bps: u16,
+) -> Result<u64> {
+    let bonus = (repay_amount_usdc as u128)
+        .checked_mul(liquidation_bonus_bps as u128)
+        .ok_or(error!(CreditError::MathOverflow))?
         .checked_div(10000) // Convert from basis points
         .ok_or(error!(CreditError::MathOverflow))?;
     
-    // Health factor = liquidation_value / debt
-    // Scaled by 100 for precision (100 = 1.0)
-    let health_factor = liquidation_value
-        .checked_mul(100)
+    Ok(bonus as u64)
+}
+
+/// Convert a USDC amount (6 decimals) into collateral (9 decimals) at a 6-decimal price
+pub fn usdc_to_collateral(
+    usdc_amount: u64,
+    price: u64,
+) -> Result<u64> {
+    let collateral = (usdc_amount as u128)
+        .checked_mul(1_000_000_000) // Convert from SOL to lamports
         .ok_or(error!(CreditError::MathOverflow))?
-        .checked_div(debt_usdc as u128)
+        .checked_div(price as u128)
         .ok_or(error!(CreditError::MathOverflow))?;
     
-    Ok(health_factor as u64)
+    u64::try_from(collateral).map_err(|_| error!(CreditError::MathOverflow))
+}
+
+/// Convert a USDC amount (6 decimals) into a token with `decimals` at a 6-decimal price
+pub fn usdc_to_token(
+    usdc_amount: u64,
+    price: u64,
+    decimals: u8,
+) -> Result<u64> {
+    let amount = (usdc_amount as u128)
+        .checked_mul(10u128.pow(decimals as u32))
+        .ok_or(error!(CreditError::MathOverflow))?
+        .checked_div(price as u128)
+        .ok_or(error!(CreditError::MathOverflow))?;
+    
+    u64::try_from(amount).map_err(|_| error!(CreditError::MathOverflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // $1e12 in USDC decimals, far above any feed the oracle sanity bound accepts
+    const WHALE_PRICE: u64 = 1_000_000_000_000_000_000;
+    const TEN_SOL: u64 = 10_000_000_000;
+
+    #[test]
+    fn values_near_maximum_collateral_without_overflow() {
+        // 10 SOL at $1e12 is $1e13, just under u64::MAX in USDC decimals
+        let value = calculate_collateral_value(TEN_SOL, WHALE_PRICE).unwrap();
+        assert_eq!(value, 10_000_000_000_000_000_000);
+
+        // $1e13 * 60% / $1 debt = 6e12, i.e. 6e16 bps
+        assert_eq!(calculate_health_factor(value, 1_000_000, 6000).unwrap(), 60_000_000_000_000_000);
+    }
+
+    #[test]
+    fn saturates_health_factor_against_dust_debt() {
+        let value = calculate_collateral_value(TEN_SOL, WHALE_PRICE).unwrap();
+        assert_eq!(calculate_health_factor(value, 1_000, 6000).unwrap(), u64::MAX);
+    }
 }
@@ -1,8 +1,65 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
-import { TOKEN_PROGRAM_ID, createMint, createAccount, mintTo, getAccount } from "@solana/spl-token";
+import {
+  TOKEN_PROGRAM_ID,
+  createMint,
+  createAccount,
+  mintTo,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  transfer,
+} from "@solana/spl-token";
 import { expect } from "chai";
+import {
+  ensureConfig,
+  setParams,
+  fetchConfig,
+  fetchPosition,
+  expectError,
+  userWithCollateral,
+  recordDebt,
+  refreshPosition,
+  refreshHealthFactor,
+  HEALTH_FACTOR_SCALE,
+  pythFeed,
+  fundedKeypair,
+  openPosition,
+  depositWsol,
+  depositWsolBatch,
+  initAndDeposit,
+  depositAndBorrow,
+  setMaxInterestRate,
+  setPauseFlags,
+  PAUSE_BORROWS,
+  accrueInterest,
+  setPaused,
+  getConfigView,
+  migratePositionMint,
+  recoverToken,
+  wrapSol,
+  vaultPda,
+  CLIENT_VERSION,
+  setBorrowIndex,
+  setOracleDecimals,
+  setCollateralType,
+  fetchCollateralType,
+  depositMultiCollateral,
+  EMA_FOR_DEPOSITS,
+  EMA_FOR_BORROWS,
+  reconcileCollateral,
+  borrowUsdc,
+  repayUsdc,
+  VALUATION_MID,
+  VALUATION_BID,
+  initVault,
+  positionPda,
+  pendingParamsPda,
+  applyPendingParams,
+  waitForSlot,
+  vaultFixture,
+  mergePositions,
+} from "./helpers";
 
 // Import the generated types (this will be generated by anchor build)
 type CreditCore = any; // Replace with generated type
@@ -128,6 +185,1242 @@ describe("credit-core", () => {
     console.log("✅ Program structure validated");
   });
 
+  describe("initialize", () => {
+    it("rejects a WSOL mint other than the native mint", async function () {
+      const [config] = PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId);
+      if (await provider.connection.getAccountInfo(config)) {
+        // The singleton config already exists, so initialize cannot reach its parameter checks
+        this.skip();
+      }
+
+      const oracle = pythFeed("sol-usd-100");
+      await expectError(
+        program.methods
+          .initialize({
+            ltvMaxBps: 5000,
+            liquidationThresholdBps: 6000,
+            liquidationBonusBps: 600,
+            interestRateBps: 1200,
+            solUsdOracle: oracle,
+            jitoSolUsdOracle: oracle,
+            usdcMint,
+            jitoSolMint,
+            wsolMint: Keypair.generate().publicKey,
+          })
+          .accounts({
+            config,
+            admin: provider.wallet.publicKey,
+          })
+          .rpc(),
+        "InvalidCollateralMint"
+      );
+    });
+
+    it("rejects a collateral mint equal to the USDC mint", async function () {
+      const [config] = PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId);
+      if (await provider.connection.getAccountInfo(config)) {
+        this.skip();
+      }
+
+      const oracle = pythFeed("sol-usd-100");
+      await expectError(
+        program.methods
+          .initialize({
+            ltvMaxBps: 5000,
+            liquidationThresholdBps: 6000,
+            liquidationBonusBps: 600,
+            interestRateBps: 1200,
+            solUsdOracle: oracle,
+            jitoSolUsdOracle: oracle,
+            usdcMint,
+            jitoSolMint: usdcMint,
+            wsolMint: new PublicKey("So11111111111111111111111111111111111111112"),
+          })
+          .accounts({
+            config,
+            admin: provider.wallet.publicKey,
+          })
+          .rpc(),
+        "DuplicateMint"
+      );
+    });
+
+    it("rejects migrating jitoSOL onto the USDC mint", async () => {
+      const { usdcMint: protocolUsdcMint } = await ensureConfig();
+      await expectError(setParams({ jitoSolMint: protocolUsdcMint }), "DuplicateMint");
+    });
+
+    it("rejects a jitoSOL mint equal to the WSOL mint", async function () {
+      const [config] = PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId);
+      if (await provider.connection.getAccountInfo(config)) {
+        this.skip();
+      }
+
+      const oracle = pythFeed("sol-usd-100");
+      const wsolMint = new PublicKey("So11111111111111111111111111111111111111112");
+      await expectError(
+        program.methods
+          .initialize({
+            ltvMaxBps: 5000,
+            liquidationThresholdBps: 6000,
+            liquidationBonusBps: 600,
+            interestRateBps: 1200,
+            solUsdOracle: oracle,
+            jitoSolUsdOracle: oracle,
+            usdcMint,
+            jitoSolMint: wsolMint,
+            wsolMint,
+          })
+          .accounts({
+            config,
+            admin: provider.wallet.publicKey,
+          })
+          .rpc(),
+        "DuplicateMint"
+      );
+    });
+
+    it("rejects migrating jitoSOL onto the WSOL mint", async () => {
+      const { wsolMint } = await ensureConfig();
+      await expectError(setParams({ jitoSolMint: wsolMint }), "DuplicateMint");
+    });
+  });
+
+  describe("oracle staleness bounds", () => {
+    before(async () => {
+      await ensureConfig();
+    });
+
+    it("initializes a looser borrow bound than the liquidation bound", async () => {
+      const config = await fetchConfig();
+      expect(config.borrowMaxStalenessSlots.toNumber()).to.equal(60);
+      expect(config.liquidationMaxStalenessSlots.toNumber()).to.equal(30);
+    });
+
+    it("updates the borrow bound independently", async () => {
+      await setParams({ borrowMaxStalenessSlots: new anchor.BN(90) });
+
+      const config = await fetchConfig();
+      expect(config.borrowMaxStalenessSlots.toNumber()).to.equal(90);
+      expect(config.liquidationMaxStalenessSlots.toNumber()).to.equal(30);
+    });
+
+    it("updates the liquidation bound independently", async () => {
+      await setParams({ liquidationMaxStalenessSlots: new anchor.BN(20) });
+
+      const config = await fetchConfig();
+      expect(config.borrowMaxStalenessSlots.toNumber()).to.equal(90);
+      expect(config.liquidationMaxStalenessSlots.toNumber()).to.equal(20);
+    });
+
+    it("rejects a liquidation bound looser than the borrow bound", async () => {
+      await expectError(
+        setParams({ liquidationMaxStalenessSlots: new anchor.BN(91) }),
+        "InvalidParameter"
+      );
+    });
+
+    it("rejects a borrow bound tighter than the liquidation bound", async () => {
+      await expectError(
+        setParams({ borrowMaxStalenessSlots: new anchor.BN(10) }),
+        "InvalidParameter"
+      );
+    });
+
+    it("rejects zero and out-of-range bounds", async () => {
+      await expectError(
+        setParams({ liquidationMaxStalenessSlots: new anchor.BN(0) }),
+        "InvalidParameter"
+      );
+      await expectError(
+        setParams({ borrowMaxStalenessSlots: new anchor.BN(301) }),
+        "InvalidParameter"
+      );
+    });
+
+    after(async () => {
+      await setParams({
+        borrowMaxStalenessSlots: new anchor.BN(60),
+        liquidationMaxStalenessSlots: new anchor.BN(30),
+      });
+    });
+  });
+
+  describe("refresh_position", () => {
+    it("updates the cached health factor after a price move", async () => {
+      // 10 SOL at $100 with $400 debt: 1000 * 60% / 400 = 1.5
+      const owner = await userWithCollateral(10 * anchor.web3.LAMPORTS_PER_SOL);
+      await recordDebt(owner, 400_000_000);
+
+      await refreshPosition(owner.publicKey, pythFeed("sol-usd-100"));
+      let position = await fetchPosition(owner.publicKey);
+      expect(position.lastHealthFactor.toNumber()).to.be.closeTo(15_000, 1);
+      expect(position.creditLimit.toNumber()).to.equal(500_000_000);
+
+      // SOL drops to $80: 800 * 60% / 400 = 1.2
+      await refreshPosition(owner.publicKey, pythFeed("sol-usd-80"));
+      position = await fetchPosition(owner.publicKey);
+      expect(position.lastHealthFactor.toNumber()).to.be.closeTo(12_000, 1);
+      expect(position.creditLimit.toNumber()).to.equal(400_000_000);
+      expect(position.lastHealthTimestamp.toNumber()).to.be.greaterThan(0);
+    });
+
+    it("returns the health factor in bps", async () => {
+      const owner = await userWithCollateral(10 * anchor.web3.LAMPORTS_PER_SOL);
+      await recordDebt(owner, 400_000_000);
+
+      const healthFactor = await refreshHealthFactor(owner.publicKey, pythFeed("sol-usd-100"));
+      expect(healthFactor / HEALTH_FACTOR_SCALE).to.be.closeTo(1.5, 0.0001);
+      expect(healthFactor).to.equal((await fetchPosition(owner.publicKey)).lastHealthFactor.toNumber());
+    });
+
+    it("values legacy and multi-collateral positions alike", async () => {
+      const SOL = anchor.web3.LAMPORTS_PER_SOL;
+      const { wsolMint, solUsdOracle } = await ensureConfig();
+      await setCollateralType(wsolMint, { oracle: solUsdOracle });
+
+      // Legacy format: collateral in the single collateral_amount field
+      const legacy = await userWithCollateral(10 * SOL);
+      await recordDebt(legacy, 400_000_000);
+
+      // New format: the same collateral in a multi-collateral slot
+      const multi = await fundedKeypair(15);
+      await openPosition(multi);
+      await depositMultiCollateral(multi, wsolMint, await wrapSol(multi, 10 * SOL), 10 * SOL);
+      await recordDebt(multi, 400_000_000);
+      const multiPosition = await fetchPosition(multi.publicKey);
+      expect(multiPosition.collateralAmount.toNumber()).to.equal(0);
+
+      // Both hold $1000 against $400: 1000 * 60% / 400 = 1.5
+      expect(await refreshHealthFactor(legacy.publicKey, pythFeed("sol-usd-100"))).to.equal(15_000);
+      expect(await refreshHealthFactor(multi.publicKey, pythFeed("sol-usd-100"))).to.equal(15_000);
+      expect((await fetchPosition(multi.publicKey)).creditLimit.toNumber()).to.equal(500_000_000);
+    });
+  });
+
+  describe("deposit oracle confidence", () => {
+    it("sets a conservative credit limit when the confidence band is wide", async () => {
+      await ensureConfig();
+      const owner = await fundedKeypair(15);
+      await openPosition(owner);
+
+      // $100 +/- $10: collateral valued at $90, so 10 SOL * $90 * 50% LTV
+      await depositWsol(owner, 10 * anchor.web3.LAMPORTS_PER_SOL, pythFeed("sol-usd-100-wide"));
+
+      const position = await fetchPosition(owner.publicKey);
+      expect(position.creditLimit.toNumber()).to.equal(450_000_000);
+    });
+
+    it("uses the midpoint price when the confidence band is tight", async () => {
+      const owner = await fundedKeypair(15);
+      await openPosition(owner);
+
+      await depositWsol(owner, 10 * anchor.web3.LAMPORTS_PER_SOL, pythFeed("sol-usd-100"));
+
+      const position = await fetchPosition(owner.publicKey);
+      expect(position.creditLimit.toNumber()).to.equal(500_000_000);
+    });
+  });
+
+  describe("borrow valuation mode", () => {
+    after(async () => {
+      await setParams({ borrowValuationMode: VALUATION_MID });
+    });
+
+    it("values borrow power at the low end of the band", async () => {
+      await ensureConfig();
+      await setParams({ borrowValuationMode: VALUATION_BID });
+      const owner = await fundedKeypair(15);
+      await openPosition(owner);
+
+      // $100 +/- $0.05: 10 SOL * $99.95 * 50% LTV, even with a tight band
+      await depositWsol(owner, 10 * anchor.web3.LAMPORTS_PER_SOL, pythFeed("sol-usd-100"));
+
+      const position = await fetchPosition(owner.publicKey);
+      expect(position.creditLimit.toNumber()).to.equal(499_750_000);
+    });
+
+    it("rejects unknown modes", async () => {
+      await expectError(setParams({ borrowValuationMode: 3 }), "InvalidParameter");
+    });
+  });
+
+  describe("cached deposit credit limit", () => {
+    after(async () => {
+      await setParams({
+        creditLimitRefreshIntervalSecs: new anchor.BN(0),
+        creditLimitRefreshPriceBps: 0,
+      });
+    });
+
+    it("keeps the credit limit within the interval unless the price moves", async () => {
+      await ensureConfig();
+      // Recompute at most hourly unless the price moves 1% or more
+      await setParams({
+        creditLimitRefreshIntervalSecs: new anchor.BN(3600),
+        creditLimitRefreshPriceBps: 100,
+      });
+      const owner = await fundedKeypair(20);
+      await openPosition(owner);
+
+      await depositWsol(owner, 10 * anchor.web3.LAMPORTS_PER_SOL, pythFeed("sol-usd-100"));
+      expect((await fetchPosition(owner.publicKey)).creditLimit.toNumber()).to.equal(500_000_000);
+
+      // A 0.1% move keeps the cached limit, new collateral and all
+      await depositWsol(owner, anchor.web3.LAMPORTS_PER_SOL, pythFeed("sol-usd-100-10"));
+      let position = await fetchPosition(owner.publicKey);
+      expect(position.collateralAmount.toNumber()).to.equal(11 * anchor.web3.LAMPORTS_PER_SOL);
+      expect(position.creditLimit.toNumber()).to.equal(500_000_000);
+
+      // A 20% drop recomputes: 12 SOL * $80 * 50%
+      await depositWsol(owner, anchor.web3.LAMPORTS_PER_SOL, pythFeed("sol-usd-80"));
+      position = await fetchPosition(owner.publicKey);
+      expect(position.creditLimit.toNumber()).to.equal(480_000_000);
+    });
+  });
+
+  describe("credit limit buffer", () => {
+    after(async () => {
+      await setParams({ creditLimitBufferBps: 0 });
+    });
+
+    it("sets credit limits below the raw LTV by the buffer", async () => {
+      await ensureConfig();
+      await setParams({ creditLimitBufferBps: 500 });
+      const owner = await fundedKeypair(15);
+      await openPosition(owner);
+
+      await depositWsol(owner, 10 * anchor.web3.LAMPORTS_PER_SOL, pythFeed("sol-usd-100"));
+
+      // $1000 at 45% (50% LTV less the 5% buffer) instead of the raw $500
+      const position = await fetchPosition(owner.publicKey);
+      expect(position.creditLimit.toNumber()).to.equal(450_000_000);
+    });
+
+    it("rejects a buffer that consumes the whole LTV", async () => {
+      await expectError(setParams({ creditLimitBufferBps: 5000 }), "InvalidPercentage");
+    });
+  });
+
+  describe("non-positive oracle prices", () => {
+    it("rejects a zero price", async () => {
+      await ensureConfig();
+      const owner = await fundedKeypair(5);
+      await openPosition(owner);
+
+      await expectError(
+        depositWsol(owner, anchor.web3.LAMPORTS_PER_SOL, pythFeed("sol-usd-zero")),
+        "InvalidOracle"
+      );
+    });
+
+    it("rejects a negative price", async () => {
+      const owner = await fundedKeypair(5);
+      await openPosition(owner);
+
+      await expectError(
+        depositWsol(owner, anchor.web3.LAMPORTS_PER_SOL, pythFeed("sol-usd-negative")),
+        "InvalidOracle"
+      );
+    });
+  });
+
+  describe("oracle account layout", () => {
+    it("rejects a Pyth account with an unexpected account type", async () => {
+      await ensureConfig();
+      const owner = await fundedKeypair(5);
+      await openPosition(owner);
+
+      await expectError(
+        depositWsol(owner, anchor.web3.LAMPORTS_PER_SOL, pythFeed("sol-usd-unknown-layout")),
+        "InvalidOracle"
+      );
+    });
+  });
+
+  describe("oracle exponent range", () => {
+    it("rejects a feed whose exponent is past the supported scaling", async () => {
+      await ensureConfig();
+      const owner = await fundedKeypair(5);
+      await openPosition(owner);
+
+      // expo 20 is 26 powers of ten from USDC decimals, past what 10_i64.pow could hold
+      await expectError(
+        depositWsol(owner, anchor.web3.LAMPORTS_PER_SOL, pythFeed("sol-usd-expo-20")),
+        "OracleExponentOutOfRange"
+      );
+    });
+  });
+
+  describe("oracle quote decimals override", () => {
+    const feed = pythFeed("sol-usd-100-expo-6");
+
+    after(async () => {
+      await setOracleDecimals(feed, null);
+    });
+
+    it("rejects a feed whose exponent mismatches the expected decimals", async () => {
+      await ensureConfig();
+      const owner = await fundedKeypair(5);
+      await openPosition(owner);
+
+      await setOracleDecimals(feed, 8);
+      await expectError(
+        depositWsol(owner, anchor.web3.LAMPORTS_PER_SOL, feed),
+        "OracleDecimalsMismatch"
+      );
+
+      // Matching the feed's real precision prices it normally
+      await setOracleDecimals(feed, 6);
+      await depositWsol(owner, anchor.web3.LAMPORTS_PER_SOL, feed);
+      const position = await fetchPosition(owner.publicKey);
+      expect(position.creditLimit.toNumber()).to.equal(50_000_000);
+    });
+
+    it("rejects more than 18 expected decimals", async () => {
+      await expectError(setOracleDecimals(feed, 19), "InvalidParameter");
+    });
+  });
+
+  describe("EMA pricing", () => {
+    const feed = pythFeed("sol-usd-100-ema-90");
+
+    after(async () => {
+      await setParams({ emaPriceFlags: 0 });
+    });
+
+    it("values deposits at the EMA only for selected operations", async () => {
+      await ensureConfig();
+      const spot = await fundedKeypair(5);
+      await openPosition(spot);
+      await depositWsol(spot, anchor.web3.LAMPORTS_PER_SOL, feed);
+      // $100 spot at 50% LTV
+      expect((await fetchPosition(spot.publicKey)).creditLimit.toNumber()).to.equal(50_000_000);
+
+      await setParams({ emaPriceFlags: EMA_FOR_BORROWS });
+      const unselected = await fundedKeypair(5);
+      await openPosition(unselected);
+      await depositWsol(unselected, anchor.web3.LAMPORTS_PER_SOL, feed);
+      expect((await fetchPosition(unselected.publicKey)).creditLimit.toNumber()).to.equal(50_000_000);
+
+      await setParams({ emaPriceFlags: EMA_FOR_DEPOSITS });
+      const ema = await fundedKeypair(5);
+      await openPosition(ema);
+      await depositWsol(ema, anchor.web3.LAMPORTS_PER_SOL, feed);
+      // $90 EMA at 50% LTV
+      expect((await fetchPosition(ema.publicKey)).creditLimit.toNumber()).to.equal(45_000_000);
+    });
+
+    it("rejects unknown operation bits", async () => {
+      await expectError(setParams({ emaPriceFlags: 1 << 4 }), "InvalidParameter");
+    });
+  });
+
+  describe("collateral oracle health", () => {
+    after(async () => {
+      const { wsolMint, solUsdOracle } = await ensureConfig();
+      await setParams({ oracleHealthWindowSlots: new anchor.BN(0) });
+      await setCollateralType(wsolMint, { oracle: solUsdOracle });
+    });
+
+    it("rejects deposits while the collateral's oracle is flagged unhealthy", async () => {
+      const SOL = anchor.web3.LAMPORTS_PER_SOL;
+      const { wsolMint } = await ensureConfig();
+      const owner = await fundedKeypair(5);
+      await openPosition(owner);
+      await setParams({ oracleHealthWindowSlots: new anchor.BN(1_000) });
+
+      // The keeper sees a confidence band past the bound, so never marks it healthy
+      const wide = pythFeed("sol-usd-100-wide");
+      await setCollateralType(wsolMint, { oracle: wide });
+      await accrueInterest([wide]);
+      expect((await fetchCollateralType(wsolMint)).lastHealthyOracleSlot.toNumber()).to.equal(0);
+      await expectError(
+        depositMultiCollateral(owner, wsolMint, await wrapSol(owner, SOL), SOL),
+        "OracleUnhealthy"
+      );
+
+      // A healthy feed marked by the keeper accepts the deposit
+      const healthy = pythFeed("sol-usd-100");
+      await setCollateralType(wsolMint, { oracle: healthy });
+      await accrueInterest([healthy]);
+      expect((await fetchCollateralType(wsolMint)).lastHealthyOracleSlot.toNumber()).to.be.greaterThan(0);
+      await depositMultiCollateral(owner, wsolMint, await wrapSol(owner, SOL), SOL);
+    });
+
+    it("gates every WSOL deposit path on the oracle health window", async () => {
+      const SOL = anchor.web3.LAMPORTS_PER_SOL;
+      const { wsolMint } = await ensureConfig();
+      await setParams({ oracleHealthWindowSlots: new anchor.BN(1_000) });
+      const wide = pythFeed("sol-usd-100-wide");
+      await setCollateralType(wsolMint, { oracle: wide });
+
+      const owner = await fundedKeypair(10);
+      await openPosition(owner);
+      await expectError(depositWsol(owner, SOL), "OracleUnhealthy");
+      await expectError(depositWsolBatch(owner, [SOL, SOL]), "OracleUnhealthy");
+      await expectError(depositAndBorrow(owner, SOL, 10_000_000), "OracleUnhealthy");
+      await expectError(initAndDeposit(await fundedKeypair(5), SOL), "OracleUnhealthy");
+
+      // Once marked healthy the legacy path accepts the deposit again
+      const healthy = pythFeed("sol-usd-100");
+      await setCollateralType(wsolMint, { oracle: healthy });
+      await accrueInterest([healthy]);
+      await depositWsol(owner, SOL);
+    });
+  });
+
+  describe("exchange-rate collateral", () => {
+    const SOL = anchor.web3.LAMPORTS_PER_SOL;
+    let lstMint: PublicKey;
+    let mintAuthority: Keypair;
+
+    before(async () => {
+      await ensureConfig();
+      mintAuthority = await fundedKeypair(2);
+      lstMint = await createMint(provider.connection, mintAuthority, mintAuthority.publicKey, null, 9);
+      await initVault(lstMint);
+      await setCollateralType(lstMint, {
+        oracle: pythFeed("sol-usd-100"),
+        exchangeRateOracle: pythFeed("jitosol-sol-110"),
+      });
+    });
+
+    async function depositLst(owner: Keypair, amount: number): Promise<any> {
+      const source = await createAccount(provider.connection, owner, lstMint, owner.publicKey);
+      await mintTo(provider.connection, mintAuthority, lstMint, source, mintAuthority, amount);
+
+      let event: any;
+      const listener = program.addEventListener("CollateralDeposited", (e) => {
+        event = e;
+      });
+      await depositMultiCollateral(owner, lstMint, source, amount);
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+      return event;
+    }
+
+    it("values the LST at the SOL price times its exchange rate", async () => {
+      const owner = await fundedKeypair(2);
+      await openPosition(owner);
+
+      // $100 SOL at 1.1 SOL per jitoSOL is $110, above the naive $100
+      const event = await depositLst(owner, 2 * SOL);
+      expect(event.oraclePrice.toNumber()).to.equal(110_000_000);
+      expect(event.oraclePrice.toNumber()).to.be.greaterThan(100_000_000);
+    });
+
+    it("lends against the LST at its rate-adjusted value", async () => {
+      const owner = await fundedKeypair(2);
+      await openPosition(owner);
+      await depositLst(owner, 2 * SOL);
+
+      // 2 jitoSOL at $110 is $220, so 50% LTV lends $110 where the naive $200 would stop at $100
+      await borrowUsdc(owner, 105_000_000);
+      const position = await fetchPosition(owner.publicKey);
+      expect(position.creditLimit.toNumber()).to.equal(110_000_000);
+      await expectError(borrowUsdc(owner, 10_000_000), "DebtLimitExceeded");
+    });
+
+    it("rejects a deposit without the exchange-rate feed", async () => {
+      const owner = await fundedKeypair(2);
+      await openPosition(owner);
+      const source = await createAccount(provider.connection, owner, lstMint, owner.publicKey);
+      await mintTo(provider.connection, mintAuthority, lstMint, source, mintAuthority, SOL);
+
+      await expectError(
+        program.methods
+          .depositMultiCollateral(new anchor.BN(SOL))
+          .accounts({
+            userPosition: positionPda(owner.publicKey),
+            config: configPda,
+            collateralMint: lstMint,
+            userCollateralAccount: source,
+            vaultCollateralAccount: vaultPda(lstMint),
+            vaultAuthority: vaultAuthorityPda,
+            collateralOracle: pythFeed("sol-usd-100"),
+            owner: owner.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([owner])
+          .rpc(),
+        "InvalidOracle"
+      );
+    });
+
+    it("sums borrow power over each collateral at its own LTV", async () => {
+      const { wsolMint } = await ensureConfig();
+      await setCollateralType(wsolMint, { ltvBps: 4000 });
+      await setCollateralType(lstMint, { ltvBps: 2000 });
+
+      const owner = await fundedKeypair(10);
+      await openPosition(owner);
+      await depositMultiCollateral(owner, wsolMint, await wrapSol(owner, 5 * SOL), 5 * SOL);
+      await depositLst(owner, 5 * SOL);
+
+      // $500 of WSOL at 40% plus $550 of the LST at 20%
+      await borrowUsdc(owner, 300_000_000);
+      const position = await fetchPosition(owner.publicKey);
+      expect(position.creditLimit.toNumber()).to.equal(310_000_000);
+      await expectError(borrowUsdc(owner, 20_000_000), "DebtLimitExceeded");
+
+      await setCollateralType(wsolMint, { ltvBps: 0 });
+      await setCollateralType(lstMint, { ltvBps: 0 });
+    });
+
+    it("rejects a collateral LTV at the liquidation threshold", async () => {
+      await expectError(setCollateralType(lstMint, { ltvBps: 6000 }), "InvalidPercentage");
+    });
+  });
+
+  describe("vault delegate checks", () => {
+    it("rejects a deposit into a vault with a delegate set", async () => {
+      // Preloaded vault for this mint, owned by the vault authority but delegated
+      const mint = vaultFixture("delegated-mint");
+      expect(vaultPda(mint).equals(vaultFixture("delegated-vault"))).to.be.true;
+
+      const owner = await fundedKeypair(2);
+      await openPosition(owner);
+      const source = await createAccount(provider.connection, owner, mint, owner.publicKey);
+
+      // Rejected by the vault's account constraints, before the mint is looked up
+      await expectError(
+        program.methods
+          .depositMultiCollateral(new anchor.BN(anchor.web3.LAMPORTS_PER_SOL))
+          .accounts({
+            userPosition: positionPda(owner.publicKey),
+            config: configPda,
+            collateralMint: mint,
+            userCollateralAccount: source,
+            vaultCollateralAccount: vaultPda(mint),
+            vaultAuthority: vaultAuthorityPda,
+            collateralOracle: pythFeed("sol-usd-100"),
+            owner: owner.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([owner])
+          .rpc(),
+        "VaultDelegateSet"
+      );
+    });
+  });
+
+  describe("protocol collateral ceiling", () => {
+    after(async () => {
+      await setParams({ maxTotalCollateralValueUsdc: new anchor.BN(0) });
+    });
+
+    it("accepts deposits up to the ceiling and rejects the one past it", async () => {
+      await ensureConfig();
+      // Leave room for exactly 2 more SOL at $100
+      const totalCollateral = (await fetchConfig()).totalCollateral.toNumber();
+      const ceiling = Math.floor((totalCollateral + 2 * anchor.web3.LAMPORTS_PER_SOL) / 10);
+      await setParams({ maxTotalCollateralValueUsdc: new anchor.BN(ceiling) });
+
+      const owner = await userWithCollateral(2 * anchor.web3.LAMPORTS_PER_SOL);
+      await expectError(depositWsol(owner, anchor.web3.LAMPORTS_PER_SOL), "CollateralCapExceeded");
+    });
+  });
+
+  describe("deposit_collateral_batch", () => {
+    it("deposits from several WSOL accounts in one instruction", async () => {
+      await ensureConfig();
+      const owner = await fundedKeypair(10);
+      await openPosition(owner);
+
+      const sol = anchor.web3.LAMPORTS_PER_SOL;
+      await depositWsolBatch(owner, [1 * sol, 1 * sol, 1 * sol]);
+
+      // 3 SOL * $100 * 50% LTV
+      const position = await fetchPosition(owner.publicKey);
+      expect(position.collateralAmount.toNumber()).to.equal(3 * sol);
+      expect(position.creditLimit.toNumber()).to.equal(150_000_000);
+    });
+
+    it("rejects a zero amount in the batch", async () => {
+      const owner = await fundedKeypair(10);
+      await openPosition(owner);
+
+      const sol = anchor.web3.LAMPORTS_PER_SOL;
+      await expectError(depositWsolBatch(owner, [1 * sol, 0]), "AmountTooSmall");
+    });
+  });
+
+  describe("interest rate ceiling", () => {
+    it("rejects a rate above the ceiling", async () => {
+      await ensureConfig();
+      const config = await fetchConfig();
+      expect(config.maxInterestRateBps).to.equal(3600);
+
+      await expectError(setParams({ interestRateBps: 3700 }), "InterestRateAboveCeiling");
+      await setParams({ interestRateBps: 3600 });
+      await setParams({ interestRateBps: 1200 });
+    });
+
+    it("queues a raised ceiling behind the timelock", async () => {
+      await setMaxInterestRate(5000);
+
+      let config = await fetchConfig();
+      expect(config.maxInterestRateBps).to.equal(3600);
+      expect(config.pendingMaxInterestRateBps).to.equal(5000);
+
+      await expectError(setMaxInterestRate(5000), "TimelockNotElapsed");
+      await expectError(setParams({ interestRateBps: 4000 }), "InterestRateAboveCeiling");
+
+      // Lowering applies immediately and drops the queued raise
+      await setMaxInterestRate(3600);
+      config = await fetchConfig();
+      expect(config.maxInterestRateBps).to.equal(3600);
+      expect(config.pendingMaxInterestRateBps).to.equal(0);
+    });
+
+    it("rejects a ceiling below the current rate", async () => {
+      await expectError(setMaxInterestRate(1000), "InterestRateAboveCeiling");
+    });
+  });
+
+  describe("params timelock", () => {
+    after(async () => {
+      const config = await fetchConfig();
+      if (config.paramsTimelockSlots.toNumber() > 0) {
+        await setParams({ paramsTimelockSlots: new anchor.BN(0), prepaymentFeeBps: 0 });
+        await waitForSlot((await program.account.pendingParams.fetch(pendingParamsPda)).effectiveSlot.toNumber());
+        await applyPendingParams();
+      }
+    });
+
+    it("holds queued changes until the timelock elapses", async () => {
+      await ensureConfig();
+      await setParams({ paramsTimelockSlots: new anchor.BN(10) });
+      const feeBefore = (await fetchConfig()).prepaymentFeeBps;
+
+      let queued: any;
+      const listener = program.addEventListener("ParamsQueued", (e) => {
+        queued = e;
+      });
+      await setParams({ prepaymentFeeBps: 50 });
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      const pending = await program.account.pendingParams.fetch(pendingParamsPda);
+      expect(pending.effectiveSlot.toNumber()).to.equal(pending.queuedSlot.toNumber() + 10);
+      expect(queued.effectiveSlot.toNumber()).to.equal(pending.effectiveSlot.toNumber());
+      expect((await fetchConfig()).prepaymentFeeBps).to.equal(feeBefore);
+      await expectError(applyPendingParams(), "TimelockNotElapsed");
+
+      let applied: any;
+      const appliedListener = program.addEventListener("ParamsApplied", (e) => {
+        applied = e;
+      });
+      await waitForSlot(pending.effectiveSlot.toNumber());
+      await applyPendingParams();
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(appliedListener);
+
+      expect((await fetchConfig()).prepaymentFeeBps).to.equal(50);
+      expect(applied.queuedSlot.toNumber()).to.equal(pending.queuedSlot.toNumber());
+      expect(applied.appliedSlot.toNumber()).to.be.at.least(pending.effectiveSlot.toNumber());
+      await expectError(applyPendingParams(), "NoPendingParams");
+    });
+
+    it("rejects a timelock above two days", async () => {
+      await expectError(setParams({ paramsTimelockSlots: new anchor.BN(432_001) }), "InvalidParameter");
+    });
+  });
+
+  describe("pause flags", () => {
+    after(async () => {
+      await setPauseFlags(0);
+    });
+
+    it("emits the full pause state for a borrows-only pause", async () => {
+      await ensureConfig();
+      const owner = await userWithCollateral(10 * anchor.web3.LAMPORTS_PER_SOL);
+
+      let event: any;
+      const listener = program.addEventListener("ProtocolPausedStateChanged", (e) => {
+        event = e;
+      });
+      await setPauseFlags(PAUSE_BORROWS);
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event.paused).to.equal(false);
+      expect(event.pauseFlags).to.equal(PAUSE_BORROWS);
+      expect((await fetchConfig()).pauseFlags).to.equal(PAUSE_BORROWS);
+
+      // Borrows are blocked while deposits still go through
+      await expectError(recordDebt(owner, 10_000_000), "ProtocolPaused");
+      await depositWsol(owner, anchor.web3.LAMPORTS_PER_SOL);
+    });
+
+    it("rejects unknown pause flags", async () => {
+      await expectError(setPauseFlags(1 << 7), "InvalidParameter");
+    });
+  });
+
+  describe("repayments under the global pause", () => {
+    let owner: Keypair;
+
+    before(async () => {
+      await ensureConfig();
+      owner = await userWithCollateral(10 * anchor.web3.LAMPORTS_PER_SOL);
+      await recordDebt(owner, 100_000_000);
+      await setPaused(true);
+    });
+
+    after(async () => {
+      await setParams({ pauseBlocksRepays: false });
+      await setPaused(false);
+    });
+
+    it("blocks new debt but lets borrowers repay", async () => {
+      await expectError(recordDebt(owner, 10_000_000), "ProtocolPaused");
+      await expectError(borrowUsdc(owner, 10_000_000), "ProtocolPaused");
+
+      const debtBefore = (await fetchPosition(owner.publicKey)).debtUsdc.toNumber();
+      await repayUsdc(owner, 10_000_000);
+      expect((await fetchPosition(owner.publicKey)).debtUsdc.toNumber()).to.be.lessThan(debtBefore);
+    });
+
+    it("blocks repayments when the pause is configured to cover them", async () => {
+      await setParams({ pauseBlocksRepays: true });
+      await expectError(repayUsdc(owner, 10_000_000), "ProtocolPaused");
+
+      // Only the global pause is affected by the setting
+      await setPaused(false);
+      await repayUsdc(owner, 10_000_000);
+      await setPaused(true);
+    });
+  });
+
+  describe("get_config", () => {
+    after(async () => {
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(60) });
+    });
+
+    it("returns the stored config with computed fields", async () => {
+      const { usdcMint } = await ensureConfig();
+      // No whole interval elapses, so the index accrued to now is the stored one
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(3600) });
+
+      const view = await getConfigView();
+      const config = await fetchConfig();
+      const treasury = await getAccount(provider.connection, vaultPda(usdcMint));
+
+      expect(view.version).to.equal(2);
+      expect(view.protocolVersion).to.equal(CLIENT_VERSION);
+      expect(view.minClientVersion).to.equal(config.minClientVersion);
+      expect(view.admin.toBase58()).to.equal(config.admin.toBase58());
+      expect(view.usdcMint.toBase58()).to.equal(config.usdcMint.toBase58());
+      expect(view.ltvMaxBps).to.equal(config.ltvMaxBps);
+      expect(view.liquidationThresholdBps).to.equal(config.liquidationThresholdBps);
+      expect(view.totalDebtUsdc.toString()).to.equal(config.totalDebtUsdc.toString());
+      expect(view.totalReservesUsdc.toString()).to.equal(config.totalReservesUsdc.toString());
+      expect(view.currentBorrowRateBps).to.equal(config.interestRateBps);
+      expect(view.borrowIndexNow.toString()).to.equal(config.globalBorrowIndex.toString());
+
+      const debt = BigInt(config.totalDebtUsdc.toString());
+      const liquidity = debt + treasury.amount;
+      const utilization = liquidity === 0n ? 0n : (debt * 10_000n) / liquidity;
+      expect(view.utilizationBps).to.equal(Number(utilization));
+    });
+  });
+
+  describe("interest during a global pause", () => {
+    before(async () => {
+      await ensureConfig();
+      // Accrue every second at a high rate so any leak is visible
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(0), interestRateBps: 3600 });
+    });
+
+    after(async () => {
+      await setPaused(false);
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(60), interestRateBps: 1200 });
+    });
+
+    it("accrues no interest across the paused window", async () => {
+      const owner = await userWithCollateral(10 * anchor.web3.LAMPORTS_PER_SOL);
+      await recordDebt(owner, 400_000_000);
+
+      await setPaused(true);
+      await refreshPosition(owner.publicKey);
+      const debtAtPause = (await fetchPosition(owner.publicKey)).debtUsdc.toNumber();
+      const indexAtPause = (await fetchConfig()).globalBorrowIndex.toString();
+
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+      await accrueInterest();
+      await refreshPosition(owner.publicKey);
+
+      expect((await fetchPosition(owner.publicKey)).debtUsdc.toNumber()).to.equal(debtAtPause);
+      expect((await fetchConfig()).globalBorrowIndex.toString()).to.equal(indexAtPause);
+
+      // Unpausing skips the accrual clock past the window instead of back-charging it
+      const pausedBefore = (await fetchConfig()).totalPausedSecs.toNumber();
+      await setPaused(false);
+      await accrueInterest();
+      const config = await fetchConfig();
+      expect(config.pausedSince.toNumber()).to.equal(0);
+      expect(config.totalPausedSecs.toNumber() - pausedBefore).to.be.at.least(3);
+      await refreshPosition(owner.publicKey);
+      // At most a couple of seconds of 36% APR interest on $400 after resuming
+      expect((await fetchPosition(owner.publicKey)).debtUsdc.toNumber() - debtAtPause).to.be.lessThan(100);
+    });
+  });
+
+  describe("borrow index correction", () => {
+    const RAY = 10n ** 27n;
+    let originalIndex: anchor.BN;
+
+    before(async () => {
+      await ensureConfig();
+      originalIndex = (await fetchConfig()).globalBorrowIndex;
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(0) });
+    });
+
+    after(async () => {
+      await setBorrowIndex(originalIndex);
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(60) });
+    });
+
+    it("requires confirmation and an index of at least 1.0", async () => {
+      await expectError(setBorrowIndex(new anchor.BN((2n * RAY).toString()), false), "ConfirmationRequired");
+      await expectError(setBorrowIndex(new anchor.BN((RAY - 1n).toString())), "InvalidParameter");
+    });
+
+    it("emits the correction and accrues from the corrected index", async () => {
+      const corrected = 2n * RAY;
+      let event: any;
+      const listener = program.addEventListener("BorrowIndexCorrected", (e) => {
+        event = e;
+      });
+      await setBorrowIndex(new anchor.BN(corrected.toString()));
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(BigInt((await fetchConfig()).globalBorrowIndex.toString())).to.equal(corrected);
+      expect(BigInt(event.newIndex.toString())).to.equal(corrected);
+      expect(event.admin.toBase58()).to.equal(provider.wallet.publicKey.toBase58());
+
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+      await accrueInterest();
+
+      // A few seconds of 12% APR on top of 2.0, not on the old index
+      const index = BigInt((await fetchConfig()).globalBorrowIndex.toString());
+      expect(index > corrected).to.equal(true);
+      expect(index < corrected + corrected / 100_000n).to.equal(true);
+    });
+  });
+
+  describe("per-collateral interest", () => {
+    let wsolMint: PublicKey;
+
+    before(async () => {
+      ({ wsolMint } = await ensureConfig());
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(0) });
+    });
+
+    after(async () => {
+      await setCollateralType(wsolMint, { interestRateBps: 0 });
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(60) });
+    });
+
+    it("rejects a collateral rate above the ceiling", async () => {
+      const { maxInterestRateBps } = await fetchConfig();
+      await expectError(
+        setCollateralType(wsolMint, { interestRateBps: maxInterestRateBps + 1 }),
+        "InterestRateAboveCeiling"
+      );
+    });
+
+    it("accrues each position at the rate of its collateral", async () => {
+      // WSOL positions pay 36% while the global index grows at 12%
+      await setCollateralType(wsolMint, { interestRateBps: 3600 });
+      const wsolBacked = await fundedKeypair(2);
+      await openPosition(wsolBacked);
+      await recordDebt(wsolBacked, 10_000_000_000);
+
+      const wsolIndex = (config: any) =>
+        BigInt(config.collateralTypes.find((c: any) => c.mint.equals(wsolMint)).borrowIndex.toString());
+      const start = await fetchConfig();
+      const debtBefore = (await fetchPosition(wsolBacked.publicKey)).debtUsdc.toNumber();
+
+      await new Promise((resolve) => setTimeout(resolve, 6000));
+      await refreshPosition(wsolBacked.publicKey);
+
+      const end = await fetchConfig();
+      const wsolGrowth = Number(wsolIndex(end) - wsolIndex(start));
+      const globalGrowth = Number(BigInt(end.globalBorrowIndex.toString()) - BigInt(start.globalBorrowIndex.toString()));
+      expect(globalGrowth).to.be.greaterThan(0);
+      expect(wsolGrowth / globalGrowth).to.be.within(2.5, 3.5);
+
+      // The position settled against the WSOL index, not the global one
+      const position = await fetchPosition(wsolBacked.publicKey);
+      expect(position.debtUsdc.toNumber()).to.be.greaterThan(debtBefore);
+      expect(BigInt(position.borrowIndexSnapshot.toString())).to.equal(wsolIndex(end));
+    });
+  });
+
+  describe("position merge", () => {
+    let wsolMint: PublicKey;
+    const wsolIndex = (config: any) =>
+      BigInt(config.collateralTypes.find((c: any) => c.mint.equals(wsolMint)).borrowIndex.toString());
+
+    before(async () => {
+      ({ wsolMint } = await ensureConfig());
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(0) });
+      await setCollateralType(wsolMint, { interestRateBps: 3600 });
+    });
+
+    after(async () => {
+      await setCollateralType(wsolMint, { interestRateBps: 0 });
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(60) });
+    });
+
+    it("sums both positions and keeps the combined debt accruing as the two would", async () => {
+      const owner = await userWithCollateral(2 * anchor.web3.LAMPORTS_PER_SOL);
+      const second = await userWithCollateral(anchor.web3.LAMPORTS_PER_SOL);
+      await recordDebt(owner, 100_000_000);
+      // Borrowed later, so the two debts carry different index snapshots
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+      await recordDebt(second, 50_000_000);
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+
+      const before = await fetchPosition(owner.publicKey);
+      const source = await fetchPosition(second.publicKey);
+      await mergePositions(owner, second);
+
+      expect(await provider.connection.getAccountInfo(positionPda(second.publicKey))).to.equal(null);
+
+      const mergeIndex = wsolIndex(await fetchConfig());
+      const owed = (position: any) =>
+        (BigInt(position.debtUsdc.toString()) * mergeIndex) / BigInt(position.borrowIndexSnapshot.toString());
+      const owedAtMerge = owed(before) + owed(source);
+
+      const merged = await fetchPosition(owner.publicKey);
+      expect(merged.collateralAmount.toNumber()).to.equal(3 * anchor.web3.LAMPORTS_PER_SOL);
+      expect(BigInt(merged.debtUsdc.toString())).to.equal(
+        BigInt(before.debtUsdc.toString()) + owed(source)
+      );
+      expect(BigInt(merged.borrowIndexSnapshot.toString()) < mergeIndex).to.equal(true);
+      const mergedOwed = owed(merged);
+      expect(mergedOwed >= owedAtMerge - 1n && mergedOwed <= owedAtMerge + 1n).to.equal(true);
+
+      // From the merge on, the sum grows with the index like both debts would have
+      await new Promise((resolve) => setTimeout(resolve, 4000));
+      await refreshPosition(owner.publicKey);
+      const endIndex = wsolIndex(await fetchConfig());
+      const expected = (owedAtMerge * endIndex) / mergeIndex;
+      const debt = BigInt((await fetchPosition(owner.publicKey)).debtUsdc.toString());
+      expect(debt > owedAtMerge).to.equal(true);
+      expect(debt >= expected - 2n && debt <= expected + 2n).to.equal(true);
+    });
+
+    it("rejects merging a position into itself", async () => {
+      const owner = await userWithCollateral(anchor.web3.LAMPORTS_PER_SOL);
+      await expectError(mergePositions(owner, owner), "InvalidParameter");
+    });
+  });
+
+  describe("collateral reconciliation", () => {
+    it("resets total collateral to the vault balances", async () => {
+      const { wsolMint } = await ensureConfig();
+      await reconcileCollateral();
+      const before = (await fetchConfig()).totalCollateral;
+
+      // Tokens sent straight to the vault bypass the tracked total
+      const donor = await fundedKeypair(2);
+      const source = await wrapSol(donor, 250_000_000);
+      await transfer(provider.connection, donor, source, vaultPda(wsolMint), donor, 250_000_000);
+      expect((await fetchConfig()).totalCollateral.toString()).to.equal(before.toString());
+
+      let event: any;
+      const listener = program.addEventListener("CollateralReconciled", (e) => {
+        event = e;
+      });
+      await reconcileCollateral();
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      const after = (await fetchConfig()).totalCollateral;
+      expect(after.toString()).to.equal(before.addn(250_000_000).toString());
+      expect(event.previousTotal.toString()).to.equal(before.toString());
+      expect(event.reconciledTotal.toString()).to.equal(after.toString());
+    });
+
+    it("requires one vault per collateral type", async () => {
+      await expectError(
+        program.methods
+          .reconcileCollateral()
+          .accounts({ config: configPda, admin: provider.wallet.publicKey })
+          .rpc(),
+        "InvalidParameter"
+      );
+    });
+  });
+
+  describe("interest accrual interval", () => {
+    const WAD = 10n ** 18n;
+    const SECONDS_PER_YEAR = 31_536_000n;
+
+    after(async () => {
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(60) });
+    });
+
+    it("defers accruals inside the interval", async () => {
+      await ensureConfig();
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(3600) });
+      const before = await fetchConfig();
+
+      for (let i = 0; i < 5; i++) {
+        await accrueInterest();
+      }
+
+      const after = await fetchConfig();
+      expect(after.globalBorrowIndex.toString()).to.equal(before.globalBorrowIndex.toString());
+      expect(after.lastUpdateTimestamp.toNumber()).to.equal(before.lastUpdateTimestamp.toNumber());
+    });
+
+    it("compounds once per interval regardless of call cadence", async () => {
+      const interval = 2;
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(interval) });
+      const before = await fetchConfig();
+
+      for (let i = 0; i < 10; i++) {
+        await accrueInterest();
+        await new Promise((resolve) => setTimeout(resolve, 500));
+      }
+      await accrueInterest();
+
+      const after = await fetchConfig();
+      const elapsed = after.lastUpdateTimestamp.toNumber() - before.lastUpdateTimestamp.toNumber();
+      expect(elapsed % interval).to.equal(0);
+      expect(elapsed).to.be.greaterThan(0);
+
+      // index * (1 + rate * interval / year) ^ (elapsed / interval)
+      const rate = BigInt(after.interestRateBps);
+      const factor = WAD + (WAD * rate * BigInt(interval)) / SECONDS_PER_YEAR / 10_000n;
+      let expected = BigInt(before.globalBorrowIndex.toString());
+      for (let i = 0; i < elapsed / interval; i++) {
+        expected = (expected * factor) / WAD;
+      }
+      const actual = BigInt(after.globalBorrowIndex.toString());
+      const diff = actual > expected ? actual - expected : expected - actual;
+      expect(diff * 10n ** 15n <= expected).to.equal(true);
+    });
+
+    it("leaves the index flat at a zero rate", async () => {
+      const { wsolMint } = await ensureConfig();
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(1) });
+      await accrueInterest();
+      await setParams({ interestRateBps: 0 });
+      const before = await fetchConfig();
+
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+      await accrueInterest();
+      await setParams({ interestRateBps: 1200 });
+
+      // The clock moved on but neither index grew (or wrapped below its start)
+      const after = await fetchConfig();
+      expect(after.lastUpdateTimestamp.toNumber()).to.be.greaterThan(before.lastUpdateTimestamp.toNumber());
+      expect(after.globalBorrowIndex.toString()).to.equal(before.globalBorrowIndex.toString());
+      const wsolIndex = (c: any) => c.collateralTypes.find((t: any) => t.mint.equals(wsolMint)).borrowIndex.toString();
+      expect(wsolIndex(after)).to.equal(wsolIndex(before));
+    });
+
+    it("rejects a rate above 100% APR", async () => {
+      await expectError(setParams({ interestRateBps: 10_001 }), "InvalidPercentage");
+    });
+
+    it("rejects an interval above one day", async () => {
+      await expectError(setParams({ minAccrualIntervalSecs: new anchor.BN(86_401) }), "InvalidParameter");
+    });
+  });
+
+  describe("index jump ceiling", () => {
+    const WAD = 10n ** 18n;
+
+    after(async () => {
+      await setParams({ maxIndexGrowthWad: new anchor.BN(0) });
+      await setParams({ interestRateBps: 1200, minAccrualIntervalSecs: new anchor.BN(60) });
+    });
+
+    it("rejects an accrual that grows the index past the ceiling", async () => {
+      await ensureConfig();
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(0), interestRateBps: 3600 });
+      await accrueInterest();
+
+      // A ceiling just above 1.0 makes any real gap look like a corrupted clock
+      await setParams({ maxIndexGrowthWad: new anchor.BN((WAD + 1n).toString()) });
+      const before = await fetchConfig();
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+      await expectError(accrueInterest(), "IndexJumpTooLarge");
+      expect((await fetchConfig()).globalBorrowIndex.toString()).to.equal(before.globalBorrowIndex.toString());
+
+      // A 2x ceiling lets the same gap through
+      await setParams({ maxIndexGrowthWad: new anchor.BN((2n * WAD).toString()) });
+      await accrueInterest();
+      const after = await fetchConfig();
+      expect(BigInt(after.globalBorrowIndex.toString()) > BigInt(before.globalBorrowIndex.toString())).to.equal(true);
+    });
+
+    it("rejects a ceiling at or below 1.0", async () => {
+      await expectError(setParams({ maxIndexGrowthWad: new anchor.BN(WAD.toString()) }), "InvalidParameter");
+    });
+  });
+
+  describe("configured oracle enforcement", () => {
+    after(async () => {
+      await setParams({ enforceConfiguredOracles: false });
+    });
+
+    it("rejects an oracle other than the configured feed", async () => {
+      const { solUsdOracle } = await ensureConfig();
+      const owner = await userWithCollateral(10 * anchor.web3.LAMPORTS_PER_SOL);
+
+      // Switched off for the fixtures: any Pyth feed prices the position
+      await refreshPosition(owner.publicKey, pythFeed("sol-usd-80"));
+
+      await setParams({ enforceConfiguredOracles: true });
+      await expectError(borrowUsdc(owner, 100_000_000, pythFeed("sol-usd-80")), "InvalidOracle");
+      await expectError(refreshPosition(owner.publicKey, pythFeed("sol-usd-80")), "InvalidOracle");
+
+      // The configured feed is still accepted
+      await refreshPosition(owner.publicKey, solUsdOracle);
+      await borrowUsdc(owner, 100_000_000, solUsdOracle);
+    });
+  });
+
+  describe("collateral mint migration", () => {
+    after(async () => {
+      const { jitoSolMint } = await ensureConfig();
+      await setParams({ jitoSolMint });
+    });
+
+    it("migrates the jitoSOL mint without moving WSOL positions", async () => {
+      const { jitoSolMint, wsolMint } = await ensureConfig();
+      const funded = await userWithCollateral(2 * anchor.web3.LAMPORTS_PER_SOL);
+
+      const newMint = await createMint(provider.connection, admin, admin.publicKey, null, 9);
+      await setParams({ jitoSolMint: newMint });
+
+      const config = await fetchConfig();
+      expect(config.jitoSolMint.toBase58()).to.equal(newMint.toBase58());
+      expect(config.previousJitoSolMint.toBase58()).to.equal(jitoSolMint.toBase58());
+
+      // Positions start on WSOL, which the migration leaves alone
+      await expectError(migratePositionMint(funded), "InvalidCollateralMint");
+
+      // New positions still take WSOL after the migration
+      const owner = await fundedKeypair(3);
+      await openPosition(owner);
+      await depositWsol(owner, anchor.web3.LAMPORTS_PER_SOL);
+      const position = await fetchPosition(owner.publicKey);
+      expect(position.collateralMint.toBase58()).to.equal(wsolMint.toBase58());
+      expect(position.collateralAmount.toNumber()).to.equal(anchor.web3.LAMPORTS_PER_SOL);
+    });
+  });
+
   // TODO: Add more comprehensive tests once we have test environment setup
   // These would include:
   // - Initialize program
@@ -137,4 +1430,37 @@ describe("credit-core", () => {
   // - Repay debt
   // - Liquidation scenarios
   // - Oracle price testing
-}); 
\ No newline at end of file
+
+  describe("admin_recover_token", () => {
+    it("returns stray tokens held by the vault authority", async () => {
+      await ensureConfig();
+      const sender = await fundedKeypair();
+      const strayMint = await createMint(provider.connection, sender, sender.publicKey, null, 6);
+      const stray = await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        sender,
+        strayMint,
+        vaultAuthorityPda,
+        true
+      );
+      await mintTo(provider.connection, sender, strayMint, stray.address, sender, 1_000);
+      const recipient = await createAccount(provider.connection, sender, strayMint, sender.publicKey);
+
+      await recoverToken(stray.address, recipient, 1_000);
+
+      expect(Number((await getAccount(provider.connection, recipient)).amount)).to.equal(1_000);
+      expect(Number((await getAccount(provider.connection, stray.address)).amount)).to.equal(0);
+    });
+
+    it("refuses to move collateral", async () => {
+      const { wsolMint } = await ensureConfig();
+      const sender = await fundedKeypair();
+      const recipient = await wrapSol(sender, 0);
+
+      await expectError(
+        recoverToken(vaultPda(wsolMint), recipient, 1),
+        "ProtocolTokenNotRecoverable"
+      );
+    });
+  });
+});
@@ -0,0 +1,265 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  ensureConfig,
+  fundedKeypair,
+  debitDeposit,
+  setDebitTier,
+  fetchDebitAccount,
+  fetchConfig,
+  expectError,
+  DEBIT_TIER_BASIC,
+  DEBIT_TIER_PREMIUM,
+  debitSpend,
+  debitSettle,
+  setDebitLimits,
+  setParams,
+  setPaused,
+  setPauseFlags,
+  PAUSE_BORROWS,
+  withdrawCollateral,
+  userWithCollateral,
+  linkCreditPosition,
+  positionPda,
+  fetchPosition,
+} from "./helpers";
+
+const USDC = 1_000_000;
+const SOL = anchor.web3.LAMPORTS_PER_SOL;
+
+describe("debit tiers", () => {
+  before(async () => {
+    await ensureConfig();
+  });
+
+  it("opens debit accounts on the basic tier", async () => {
+    const user = await fundedKeypair(2);
+    await debitDeposit(user, 100 * USDC);
+
+    const account = await fetchDebitAccount(user.publicKey);
+    expect(account.tier).to.equal(DEBIT_TIER_BASIC);
+    expect(account.dailyLimit.toNumber()).to.equal(1_000 * USDC);
+    expect(account.monthlyLimit.toNumber()).to.equal(10_000 * USDC);
+    expect(account.perTxLimit.toNumber()).to.equal(500 * USDC);
+  });
+
+  it("applies the premium tier limits when assigned", async () => {
+    const user = await fundedKeypair(2);
+    await debitDeposit(user, 100 * USDC);
+
+    await setDebitTier(user.publicKey, DEBIT_TIER_PREMIUM);
+
+    const premium = (await fetchConfig()).tierConfig[DEBIT_TIER_PREMIUM];
+    const account = await fetchDebitAccount(user.publicKey);
+    expect(account.tier).to.equal(DEBIT_TIER_PREMIUM);
+    expect(account.dailyLimit.toNumber()).to.equal(premium.dailyLimit.toNumber());
+    expect(account.monthlyLimit.toNumber()).to.equal(premium.monthlyLimit.toNumber());
+    expect(account.perTxLimit.toNumber()).to.equal(premium.perTxLimit.toNumber());
+    expect(account.dailyLimit.toNumber()).to.equal(5_000 * USDC);
+  });
+
+  it("rejects a tier with no configured limits", async () => {
+    const user = await fundedKeypair(2);
+    await debitDeposit(user, 100 * USDC);
+
+    await expectError(setDebitTier(user.publicKey, 3), "InvalidDebitTier");
+  });
+});
+
+describe("debit limit overrides", () => {
+  // Funded basic-tier account that has settled `spent` USDC
+  async function accountWithSpend(spent: number) {
+    const user = await fundedKeypair(2);
+    await debitDeposit(user, 500 * USDC);
+    await debitSpend(user.publicKey, spent);
+    await debitSettle(user.publicKey, spent);
+    return user;
+  }
+
+  it("keeps existing spend when limits change without a reset", async () => {
+    const user = await accountWithSpend(200 * USDC);
+
+    await setDebitLimits(user.publicKey, {
+      dailyLimit: new anchor.BN(2_000 * USDC),
+      monthlyLimit: new anchor.BN(20_000 * USDC),
+    });
+
+    const account = await fetchDebitAccount(user.publicKey);
+    expect(account.dailyLimit.toNumber()).to.equal(2_000 * USDC);
+    expect(account.monthlyLimit.toNumber()).to.equal(20_000 * USDC);
+    expect(account.dailySpent.toNumber()).to.equal(200 * USDC);
+    expect(account.monthlySpent.toNumber()).to.equal(200 * USDC);
+  });
+
+  it("starts a fresh budget when the reset flag is set", async () => {
+    const user = await accountWithSpend(200 * USDC);
+
+    await setDebitLimits(user.publicKey, {
+      dailyLimit: new anchor.BN(2_000 * USDC),
+      resetSpent: true,
+    });
+
+    const account = await fetchDebitAccount(user.publicKey);
+    expect(account.dailyLimit.toNumber()).to.equal(2_000 * USDC);
+    expect(account.dailySpent.toNumber()).to.equal(0);
+    expect(account.monthlySpent.toNumber()).to.equal(0);
+  });
+
+  it("rejects a daily limit above the monthly limit", async () => {
+    const user = await accountWithSpend(100 * USDC);
+    await expectError(
+      setDebitLimits(user.publicKey, { dailyLimit: new anchor.BN(20_000 * USDC) }),
+      "InvalidParameter"
+    );
+  });
+});
+
+describe("debit settlement dust", () => {
+  it("releases a reservation residue below the dust threshold", async () => {
+    const user = await fundedKeypair(2);
+    await debitDeposit(user, 100 * USDC);
+    await debitSpend(user.publicKey, 10 * USDC);
+
+    // Settles half a cent under the authorization
+    await debitSettle(user.publicKey, 10 * USDC - 5_000);
+
+    const account = await fetchDebitAccount(user.publicKey);
+    expect(account.usdcReserved.toNumber()).to.equal(0);
+    expect(account.usdcAvailable.toNumber()).to.equal(90 * USDC + 5_000);
+    expect(account.lifetimeSpent.toNumber()).to.equal(10 * USDC - 5_000);
+  });
+
+  it("keeps a residue at or above the threshold reserved", async () => {
+    const user = await fundedKeypair(2);
+    await debitDeposit(user, 100 * USDC);
+    await debitSpend(user.publicKey, 10 * USDC);
+
+    await debitSettle(user.publicKey, 5 * USDC);
+
+    const account = await fetchDebitAccount(user.publicKey);
+    expect(account.usdcReserved.toNumber()).to.equal(5 * USDC);
+    expect(account.usdcAvailable.toNumber()).to.equal(90 * USDC);
+  });
+});
+
+describe("debit reservation cap", () => {
+  after(async () => {
+    await setParams({ maxActiveReservations: 16 });
+  });
+
+  it("rejects reservations past the cap until one settles", async () => {
+    const user = await fundedKeypair(2);
+    await debitDeposit(user, 100 * USDC);
+    await setParams({ maxActiveReservations: 2 });
+
+    await debitSpend(user.publicKey, 10 * USDC);
+    await debitSpend(user.publicKey, 10 * USDC);
+    expect((await fetchDebitAccount(user.publicKey)).activeReservations).to.equal(2);
+    await expectError(debitSpend(user.publicKey, 10 * USDC), "TooManyReservations");
+
+    await debitSettle(user.publicKey, 10 * USDC);
+    expect((await fetchDebitAccount(user.publicKey)).activeReservations).to.equal(1);
+    await debitSpend(user.publicKey, 10 * USDC);
+  });
+});
+
+describe("debit under the protocol pause", () => {
+  after(async () => {
+    await setPaused(false);
+  });
+
+  it("rejects spends and deposits while paused", async () => {
+    const user = await fundedKeypair(2);
+    await debitDeposit(user, 100 * USDC);
+
+    await setPaused(true);
+    await expectError(debitSpend(user.publicKey, 10 * USDC), "ProtocolPaused");
+    await expectError(debitDeposit(await fundedKeypair(2), 10 * USDC), "ProtocolPaused");
+
+    await setPaused(false);
+    await debitSpend(user.publicKey, 10 * USDC);
+    expect((await fetchDebitAccount(user.publicKey)).usdcReserved.toNumber()).to.equal(10 * USDC);
+  });
+
+  it("rejects spends while borrows are paused", async () => {
+    const user = await fundedKeypair(2);
+    await debitDeposit(user, 100 * USDC);
+
+    await setPauseFlags(PAUSE_BORROWS);
+    try {
+      await expectError(debitSpend(user.publicKey, 10 * USDC), "ProtocolPaused");
+    } finally {
+      await setPauseFlags(0);
+    }
+
+    await debitSpend(user.publicKey, 10 * USDC);
+    expect((await fetchDebitAccount(user.publicKey)).usdcReserved.toNumber()).to.equal(10 * USDC);
+  });
+});
+
+describe("debit spend backed by a linked credit line", () => {
+  it("holds the shortfall against the credit line and books it as debt on settlement", async () => {
+    const user = await userWithCollateral(10 * SOL);
+    const position = positionPda(user.publicKey);
+    await debitDeposit(user, 100 * USDC);
+
+    // Unlinked, a spend past the balance is declined as before
+    await expectError(debitSpend(user.publicKey, 150 * USDC, position), "SpendingNotAllowed");
+
+    await linkCreditPosition(user);
+    await debitSpend(user.publicKey, 150 * USDC, position);
+
+    let account = await fetchDebitAccount(user.publicKey);
+    expect(account.usdcAvailable.toNumber()).to.equal(0);
+    expect(account.usdcReserved.toNumber()).to.equal(100 * USDC);
+    expect(account.creditReserved.toNumber()).to.equal(50 * USDC);
+    expect(account.activeReservations).to.equal(1);
+    expect((await fetchPosition(user.publicKey)).pendingCreditUsdc.toNumber()).to.equal(50 * USDC);
+
+    // The link stays while credit is held for the spend
+    await expectError(linkCreditPosition(user, false), "CreditHoldsOutstanding");
+
+    await debitSettle(user.publicKey, 150 * USDC, position);
+
+    account = await fetchDebitAccount(user.publicKey);
+    expect(account.usdcReserved.toNumber()).to.equal(0);
+    expect(account.creditReserved.toNumber()).to.equal(0);
+    expect(account.activeReservations).to.equal(0);
+    expect(account.lifetimeSpent.toNumber()).to.equal(150 * USDC);
+    const settled = await fetchPosition(user.publicKey);
+    expect(settled.pendingCreditUsdc.toNumber()).to.equal(0);
+    expect(settled.debtUsdc.toNumber()).to.equal(50 * USDC);
+
+    await linkCreditPosition(user, false);
+  });
+
+  it("rejects a shortfall beyond the remaining credit", async () => {
+    const user = await userWithCollateral(SOL);
+    const position = positionPda(user.publicKey);
+    await debitDeposit(user, 10 * USDC);
+    await linkCreditPosition(user);
+
+    const creditLimit = (await fetchPosition(user.publicKey)).creditLimit.toNumber();
+    expect(creditLimit).to.be.below(400 * USDC);
+
+    await expectError(
+      debitSpend(user.publicKey, 10 * USDC + creditLimit + 1, position),
+      "DebtLimitExceeded"
+    );
+    await debitSpend(user.publicKey, 10 * USDC + creditLimit, position);
+    expect((await fetchPosition(user.publicKey)).pendingCreditUsdc.toNumber()).to.equal(creditLimit);
+  });
+
+  it("counts held credit against collateral withdrawals", async () => {
+    const user = await userWithCollateral(SOL);
+    const position = positionPda(user.publicKey);
+    await linkCreditPosition(user);
+
+    const creditLimit = (await fetchPosition(user.publicKey)).creditLimit.toNumber();
+    await debitSpend(user.publicKey, creditLimit, position);
+    expect((await fetchPosition(user.publicKey)).debtUsdc.toNumber()).to.equal(0);
+
+    // No debt is booked yet, but the hold will become debt on settlement
+    await expectError(withdrawCollateral(user, 0.9 * SOL), "HealthFactorTooLow");
+  });
+});
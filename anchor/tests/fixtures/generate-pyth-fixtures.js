@@ -0,0 +1,99 @@
+// Generates synthetic Pyth V2 price accounts for the local test validator.
+//
+// The accounts are owned by the Pyth program so `get_pyth_price` reads them like
+// a real feed. Anchor.toml preloads every JSON file in tests/fixtures/pyth.
+//
+// Usage: node tests/fixtures/generate-pyth-fixtures.js
+
+const crypto = require("crypto");
+const fs = require("fs");
+const path = require("path");
+
+const PYTH_PROGRAM_ID = "gSbePebfvPy7tRqimPoVecS2UsBvYv46ynrzWocc92s";
+const PRICE_ACCOUNT_SIZE = 3312;
+
+// Publish slot far in the future so fixtures never age past a staleness bound
+const FRESH_SLOT = 1n << 40n;
+
+// name -> price account fields (price/conf in 10^expo units)
+const FEEDS = {
+  "sol-usd-100": { price: 100_00000000n, conf: 5000000n, expo: -8 },
+  "sol-usd-80": { price: 80_00000000n, conf: 4000000n, expo: -8 },
+  // 10% confidence band, well past the 2% default bound
+  "sol-usd-100-wide": { price: 100_00000000n, conf: 10_00000000n, expo: -8 },
+  // Non-positive aggregates, which derived feeds can publish
+  "sol-usd-zero": { price: 0n, conf: 0n, expo: -8 },
+  "sol-usd-negative": { price: -100_00000000n, conf: 5000000n, expo: -8 },
+  // Same $100 quoted at 6 decimals instead of the usual 8
+  "sol-usd-100-expo-6": { price: 100_000000n, conf: 50000n, expo: -6 },
+  // $100 spot with the EMA lagging at $90
+  "sol-usd-100-ema-90": { price: 100_00000000n, conf: 5000000n, expo: -8, emaPrice: 90_00000000n },
+  // A 0.1% move from $100
+  "sol-usd-100-10": { price: 100_10000000n, conf: 5000000n, expo: -8 },
+  // A valid $100 feed whose header claims a different account type (a layout we don't parse)
+  "sol-usd-unknown-layout": { price: 100_00000000n, conf: 5000000n, expo: -8, accountType: 4 },
+  // 1 * 10^20: an exponent far past the powers of ten the price scaling accepts
+  "sol-usd-expo-20": { price: 1n, conf: 0n, expo: 20 },
+  // jitoSOL/SOL exchange rate of 1.1
+  "jitosol-sol-110": { price: 1_10000000n, conf: 10000n, expo: -8 },
+};
+
+const ALPHABET = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+function base58(bytes) {
+  let value = BigInt("0x" + Buffer.from(bytes).toString("hex"));
+  let out = "";
+  while (value > 0n) {
+    out = ALPHABET[Number(value % 58n)] + out;
+    value /= 58n;
+  }
+  for (const byte of bytes) {
+    if (byte !== 0) break;
+    out = "1" + out;
+  }
+  return out;
+}
+
+// Deterministic address per fixture name
+function fixtureAddress(name) {
+  return base58(crypto.createHash("sha256").update(`credana-pyth-fixture:${name}`).digest());
+}
+
+function priceAccountData(feed) {
+  const data = Buffer.alloc(PRICE_ACCOUNT_SIZE);
+  data.writeUInt32LE(0xa1b2c3d4, 0); // magic
+  data.writeUInt32LE(2, 4); // version
+  data.writeUInt32LE(feed.accountType ?? 3, 8); // account type: price
+  data.writeUInt32LE(PRICE_ACCOUNT_SIZE, 12); // size
+  data.writeUInt32LE(1, 16); // price type: price
+  data.writeInt32LE(feed.expo, 20); // exponent
+  data.writeBigUInt64LE(feed.publishSlot ?? FRESH_SLOT, 40); // valid slot
+  data.writeBigInt64LE(feed.emaPrice ?? feed.price, 48); // ema price
+  data.writeBigInt64LE(feed.emaConf ?? feed.conf, 72); // ema confidence
+  data.writeBigInt64LE(1n, 200); // previous timestamp (low byte read as status)
+  data.writeBigInt64LE(feed.price, 208); // aggregate price
+  data.writeBigUInt64LE(feed.conf, 216); // aggregate confidence
+  data.writeUInt32LE(feed.status ?? 1, 224); // aggregate status: trading
+  data.writeBigUInt64LE(feed.publishSlot ?? FRESH_SLOT, 232); // aggregate publish slot
+  return data;
+}
+
+const outDir = path.join(__dirname, "pyth");
+fs.mkdirSync(outDir, { recursive: true });
+
+for (const [name, feed] of Object.entries(FEEDS)) {
+  const data = priceAccountData(feed);
+  const fixture = {
+    pubkey: fixtureAddress(name),
+    account: {
+      lamports: 23942400,
+      data: [data.toString("base64"), "base64"],
+      owner: PYTH_PROGRAM_ID,
+      executable: false,
+      rentEpoch: 0,
+      space: data.length,
+    },
+  };
+  fs.writeFileSync(path.join(outDir, `${name}.json`), JSON.stringify(fixture, null, 2) + "\n");
+  console.log(`${name}: ${fixture.pubkey}`);
+}
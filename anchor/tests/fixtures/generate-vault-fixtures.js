@@ -0,0 +1,136 @@
+// Generates a token mint and a delegated program vault for the local test validator.
+//
+// Vaults are PDAs whose authority is also a PDA, so no transaction can set a
+// delegate on one; the only way to exercise the vault delegate checks is to
+// preload a vault that already has one. Anchor.toml preloads every JSON file in
+// tests/fixtures/vaults.
+//
+// Usage: node tests/fixtures/generate-vault-fixtures.js
+
+const crypto = require("crypto");
+const fs = require("fs");
+const path = require("path");
+
+const PROGRAM_ID = "DzAXxi4XR4wc8ywFXXHfckEPx1neccaRWDjv7o4CCtE4";
+const TOKEN_PROGRAM_ID = "TokenkegQfeZyiNwAJbNbMwZvX8Hkxde8M4ZCrFqX1NDP";
+const MINT_SIZE = 82;
+const TOKEN_ACCOUNT_SIZE = 165;
+
+const ALPHABET = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+function base58(bytes) {
+  let value = BigInt("0x" + Buffer.from(bytes).toString("hex"));
+  let out = "";
+  while (value > 0n) {
+    out = ALPHABET[Number(value % 58n)] + out;
+    value /= 58n;
+  }
+  for (const byte of bytes) {
+    if (byte !== 0) break;
+    out = "1" + out;
+  }
+  return out;
+}
+
+function unbase58(text) {
+  let value = 0n;
+  for (const char of text) {
+    value = value * 58n + BigInt(ALPHABET.indexOf(char));
+  }
+  const hex = value.toString(16).padStart(64, "0");
+  return Buffer.from(hex, "hex");
+}
+
+// Deterministic address per fixture name
+function fixtureAddress(name) {
+  return crypto.createHash("sha256").update(`credana-vault-fixture:${name}`).digest();
+}
+
+// Whether 32 bytes decompress to an ed25519 point (PDAs must not)
+const P = (1n << 255n) - 19n;
+const D = (-121665n * modPow(121666n, P - 2n)) % P;
+
+function modPow(base, exp) {
+  let result = 1n;
+  base = ((base % P) + P) % P;
+  while (exp > 0n) {
+    if (exp & 1n) result = (result * base) % P;
+    base = (base * base) % P;
+    exp >>= 1n;
+  }
+  return result;
+}
+
+function isOnCurve(bytes) {
+  const le = Buffer.from(bytes).reverse();
+  le[0] &= 0x7f;
+  const y = BigInt("0x" + le.toString("hex")) % P;
+  const u = (y * y - 1n + P) % P;
+  const v = (((D * y) % P) * y + 1n + P) % P;
+  // x^2 = u / v must be a square
+  const x2 = (u * modPow(v, P - 2n)) % P;
+  return x2 === 0n || modPow(x2, (P - 1n) / 2n) === 1n;
+}
+
+function findProgramAddress(seeds, programId) {
+  for (let bump = 255; bump >= 0; bump--) {
+    const hash = crypto
+      .createHash("sha256")
+      .update(Buffer.concat([...seeds, Buffer.from([bump]), unbase58(programId), Buffer.from("ProgramDerivedAddress")]))
+      .digest();
+    if (!isOnCurve(hash)) return hash;
+  }
+  throw new Error("no viable bump");
+}
+
+function mintData() {
+  const data = Buffer.alloc(MINT_SIZE);
+  // mint authority: none, supply: 0
+  data.writeUInt8(9, 44); // decimals
+  data.writeUInt8(1, 45); // initialized
+  return data;
+}
+
+function tokenAccountData(mint, owner, delegate) {
+  const data = Buffer.alloc(TOKEN_ACCOUNT_SIZE);
+  mint.copy(data, 0);
+  owner.copy(data, 32);
+  data.writeUInt32LE(1, 72); // delegate: some
+  delegate.copy(data, 76);
+  data.writeUInt8(1, 108); // state: initialized
+  data.writeBigUInt64LE(0xffffffffffffffffn, 121); // delegated amount
+  return data;
+}
+
+const outDir = path.join(__dirname, "vaults");
+fs.mkdirSync(outDir, { recursive: true });
+
+const mint = fixtureAddress("delegated-mint");
+const vaultAuthority = findProgramAddress([Buffer.from("vault_authority")], PROGRAM_ID);
+const vault = findProgramAddress([Buffer.from("vault"), mint], PROGRAM_ID);
+const delegate = fixtureAddress("delegate");
+
+const FIXTURES = {
+  "delegated-mint": { address: mint, data: mintData(), lamports: 1461600 },
+  "delegated-vault": {
+    address: vault,
+    data: tokenAccountData(mint, vaultAuthority, delegate),
+    lamports: 2039280,
+  },
+};
+
+for (const [name, { address, data, lamports }] of Object.entries(FIXTURES)) {
+  const fixture = {
+    pubkey: base58(address),
+    account: {
+      lamports,
+      data: [data.toString("base64"), "base64"],
+      owner: TOKEN_PROGRAM_ID,
+      executable: false,
+      rentEpoch: 0,
+      space: data.length,
+    },
+  };
+  fs.writeFileSync(path.join(outDir, `${name}.json`), JSON.stringify(fixture, null, 2) + "\n");
+  console.log(`${name}: ${fixture.pubkey}`);
+}
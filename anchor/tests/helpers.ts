@@ -0,0 +1,1398 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { PublicKey, Keypair, LAMPORTS_PER_SOL, SystemProgram } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  NATIVE_MINT,
+  createMint,
+  createWrappedNativeAccount,
+  createAccount,
+  mintTo,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import * as fs from "fs";
+import * as path from "path";
+
+// Import the generated types (this will be generated by anchor build)
+type CreditCore = any; // Replace with generated type
+
+export const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+
+export const program = anchor.workspace.CreditCore as Program<CreditCore>;
+
+// The provider wallet doubles as protocol admin so every test file shares one config
+export const admin = (provider.wallet as anchor.Wallet).payer;
+
+export const [configPda] = PublicKey.findProgramAddressSync(
+  [Buffer.from("config")],
+  program.programId
+);
+
+export const [vaultAuthorityPda] = PublicKey.findProgramAddressSync(
+  [Buffer.from("vault_authority")],
+  program.programId
+);
+
+export const [pendingParamsPda] = PublicKey.findProgramAddressSync(
+  [Buffer.from("pending_params")],
+  program.programId
+);
+
+export function vaultPda(mint: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("vault"), mint.toBuffer()],
+    program.programId
+  )[0];
+}
+
+export function positionPda(owner: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("user_position"), owner.toBuffer()],
+    program.programId
+  )[0];
+}
+
+// Address of a synthetic Pyth feed preloaded from tests/fixtures/pyth
+export function pythFeed(name: string): PublicKey {
+  const fixture = JSON.parse(
+    fs.readFileSync(path.join(__dirname, "fixtures", "pyth", `${name}.json`), "utf8")
+  );
+  return new PublicKey(fixture.pubkey);
+}
+
+// Address of a token account fixture preloaded from tests/fixtures/vaults
+export function vaultFixture(name: string): PublicKey {
+  const fixture = JSON.parse(
+    fs.readFileSync(path.join(__dirname, "fixtures", "vaults", `${name}.json`), "utf8")
+  );
+  return new PublicKey(fixture.pubkey);
+}
+
+export interface Protocol {
+  usdcMint: PublicKey;
+  jitoSolMint: PublicKey;
+  wsolMint: PublicKey;
+  solUsdOracle: PublicKey;
+  jitoSolUsdOracle: PublicKey;
+}
+
+let protocol: Protocol | undefined;
+
+// 1M USDC minted into the treasury vault at setup
+export const TREASURY_SEED_USDC = 1_000_000 * 1_000_000;
+
+// Instruction layout version these helpers are built against (PROTOCOL_VERSION)
+export const CLIENT_VERSION = 1;
+
+// Every optional field of UpdateParams, unset
+export const EMPTY_PARAMS = {
+  ltvMaxBps: null,
+  liquidationThresholdBps: null,
+  liquidationBonusBps: null,
+  interestRateBps: null,
+  solUsdOracle: null,
+  jitoSolUsdOracle: null,
+  newAdmin: null,
+  jitoSolMint: null,
+  borrowMaxStalenessSlots: null,
+  liquidationMaxStalenessSlots: null,
+  maxConfidenceBps: null,
+  minResidualCollateral: null,
+  minAccrualIntervalSecs: null,
+  liquidationHealthEpsilonBps: null,
+  minPositionAgeSecs: null,
+  minReserveRatioBps: null,
+  maxTotalCollateralValueUsdc: null,
+  minBorrowHealthFactorBps: null,
+  warningThresholdBps: null,
+  protocolLiquidator: null,
+  prepaymentFeeBps: null,
+  minLoanTermSecs: null,
+  maxLiquidationCollateralFractionBps: null,
+  pauseInterestAccrual: null,
+  creditLimitBufferBps: null,
+  minDebtAfterLiquidation: null,
+  minClientVersion: null,
+  oracleHealthWindowSlots: null,
+  protocolOwners: null,
+  emaPriceFlags: null,
+  debitDustThreshold: null,
+  maxActiveReservations: null,
+  pauseBlocksRepays: null,
+  borrowValuationMode: null,
+  liquidationValuationMode: null,
+  maxBorrowIndexAgeSecs: null,
+  maxBorrowLiquidityShareBps: null,
+  creditLimitRefreshIntervalSecs: null,
+  creditLimitRefreshPriceBps: null,
+  maxSinglePositionDebtUsdc: null,
+  fullLiquidationRebateBps: null,
+  paramsTimelockSlots: null,
+  maxIndexGrowthWad: null,
+  enforceConfiguredOracles: null,
+};
+
+export async function airdrop(to: PublicKey, sol = 10): Promise<void> {
+  await provider.connection.confirmTransaction(
+    await provider.connection.requestAirdrop(to, sol * LAMPORTS_PER_SOL)
+  );
+}
+
+export async function fundedKeypair(sol = 10): Promise<Keypair> {
+  const keypair = Keypair.generate();
+  await airdrop(keypair.publicKey, sol);
+  return keypair;
+}
+
+// Initialize the protocol config once per validator run
+export async function ensureConfig(): Promise<Protocol> {
+  if (protocol) {
+    return protocol;
+  }
+
+  const usdcMint = await createMint(
+    provider.connection,
+    admin,
+    admin.publicKey,
+    null,
+    6 // USDC has 6 decimals
+  );
+
+  const jitoSolMint = await createMint(provider.connection, admin, admin.publicKey, null, 9);
+
+  protocol = {
+    usdcMint,
+    jitoSolMint,
+    wsolMint: NATIVE_MINT,
+    solUsdOracle: pythFeed("sol-usd-100"),
+    jitoSolUsdOracle: pythFeed("sol-usd-100"),
+  };
+
+  await program.methods
+    .initialize({
+      ltvMaxBps: 5000,
+      liquidationThresholdBps: 6000,
+      liquidationBonusBps: 600,
+      interestRateBps: 1200,
+      solUsdOracle: protocol.solUsdOracle,
+      jitoSolUsdOracle: protocol.jitoSolUsdOracle,
+      usdcMint: protocol.usdcMint,
+      jitoSolMint: protocol.jitoSolMint,
+      wsolMint: protocol.wsolMint,
+    })
+    .accounts({
+      config: configPda,
+      admin: admin.publicKey,
+    })
+    .rpc();
+
+  await initVault(protocol.wsolMint);
+  await initVault(protocol.usdcMint);
+
+  // Deployments enforce the configured feeds; the tests price positions from several fixtures
+  await setParams({ enforceConfiguredOracles: false });
+
+  // Seed the treasury so borrows have USDC to lend out
+  await mintTo(
+    provider.connection,
+    admin,
+    protocol.usdcMint,
+    vaultPda(protocol.usdcMint),
+    admin,
+    TREASURY_SEED_USDC
+  );
+
+  return protocol;
+}
+
+export async function initVault(mint: PublicKey): Promise<string> {
+  return program.methods
+    .initVault()
+    .accounts({
+      config: configPda,
+      vault: vaultPda(mint),
+      vaultAuthority: vaultAuthorityPda,
+      mint,
+      admin: admin.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+}
+
+export async function openPosition(owner: Keypair): Promise<PublicKey> {
+  const userPosition = positionPda(owner.publicKey);
+  await program.methods
+    .initPosition()
+    .accounts({
+      userPosition,
+      config: configPda,
+      owner: owner.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([owner])
+    .rpc();
+  return userPosition;
+}
+
+// Wrap SOL into a fresh WSOL account owned by `owner`
+export async function wrapSol(owner: Keypair, lamports: number): Promise<PublicKey> {
+  return createWrappedNativeAccount(
+    provider.connection,
+    owner,
+    owner.publicKey,
+    lamports,
+    Keypair.generate()
+  );
+}
+
+export async function depositWsol(
+  owner: Keypair,
+  lamports: number,
+  oracle?: PublicKey,
+  clientVersion = CLIENT_VERSION
+): Promise<string> {
+  const { wsolMint, solUsdOracle } = await ensureConfig();
+  const userWsolAccount = await wrapSol(owner, lamports);
+  return program.methods
+    .depositCollateralWsol(new BN(lamports), clientVersion)
+    .accounts({
+      userPosition: positionPda(owner.publicKey),
+      config: configPda,
+      userWsolAccount,
+      vaultWsol: vaultPda(wsolMint),
+      vaultAuthority: vaultAuthorityPda,
+      wsolMint,
+      solUsdOracle: oracle ?? solUsdOracle,
+      owner: owner.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([owner])
+    .rpc();
+}
+
+// Deposit WSOL, opening the owner's position in the same instruction if needed
+export async function initAndDeposit(owner: Keypair, lamports: number, oracle?: PublicKey): Promise<string> {
+  const { wsolMint, solUsdOracle } = await ensureConfig();
+  const userWsolAccount = await wrapSol(owner, lamports);
+  return program.methods
+    .initAndDeposit(new BN(lamports), CLIENT_VERSION)
+    .accounts({
+      userPosition: positionPda(owner.publicKey),
+      config: configPda,
+      userWsolAccount,
+      vaultWsol: vaultPda(wsolMint),
+      vaultAuthority: vaultAuthorityPda,
+      wsolMint,
+      solUsdOracle: oracle ?? solUsdOracle,
+      owner: owner.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([owner])
+    .rpc();
+}
+
+// Deposit from one fresh WSOL account per entry in `lamports`, in a single instruction
+export async function depositWsolBatch(
+  owner: Keypair,
+  lamports: number[],
+  oracle?: PublicKey
+): Promise<string> {
+  const { wsolMint, solUsdOracle } = await ensureConfig();
+  const remainingAccounts = [];
+  for (const amount of lamports) {
+    const source = await wrapSol(owner, amount);
+    remainingAccounts.push(
+      { pubkey: source, isSigner: false, isWritable: true },
+      { pubkey: vaultPda(wsolMint), isSigner: false, isWritable: true }
+    );
+  }
+  return program.methods
+    .depositCollateralBatch(lamports.map((amount) => new BN(amount)), CLIENT_VERSION)
+    .accounts({
+      userPosition: positionPda(owner.publicKey),
+      config: configPda,
+      solUsdOracle: oracle ?? solUsdOracle,
+      owner: owner.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts(remainingAccounts)
+    .signers([owner])
+    .rpc();
+}
+
+export async function borrowUsdc(
+  owner: Keypair,
+  usdcAmount: number,
+  oracle?: PublicKey,
+  clientVersion = CLIENT_VERSION,
+  accrueFirst = false
+): Promise<string> {
+  const { usdcMint, solUsdOracle } = await ensureConfig();
+  // Catch the borrow index up earlier in the same transaction
+  const preInstructions = accrueFirst
+    ? [await program.methods.accrueInterest().accounts({ config: configPda }).instruction()]
+    : [];
+  const position = await program.account.userPosition.fetchNullable(positionPda(owner.publicKey));
+  return program.methods
+    .borrowUsdc(new BN(usdcAmount), clientVersion)
+    .accounts({
+      userPosition: positionPda(owner.publicKey),
+      config: configPda,
+      treasuryUsdc: vaultPda(usdcMint),
+      vaultAuthority: vaultAuthorityPda,
+      userUsdcAccount: getAssociatedTokenAddressSync(usdcMint, owner.publicKey),
+      usdcMint,
+      solUsdOracle: oracle ?? solUsdOracle,
+      borrower: owner.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .remainingAccounts(position ? await slotOracles(position) : [])
+    .preInstructions(preInstructions)
+    .signers([owner])
+    .rpc();
+}
+
+// Deposit `lamports` of fresh WSOL and borrow up to `maxUsdcAmount` against it
+export async function depositAndBorrow(
+  owner: Keypair,
+  lamports: number,
+  maxUsdcAmount: number,
+  oracle?: PublicKey
+): Promise<string> {
+  const { usdcMint, wsolMint, solUsdOracle } = await ensureConfig();
+  const userWsolAccount = await wrapSol(owner, lamports);
+  return program.methods
+    .depositAndBorrow(new BN(lamports), new BN(maxUsdcAmount), CLIENT_VERSION)
+    .accounts({
+      userPosition: positionPda(owner.publicKey),
+      config: configPda,
+      userWsolAccount,
+      vaultWsol: vaultPda(wsolMint),
+      treasuryUsdc: vaultPda(usdcMint),
+      vaultAuthority: vaultAuthorityPda,
+      userUsdcAccount: getAssociatedTokenAddressSync(usdcMint, owner.publicKey),
+      usdcMint,
+      solUsdOracle: oracle ?? solUsdOracle,
+      owner: owner.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([owner])
+    .rpc();
+}
+
+export async function repayUsdc(
+  owner: Keypair,
+  usdcAmount: number,
+  tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+  repayAll = false
+): Promise<string> {
+  const { usdcMint } = await ensureConfig();
+  // Fund the owner's USDC account with the full amount the repay may charge
+  const userUsdc = await getOrCreateAssociatedTokenAccount(provider.connection, owner, usdcMint, owner.publicKey);
+  await mintTo(provider.connection, admin, usdcMint, userUsdc.address, admin, usdcAmount);
+  return program.methods
+    .repayUsdc(new BN(usdcAmount), repayAll)
+    .accounts({
+      userPosition: positionPda(owner.publicKey),
+      config: configPda,
+      userUsdcAccount: userUsdc.address,
+      treasuryUsdcAccount: vaultPda(usdcMint),
+      owner: owner.publicKey,
+      tokenProgram,
+    })
+    .signers([owner])
+    .rpc();
+}
+
+// Register a non-USDC debt asset; the default oracle values it at $1
+export async function setDebtAsset(mint: PublicKey, oracle: PublicKey = PublicKey.default): Promise<string> {
+  return program.methods
+    .adminSetDebtAsset(oracle)
+    .accounts({
+      config: configPda,
+      debtMint: mint,
+      admin: admin.publicKey,
+    })
+    .rpc();
+}
+
+// A registered debt asset whose vault holds `vaultAmount` base units
+export async function debtAssetWithVault(
+  decimals: number,
+  vaultAmount: number,
+  oracle?: PublicKey
+): Promise<PublicKey> {
+  await ensureConfig();
+  const mint = await createMint(provider.connection, admin, admin.publicKey, null, decimals);
+  await setDebtAsset(mint, oracle);
+  await initVault(mint);
+  await mintTo(provider.connection, admin, mint, vaultPda(mint), admin, vaultAmount);
+  return mint;
+}
+
+export async function borrowAsset(
+  owner: Keypair,
+  mint: PublicKey,
+  destination: PublicKey,
+  amount: number,
+  debtOracle?: PublicKey
+): Promise<string> {
+  const { solUsdOracle } = await ensureConfig();
+  const position = await fetchPosition(owner.publicKey);
+  return program.methods
+    .borrowAsset(new BN(amount))
+    .accounts({
+      userPosition: positionPda(owner.publicKey),
+      config: configPda,
+      debtMint: mint,
+      assetVault: vaultPda(mint),
+      vaultAuthority: vaultAuthorityPda,
+      userAssetAccount: destination,
+      solUsdOracle,
+      borrower: owner.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts([...debtOracleMeta(debtOracle), ...(await slotOracles(position))])
+    .signers([owner])
+    .rpc();
+}
+
+export async function repayAsset(
+  owner: Keypair,
+  mint: PublicKey,
+  source: PublicKey,
+  amount: number
+): Promise<string> {
+  return program.methods
+    .repayAsset(new BN(amount))
+    .accounts({
+      userPosition: positionPda(owner.publicKey),
+      config: configPda,
+      userAssetAccount: source,
+      assetVault: vaultPda(mint),
+      owner: owner.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([owner])
+    .rpc();
+}
+
+// Sell `lamports` of collateral to protocol reserves to pay down debt
+export async function repayWithCollateral(owner: Keypair, lamports: number): Promise<string> {
+  const { solUsdOracle } = await ensureConfig();
+  return program.methods
+    .repayWithCollateral(new BN(lamports))
+    .accounts({
+      userPosition: positionPda(owner.publicKey),
+      config: configPda,
+      solUsdOracle,
+      owner: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc();
+}
+
+// Repay the whole debt accrued at execution, charging at most `maxAmount`
+export async function repayAllUsdc(owner: Keypair, maxAmount: number): Promise<string> {
+  return repayUsdc(owner, maxAmount, TOKEN_PROGRAM_ID, true);
+}
+
+// Repay several positions from one payer; each entry is (position owner, amount)
+export async function repayBatch(
+  payer: Keypair,
+  payerUsdcAccount: PublicKey,
+  repayments: { owner: PublicKey; amount: number }[]
+): Promise<string> {
+  const { usdcMint } = await ensureConfig();
+  return program.methods
+    .repayBatch(repayments.map((r) => new BN(r.amount)))
+    .accounts({
+      config: configPda,
+      payerUsdcAccount,
+      treasuryUsdcAccount: vaultPda(usdcMint),
+      payer: payer.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts(
+      repayments.map((r) => ({ pubkey: positionPda(r.owner), isWritable: true, isSigner: false }))
+    )
+    .signers([payer])
+    .rpc();
+}
+
+export async function configureAutoDeleverage(
+  owner: Keypair,
+  thresholdBps: number,
+  bufferDeposit: number
+): Promise<string> {
+  const { usdcMint } = await ensureConfig();
+  return program.methods
+    .configureAutoDeleverage(thresholdBps, new BN(bufferDeposit))
+    .accounts({
+      userPosition: positionPda(owner.publicKey),
+      config: configPda,
+      userUsdcAccount: getAssociatedTokenAddressSync(usdcMint, owner.publicKey),
+      treasuryUsdcAccount: vaultPda(usdcMint),
+      vaultAuthority: vaultAuthorityPda,
+      owner: owner.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([owner])
+    .rpc();
+}
+
+export async function triggerAutoDeleverage(owner: PublicKey, oracle?: PublicKey): Promise<string> {
+  const { solUsdOracle } = await ensureConfig();
+  return program.methods
+    .triggerAutoDeleverage()
+    .accounts({
+      userPosition: positionPda(owner),
+      config: configPda,
+      solUsdOracle: oracle ?? solUsdOracle,
+    })
+    .rpc();
+}
+
+export async function configureStopLoss(owner: Keypair, stopLossHealthBps: number): Promise<string> {
+  return program.methods
+    .configureStopLoss(stopLossHealthBps)
+    .accounts({
+      userPosition: positionPda(owner.publicKey),
+      owner: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc();
+}
+
+export async function executeStopLoss(owner: PublicKey, oracle?: PublicKey): Promise<string> {
+  const { solUsdOracle } = await ensureConfig();
+  return program.methods
+    .executeStopLoss()
+    .accounts({
+      userPosition: positionPda(owner),
+      config: configPda,
+      solUsdOracle: oracle ?? solUsdOracle,
+    })
+    .rpc();
+}
+
+export async function enforceBuffer(owner: PublicKey, oracle?: PublicKey): Promise<string> {
+  const { solUsdOracle } = await ensureConfig();
+  return program.methods
+    .enforceBuffer()
+    .accounts({
+      userPosition: positionPda(owner),
+      config: configPda,
+      solUsdOracle: oracle ?? solUsdOracle,
+    })
+    .rpc();
+}
+
+export async function withdrawCollateral(
+  owner: Keypair,
+  lamports: number,
+  destination?: PublicKey,
+  oracle?: PublicKey,
+  recipient?: PublicKey
+): Promise<string> {
+  const { wsolMint, solUsdOracle } = await ensureConfig();
+  return program.methods
+    .withdrawCollateral(new BN(lamports))
+    .accounts({
+      userPosition: positionPda(owner.publicKey),
+      config: configPda,
+      userCollateralAccount: destination ?? (await wrapSol(owner, 0)),
+      recipientCollateralAccount: recipient ?? null,
+      vaultCollateralAccount: vaultPda(wsolMint),
+      vaultAuthority: vaultAuthorityPda,
+      solUsdOracle: oracle ?? solUsdOracle,
+      owner: owner.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([owner])
+    .rpc();
+}
+
+// Withdraw WSOL collateral, unwrapping `unwrapLamports` of it to native SOL
+export async function withdrawAndUnwrap(
+  owner: Keypair,
+  lamports: number,
+  unwrapLamports: number,
+  destination?: PublicKey
+): Promise<string> {
+  const { wsolMint, solUsdOracle } = await ensureConfig();
+  const [unwrapAccount] = PublicKey.findProgramAddressSync(
+    [Buffer.from("unwrap"), owner.publicKey.toBuffer()],
+    program.programId
+  );
+  return program.methods
+    .withdrawAndUnwrap(new BN(lamports), new BN(unwrapLamports))
+    .accounts({
+      userPosition: positionPda(owner.publicKey),
+      config: configPda,
+      userWsolAccount: destination ?? (await wrapSol(owner, 0)),
+      unwrapAccount,
+      vaultWsol: vaultPda(wsolMint),
+      vaultAuthority: vaultAuthorityPda,
+      wsolMint,
+      solUsdOracle,
+      owner: owner.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([owner])
+    .rpc();
+}
+
+export async function recordDebt(owner: Keypair, usdcAmount: number): Promise<string> {
+  return program.methods
+    .recordDebt(new BN(usdcAmount))
+    .accounts({
+      userPosition: positionPda(owner.publicKey),
+      config: configPda,
+      owner: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc();
+}
+
+// Funded wallet with an initialized position holding `lamports` of WSOL collateral
+export async function userWithCollateral(lamports: number): Promise<Keypair> {
+  await ensureConfig();
+  const owner = await fundedKeypair(lamports / LAMPORTS_PER_SOL + 5);
+  await openPosition(owner);
+  await depositWsol(owner, lamports);
+  return owner;
+}
+
+export async function refreshPosition(
+  owner: PublicKey,
+  oracle?: PublicKey,
+  debtOracle?: PublicKey
+): Promise<string> {
+  const { solUsdOracle } = await ensureConfig();
+  const position = await fetchPosition(owner);
+  return program.methods
+    .refreshPosition()
+    .accounts({
+      userPosition: positionPda(owner),
+      config: configPda,
+      solUsdOracle: oracle ?? solUsdOracle,
+    })
+    .remainingAccounts([...debtOracleMeta(debtOracle), ...(await slotOracles(position))])
+    .rpc();
+}
+
+// Oracle of an oracle-priced debt asset, which leads the remaining accounts
+function debtOracleMeta(debtOracle?: PublicKey): anchor.web3.AccountMeta[] {
+  return debtOracle ? [{ pubkey: debtOracle, isWritable: false, isSigner: false }] : [];
+}
+
+// Health factors are returned in bps: HEALTH_FACTOR_SCALE is 1.0
+export const HEALTH_FACTOR_SCALE = 10_000;
+
+// u64 return data of a confirmed transaction
+export async function returnDataU64(signature: string): Promise<number> {
+  await provider.connection.confirmTransaction(signature, "confirmed");
+  const tx = await provider.connection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  const [data] = tx!.meta!.returnData!.data;
+  return Number(Buffer.from(data, "base64").readBigUInt64LE(0));
+}
+
+// Program log lines of a confirmed transaction
+export async function transactionLogs(signature: string): Promise<string[]> {
+  await provider.connection.confirmTransaction(signature, "confirmed");
+  const tx = await provider.connection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  return tx!.meta!.logMessages ?? [];
+}
+
+// Refresh a position and read back the health factor it returned
+export async function refreshHealthFactor(owner: PublicKey, oracle?: PublicKey): Promise<number> {
+  const signature = await refreshPosition(owner, oracle);
+  return returnDataU64(signature);
+}
+
+// Largest collateral withdrawal that keeps the minimum borrow health factor
+export async function maxWithdrawable(owner: PublicKey, oracle?: PublicKey): Promise<number> {
+  const { solUsdOracle } = await ensureConfig();
+  const signature = await program.methods
+    .maxWithdrawable()
+    .accounts({
+      userPosition: positionPda(owner),
+      config: configPda,
+      solUsdOracle: oracle ?? solUsdOracle,
+    })
+    .rpc();
+  return returnDataU64(signature);
+}
+
+// Collateral price at which the position's health factor reaches 1.0 (0 = no debt)
+export async function getLiquidationPrice(owner: PublicKey): Promise<number> {
+  const signature = await program.methods
+    .getLiquidationPrice()
+    .accounts({
+      userPosition: positionPda(owner),
+      config: configPda,
+    })
+    .rpc();
+  return returnDataU64(signature);
+}
+
+export async function emitLiquidationWarning(owner: PublicKey, oracle?: PublicKey): Promise<string> {
+  const { solUsdOracle } = await ensureConfig();
+  return program.methods
+    .emitLiquidationWarning()
+    .accounts({
+      userPosition: positionPda(owner),
+      config: configPda,
+      solUsdOracle: oracle ?? solUsdOracle,
+      keeper: admin.publicKey,
+    })
+    .rpc();
+}
+
+export interface Liquidator {
+  keypair: Keypair;
+  usdcAccount: PublicKey;
+  collateralAccount: PublicKey;
+}
+
+// Liquidator holding `usdcAmount` USDC and an empty WSOL account for seized collateral
+export async function fundedLiquidator(usdcAmount: number): Promise<Liquidator> {
+  const { usdcMint } = await ensureConfig();
+  const keypair = await fundedKeypair(5);
+  const usdcAccount = await createAccount(provider.connection, keypair, usdcMint, keypair.publicKey);
+  await mintTo(provider.connection, admin, usdcMint, usdcAccount, admin, usdcAmount);
+  const collateralAccount = await wrapSol(keypair, 0);
+  return { keypair, usdcAccount, collateralAccount };
+}
+
+async function liquidateAccounts(liquidator: Liquidator, owner: PublicKey, oracle?: PublicKey) {
+  const { usdcMint, wsolMint, solUsdOracle } = await ensureConfig();
+  return {
+    userPosition: positionPda(owner),
+    config: configPda,
+    userBeingLiquidated: owner,
+    liquidatorUsdcAccount: liquidator.usdcAccount,
+    liquidatorCollateralAccount: liquidator.collateralAccount,
+    treasuryUsdcAccount: vaultPda(usdcMint),
+    vaultCollateralAccount: vaultPda(wsolMint),
+    vaultAuthority: vaultAuthorityPda,
+    solUsdOracle: oracle ?? solUsdOracle,
+    liquidator: liquidator.keypair.publicKey,
+    tokenProgram: TOKEN_PROGRAM_ID,
+    systemProgram: SystemProgram.programId,
+  };
+}
+
+export async function liquidate(
+  liquidator: Liquidator,
+  owner: PublicKey,
+  repayAmount: number,
+  oracle?: PublicKey
+): Promise<string> {
+  return program.methods
+    .liquidate(new BN(repayAmount))
+    .accounts(await liquidateAccounts(liquidator, owner, oracle))
+    .signers([liquidator.keypair])
+    .rpc();
+}
+
+export async function liquidateToTarget(
+  liquidator: Liquidator,
+  owner: PublicKey,
+  targetHealthBps: number
+): Promise<string> {
+  return program.methods
+    .liquidateToTarget(new BN(targetHealthBps))
+    .accounts(await liquidateAccounts(liquidator, owner))
+    .signers([liquidator.keypair])
+    .rpc();
+}
+
+export async function depositMultiCollateral(
+  owner: Keypair,
+  mint: PublicKey,
+  source: PublicKey,
+  amount: number
+): Promise<string> {
+  const collateralType = await fetchCollateralType(mint);
+  return program.methods
+    .depositMultiCollateral(new BN(amount))
+    .accounts({
+      userPosition: positionPda(owner.publicKey),
+      config: configPda,
+      collateralMint: mint,
+      userCollateralAccount: source,
+      vaultCollateralAccount: vaultPda(mint),
+      vaultAuthority: vaultAuthorityPda,
+      collateralOracle: collateralType.oracle,
+      owner: owner.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts(exchangeRateOracles([collateralType]))
+    .signers([owner])
+    .rpc();
+}
+
+// Exchange-rate feeds of the collateral types priced through one, in order
+function exchangeRateOracles(collateralTypes: any[]): anchor.web3.AccountMeta[] {
+  return collateralTypes
+    .filter((c: any) => !c.exchangeRateOracle.equals(PublicKey.default))
+    .map((c: any) => ({ pubkey: c.exchangeRateOracle, isWritable: false, isSigner: false }));
+}
+
+// Registered oracle of every occupied collateral slot, then the exchange-rate feeds
+async function slotOracles(position: any): Promise<anchor.web3.AccountMeta[]> {
+  const config = await fetchConfig();
+  const heldTypes = position.collaterals
+    .filter((entry: any) => !entry.mint.equals(PublicKey.default) && entry.amount.gtn(0))
+    .map((entry: any) => config.collateralTypes.find((c: any) => c.mint.equals(entry.mint)));
+  return [
+    ...heldTypes.map((c: any) => ({ pubkey: c.oracle, isWritable: false, isSigner: false })),
+    ...exchangeRateOracles(heldTypes),
+  ];
+}
+
+// Liquidate a multi-collateral position; `collateralAccounts` maps each held mint
+// to the liquidator's token account receiving it
+export async function liquidateMulti(
+  liquidator: Keypair,
+  liquidatorUsdcAccount: PublicKey,
+  owner: PublicKey,
+  repayAmount: number,
+  collateralAccounts: Map<string, PublicKey>
+): Promise<string> {
+  const { usdcMint } = await ensureConfig();
+  const position = await fetchPosition(owner);
+  const config = await fetchConfig();
+  const heldTypes = position.collaterals
+    .filter((entry: any) => !entry.mint.equals(PublicKey.default))
+    .map((entry: any) => config.collateralTypes.find((c: any) => c.mint.equals(entry.mint)));
+  const slotAccounts = position.collaterals
+    .filter((entry: any) => !entry.mint.equals(PublicKey.default))
+    .flatMap((entry: any) => {
+      const collateralType = config.collateralTypes.find((c: any) => c.mint.equals(entry.mint));
+      return [
+        { pubkey: collateralType.oracle, isWritable: false, isSigner: false },
+        { pubkey: vaultPda(entry.mint), isWritable: true, isSigner: false },
+        { pubkey: collateralAccounts.get(entry.mint.toBase58())!, isWritable: true, isSigner: false },
+      ];
+    });
+  return program.methods
+    .liquidateMulti(new BN(repayAmount))
+    .accounts({
+      userPosition: positionPda(owner),
+      config: configPda,
+      userBeingLiquidated: owner,
+      liquidatorUsdcAccount,
+      treasuryUsdcAccount: vaultPda(usdcMint),
+      vaultAuthority: vaultAuthorityPda,
+      liquidator: liquidator.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts([...slotAccounts, ...exchangeRateOracles(heldTypes)])
+    .signers([liquidator])
+    .rpc();
+}
+
+export function supplierPositionPda(owner: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("supplier_position"), owner.toBuffer()],
+    program.programId
+  )[0];
+}
+
+export async function supplyUsdc(
+  supplier: Keypair,
+  supplierUsdcAccount: PublicKey,
+  amount: number
+): Promise<string> {
+  const { usdcMint } = await ensureConfig();
+  return program.methods
+    .supplyUsdc(new BN(amount))
+    .accounts({
+      supplierPosition: supplierPositionPda(supplier.publicKey),
+      config: configPda,
+      supplierUsdcAccount,
+      treasuryUsdcAccount: vaultPda(usdcMint),
+      supplier: supplier.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([supplier])
+    .rpc();
+}
+
+export async function withdrawSupply(
+  supplier: Keypair,
+  supplierUsdcAccount: PublicKey,
+  amount: number
+): Promise<string> {
+  const { usdcMint } = await ensureConfig();
+  return program.methods
+    .withdrawSupply(new BN(amount))
+    .accounts({
+      supplierPosition: supplierPositionPda(supplier.publicKey),
+      config: configPda,
+      supplierUsdcAccount,
+      treasuryUsdcAccount: vaultPda(usdcMint),
+      vaultAuthority: vaultAuthorityPda,
+      supplier: supplier.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([supplier])
+    .rpc();
+}
+
+export async function migratePositionMint(owner: Keypair): Promise<string> {
+  return program.methods
+    .migratePositionMint()
+    .accounts({
+      userPosition: positionPda(owner.publicKey),
+      config: configPda,
+      owner: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc();
+}
+
+// Set the opaque id position events carry (32 bytes)
+export async function setWebhookId(owner: Keypair, webhookId: Buffer): Promise<string> {
+  return program.methods
+    .setWebhookId([...webhookId])
+    .accounts({
+      userPosition: positionPda(owner.publicKey),
+      owner: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc();
+}
+
+export async function fetchPosition(owner: PublicKey): Promise<any> {
+  return program.account.userPosition.fetch(positionPda(owner));
+}
+
+export async function setParams(params: Partial<typeof EMPTY_PARAMS>): Promise<string> {
+  return program.methods
+    .adminSetParams({ ...EMPTY_PARAMS, ...params })
+    .accounts({
+      config: configPda,
+      pendingParams: pendingParamsPda,
+      admin: admin.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+}
+
+// Commit parameter changes queued under a params timelock
+export async function applyPendingParams(): Promise<string> {
+  return program.methods
+    .applyPendingParams()
+    .accounts({
+      config: configPda,
+      pendingParams: pendingParamsPda,
+    })
+    .rpc();
+}
+
+export async function waitForSlot(slot: number): Promise<void> {
+  while ((await provider.connection.getSlot()) < slot) {
+    await new Promise((resolve) => setTimeout(resolve, 200));
+  }
+}
+
+export async function setMaxInterestRate(maxInterestRateBps: number): Promise<string> {
+  return program.methods
+    .adminSetMaxInterestRate(maxInterestRateBps)
+    .accounts({
+      config: configPda,
+      admin: admin.publicKey,
+    })
+    .rpc();
+}
+
+// Mirrors the PAUSE_* bitmask constants
+export const PAUSE_DEPOSITS = 1 << 0;
+export const PAUSE_BORROWS = 1 << 1;
+export const PAUSE_REPAYS = 1 << 2;
+export const PAUSE_WITHDRAWALS = 1 << 3;
+export const PAUSE_LIQUIDATIONS = 1 << 4;
+
+export const EMA_FOR_DEPOSITS = 1 << 0;
+export const EMA_FOR_BORROWS = 1 << 1;
+export const EMA_FOR_WITHDRAWALS = 1 << 2;
+export const EMA_FOR_LIQUIDATIONS = 1 << 3;
+
+// Point of the oracle confidence band collateral is valued at
+export const VALUATION_MID = 0;
+export const VALUATION_BID = 1;
+export const VALUATION_ASK = 2;
+
+// Why a position's credit limit changed (CreditLimitChanged.reason)
+export const CREDIT_LIMIT_DEPOSIT = 0;
+export const CREDIT_LIMIT_WITHDRAWAL = 1;
+export const CREDIT_LIMIT_BORROW = 2;
+export const CREDIT_LIMIT_REFRESH = 3;
+
+export async function setPauseFlags(pauseFlags: number): Promise<string> {
+  return program.methods
+    .adminSetPauseFlags(pauseFlags)
+    .accounts({
+      config: configPda,
+      admin: admin.publicKey,
+    })
+    .rpc();
+}
+
+export async function setPaused(paused: boolean): Promise<string> {
+  return program.methods
+    .adminSetPaused(paused)
+    .accounts({
+      config: configPda,
+      admin: admin.publicKey,
+    })
+    .rpc();
+}
+
+// Permanently shut the protocol down (irreversible: only run from shutdown.ts)
+export async function shutdownProtocol(confirmation = "SHUTDOWN"): Promise<string> {
+  return program.methods
+    .adminShutdown(Array.from(Buffer.from(confirmation.padEnd(8).slice(0, 8))))
+    .accounts({
+      config: configPda,
+      admin: admin.publicKey,
+    })
+    .rpc();
+}
+
+export async function setBorrowIndex(newIndex: BN, confirm = true): Promise<string> {
+  return program.methods
+    .adminSetBorrowIndex(newIndex, confirm)
+    .accounts({
+      config: configPda,
+      admin: admin.publicKey,
+    })
+    .rpc();
+}
+
+export async function setOracleDecimals(
+  oracle: PublicKey,
+  expectedQuoteDecimals: number | null
+): Promise<string> {
+  return program.methods
+    .adminSetOracleDecimals(oracle, expectedQuoteDecimals)
+    .accounts({
+      config: configPda,
+      admin: admin.publicKey,
+    })
+    .rpc();
+}
+
+// Reset total collateral from the vaults: WSOL first, then the other registered types
+export async function reconcileCollateral(): Promise<string> {
+  const config = await fetchConfig();
+  const mints: PublicKey[] = [config.wsolMint].concat(
+    config.collateralTypes
+      .filter((c: any) => !c.mint.equals(PublicKey.default) && !c.mint.equals(config.wsolMint))
+      .map((c: any) => c.mint)
+  );
+  return program.methods
+    .reconcileCollateral()
+    .accounts({
+      config: configPda,
+      admin: admin.publicKey,
+    })
+    .remainingAccounts(mints.map((mint) => ({ pubkey: vaultPda(mint), isSigner: false, isWritable: false })))
+    .rpc();
+}
+
+// Accrue interest, passing collateral oracles for the keeper health check
+export async function accrueInterest(oracles: PublicKey[] = []): Promise<string> {
+  return program.methods
+    .accrueInterest()
+    .accounts({
+      config: configPda,
+    })
+    .remainingAccounts(oracles.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false })))
+    .rpc();
+}
+
+export async function socializeBadDebt(owner: PublicKey): Promise<string> {
+  return program.methods
+    .adminSocializeBadDebt()
+    .accounts({
+      userPosition: positionPda(owner),
+      config: configPda,
+      admin: admin.publicKey,
+    })
+    .rpc();
+}
+
+export async function closeBadDebtPosition(owner: PublicKey): Promise<string> {
+  return program.methods
+    .adminCloseBadDebtPosition()
+    .accounts({
+      userPosition: positionPda(owner),
+      config: configPda,
+      owner,
+      admin: admin.publicKey,
+    })
+    .rpc();
+}
+
+// Admin mints `usdcAmount` USDC to itself and moves it into protocol reserves
+export async function fundReserves(usdcAmount: number): Promise<string> {
+  const { usdcMint } = await ensureConfig();
+  const adminUsdcAccount = await createAccount(
+    provider.connection,
+    admin,
+    usdcMint,
+    admin.publicKey,
+    Keypair.generate()
+  );
+  await mintTo(provider.connection, admin, usdcMint, adminUsdcAccount, admin, usdcAmount);
+  return program.methods
+    .adminFundReserves(new BN(usdcAmount))
+    .accounts({
+      config: configPda,
+      adminUsdcAccount,
+      treasuryUsdcAccount: vaultPda(usdcMint),
+      admin: admin.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .rpc();
+}
+
+export async function setSubsidy(
+  owner: PublicKey,
+  subsidyBps: number,
+  expiryTimestamp: number
+): Promise<string> {
+  return program.methods
+    .adminSetSubsidy(subsidyBps, new BN(expiryTimestamp))
+    .accounts({
+      userPosition: positionPda(owner),
+      config: configPda,
+      admin: admin.publicKey,
+    })
+    .rpc();
+}
+
+export async function recoverToken(
+  sourceTokenAccount: PublicKey,
+  recipientTokenAccount: PublicKey,
+  amount: number
+): Promise<string> {
+  return program.methods
+    .adminRecoverToken(new BN(amount))
+    .accounts({
+      config: configPda,
+      sourceTokenAccount,
+      recipientTokenAccount,
+      vaultAuthority: vaultAuthorityPda,
+      admin: admin.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .rpc();
+}
+
+// Every optional field of CollateralTypeParams, unset
+export const EMPTY_COLLATERAL_PARAMS = {
+  protocolLiquidationShareBps: null,
+  oracle: null,
+  liquidationPriority: null,
+  interestRateBps: null,
+  exchangeRateOracle: null,
+  liquidationBonusBps: null,
+  ltvBps: null,
+};
+
+export async function setCollateralType(
+  mint: PublicKey,
+  params: Partial<typeof EMPTY_COLLATERAL_PARAMS>
+): Promise<string> {
+  return program.methods
+    .adminSetCollateralType(mint, { ...EMPTY_COLLATERAL_PARAMS, ...params })
+    .accounts({
+      config: configPda,
+      collateralMint: mint,
+      admin: admin.publicKey,
+    })
+    .rpc();
+}
+
+// Registered collateral type entry for a mint
+export async function fetchCollateralType(mint: PublicKey): Promise<any> {
+  const config = await fetchConfig();
+  return config.collateralTypes.find((c: any) => c.mint.equals(mint));
+}
+
+// Versioned config snapshot with computed fields, read by simulation
+export async function getConfigView(): Promise<any> {
+  const { usdcMint } = await ensureConfig();
+  return program.methods
+    .getConfig()
+    .accounts({
+      config: configPda,
+      treasuryUsdcAccount: vaultPda(usdcMint),
+    })
+    .view();
+}
+
+// Lifetime protocol fees by source, read by simulation
+export async function getFeeBreakdown(): Promise<any> {
+  return program.methods.getFeeBreakdown().accounts({ config: configPda }).view();
+}
+
+export async function fetchConfig(): Promise<any> {
+  return program.account.config.fetch(configPda);
+}
+
+// Assert that a transaction fails with the given program error code
+export async function expectError(promise: Promise<unknown>, code: string): Promise<void> {
+  try {
+    await promise;
+  } catch (err: any) {
+    const actual = err?.error?.errorCode?.code ?? err?.message ?? String(err);
+    expect(String(actual)).to.include(code);
+    return;
+  }
+  expect.fail(`Expected transaction to fail with ${code}`);
+}
+
+export const DEBIT_TIER_BASIC = 0;
+export const DEBIT_TIER_PREMIUM = 1;
+
+export function debitAccountPda(owner: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("debit"), owner.toBuffer()],
+    program.programId
+  )[0];
+}
+
+// Mint `usdcAmount` to a fresh account for `user` and deposit it into their debit account
+export async function debitDeposit(user: Keypair, usdcAmount: number): Promise<string> {
+  const { usdcMint } = await ensureConfig();
+  const userUsdcAccount = await createAccount(provider.connection, user, usdcMint, user.publicKey);
+  await mintTo(provider.connection, admin, usdcMint, userUsdcAccount, admin, usdcAmount);
+  return program.methods
+    .debitDeposit(new BN(usdcAmount))
+    .accounts({
+      user: user.publicKey,
+      debitAccount: debitAccountPda(user.publicKey),
+      config: configPda,
+      userUsdcAccount,
+      vaultUsdcAccount: vaultPda(usdcMint),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([user])
+    .rpc();
+}
+
+export async function setDebitTier(owner: PublicKey, tier: number): Promise<string> {
+  return program.methods
+    .adminSetDebitTier(tier)
+    .accounts({
+      config: configPda,
+      debitAccount: debitAccountPda(owner),
+      admin: admin.publicKey,
+    })
+    .rpc();
+}
+
+export async function fetchDebitAccount(owner: PublicKey): Promise<any> {
+  return program.account.debitAccount.fetch(debitAccountPda(owner));
+}
+
+export async function debitSpend(owner: PublicKey, usdcAmount: number, linkedPosition?: PublicKey): Promise<string> {
+  return program.methods
+    .debitSpend(new BN(usdcAmount))
+    .accounts({
+      authority: admin.publicKey,
+      config: configPda,
+      debitAccount: debitAccountPda(owner),
+      linkedPosition: linkedPosition ?? null,
+    })
+    .rpc();
+}
+
+export async function debitSettle(owner: PublicKey, usdcAmount: number, linkedPosition?: PublicKey): Promise<string> {
+  return program.methods
+    .debitSettle(new BN(usdcAmount))
+    .accounts({
+      authority: admin.publicKey,
+      config: configPda,
+      debitAccount: debitAccountPda(owner),
+      linkedPosition: linkedPosition ?? null,
+    })
+    .rpc();
+}
+
+export const EMPTY_DEBIT_LIMITS = {
+  dailyLimit: null,
+  monthlyLimit: null,
+  perTxLimit: null,
+  resetSpent: false,
+};
+
+export async function setDebitLimits(
+  owner: PublicKey,
+  params: Partial<typeof EMPTY_DEBIT_LIMITS>
+): Promise<string> {
+  return program.methods
+    .adminSetDebitLimits({ ...EMPTY_DEBIT_LIMITS, ...params })
+    .accounts({
+      config: configPda,
+      debitAccount: debitAccountPda(owner),
+      admin: admin.publicKey,
+    })
+    .rpc();
+}
+
+// Link (or unlink) the owner's credit position to cover debit spends beyond the balance
+export async function linkCreditPosition(owner: Keypair, linked = true): Promise<string> {
+  return program.methods
+    .linkCreditPosition(linked)
+    .accounts({
+      debitAccount: debitAccountPda(owner.publicKey),
+      userPosition: positionPda(owner.publicKey),
+      owner: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc();
+}
+
+// Merge the position of `sourceOwner` into that of `owner`; both wallets sign
+export async function mergePositions(owner: Keypair, sourceOwner: Keypair): Promise<string> {
+  return program.methods
+    .mergePositions()
+    .accounts({
+      userPosition: positionPda(owner.publicKey),
+      sourcePosition: positionPda(sourceOwner.publicKey),
+      config: configPda,
+      owner: owner.publicKey,
+      sourceOwner: sourceOwner.publicKey,
+    })
+    .signers([owner, sourceOwner])
+    .rpc();
+}
@@ -0,0 +1,1081 @@
+import * as anchor from "@coral-xyz/anchor";
+import { TOKEN_PROGRAM_ID, createAccount, getAccount, getAssociatedTokenAddressSync, mintTo } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  provider,
+  program,
+  configPda,
+  vaultPda,
+  positionPda,
+  ensureConfig,
+  userWithCollateral,
+  borrowUsdc,
+  repayUsdc,
+  repayAllUsdc,
+  transactionLogs,
+  repayBatch,
+  recordDebt,
+  fundedLiquidator,
+  configureAutoDeleverage,
+  debitDeposit,
+  triggerAutoDeleverage,
+  pythFeed,
+  supplyUsdc,
+  withdrawSupply,
+  supplierPositionPda,
+  withdrawCollateral,
+  maxWithdrawable,
+  wrapSol,
+  refreshPosition,
+  fetchPosition,
+  fetchConfig,
+  setParams,
+  fundReserves,
+  repayWithCollateral,
+  debtAssetWithVault,
+  borrowAsset,
+  repayAsset,
+  returnDataU64,
+  admin,
+  setSubsidy,
+  expectError,
+  depositWsol,
+  CLIENT_VERSION,
+  setWebhookId,
+  withdrawAndUnwrap,
+  configureStopLoss,
+  executeStopLoss,
+  enforceBuffer,
+  depositAndBorrow,
+  fundedKeypair,
+  openPosition,
+  getLiquidationPrice,
+  refreshHealthFactor,
+  CREDIT_LIMIT_DEPOSIT,
+  initAndDeposit,
+  getFeeBreakdown,
+} from "./helpers";
+
+const SOL = anchor.web3.LAMPORTS_PER_SOL;
+const USDC = 1_000_000;
+
+describe("borrow_usdc", () => {
+  it("creates the USDC token account for a fresh wallet", async () => {
+    const { usdcMint } = await ensureConfig();
+    const owner = await userWithCollateral(10 * SOL);
+    const ata = getAssociatedTokenAddressSync(usdcMint, owner.publicKey);
+    expect(await provider.connection.getAccountInfo(ata)).to.equal(null);
+
+    await borrowUsdc(owner, 100 * USDC);
+
+    const account = await getAccount(provider.connection, ata);
+    expect(Number(account.amount)).to.equal(100 * USDC);
+    expect(account.owner.toBase58()).to.equal(owner.publicKey.toBase58());
+
+    const position = await fetchPosition(owner.publicKey);
+    expect(position.debtUsdc.toNumber()).to.equal(100 * USDC);
+    expect(position.lifetimeBorrows.toNumber()).to.equal(100 * USDC);
+  });
+
+  it("reuses an existing USDC token account", async () => {
+    const { usdcMint } = await ensureConfig();
+    const owner = await userWithCollateral(10 * SOL);
+
+    await borrowUsdc(owner, 50 * USDC);
+    await borrowUsdc(owner, 50 * USDC);
+
+    const ata = getAssociatedTokenAddressSync(usdcMint, owner.publicKey);
+    const account = await getAccount(provider.connection, ata);
+    expect(Number(account.amount)).to.equal(100 * USDC);
+  });
+
+  it("rejects borrowing past the credit limit", async () => {
+    // 10 SOL at $100 with 50% LTV allows $500
+    const owner = await userWithCollateral(10 * SOL);
+    await expectError(borrowUsdc(owner, 501 * USDC), "DebtLimitExceeded");
+  });
+
+  it("rejects borrows below the minimum", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    await expectError(borrowUsdc(owner, USDC - 1), "AmountTooSmall");
+  });
+
+  it("rejects a borrow larger than the treasury holds", async () => {
+    const { usdcMint } = await ensureConfig();
+    const owner = await userWithCollateral(10 * SOL);
+    const treasury = await getAccount(provider.connection, vaultPda(usdcMint));
+
+    await expectError(
+      borrowUsdc(owner, Number(treasury.amount) + USDC),
+      "InsufficientProtocolLiquidity"
+    );
+  });
+
+  it("keeps deleverage buffers out of reach of borrowers", async () => {
+    const { usdcMint } = await ensureConfig();
+    const buffered = await userWithCollateral(10 * SOL);
+    const borrower = await userWithCollateral(10 * SOL);
+    await borrowUsdc(buffered, 100 * USDC);
+
+    // Fund a buffer that leaves $50 of the treasury lendable
+    const treasury = Number((await getAccount(provider.connection, vaultPda(usdcMint))).amount);
+    const config = await fetchConfig();
+    const lendable = treasury - config.totalReservesUsdc.toNumber()
+      - config.totalDeleverageBuffersUsdc.toNumber() - config.totalDebitBalancesUsdc.toNumber();
+    const buffer = lendable - 50 * USDC;
+    const bufferedAta = getAssociatedTokenAddressSync(usdcMint, buffered.publicKey);
+    await mintTo(provider.connection, admin, usdcMint, bufferedAta, admin, buffer);
+    await configureAutoDeleverage(buffered, 12_000, buffer);
+
+    try {
+      // The treasury still holds the buffer, but none of it may be lent
+      expect(Number((await getAccount(provider.connection, vaultPda(usdcMint))).amount)).to.be.above(100 * USDC);
+      await expectError(borrowUsdc(borrower, 100 * USDC), "InsufficientProtocolLiquidity");
+      await borrowUsdc(borrower, 50 * USDC);
+    } finally {
+      // Opting out still refunds the whole buffer
+      await configureAutoDeleverage(buffered, 0, 0);
+    }
+    expect(Number((await getAccount(provider.connection, bufferedAta)).amount)).to.equal(100 * USDC + buffer);
+  });
+
+  describe("minimum position age", () => {
+    before(async () => {
+      await setParams({ minPositionAgeSecs: new anchor.BN(2) });
+    });
+
+    after(async () => {
+      await setParams({ minPositionAgeSecs: new anchor.BN(0) });
+    });
+
+    it("rejects a borrow right after the first deposit", async () => {
+      const owner = await userWithCollateral(10 * SOL);
+      await expectError(borrowUsdc(owner, 100 * USDC), "PositionTooNew");
+    });
+
+    it("allows the borrow once the position is old enough", async () => {
+      const owner = await userWithCollateral(10 * SOL);
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+      await borrowUsdc(owner, 100 * USDC);
+
+      const position = await fetchPosition(owner.publicKey);
+      expect(position.debtUsdc.toNumber()).to.equal(100 * USDC);
+    });
+  });
+
+  describe("per-position debt cap", () => {
+    after(async () => {
+      await setParams({ maxSinglePositionDebtUsdc: new anchor.BN(0) });
+    });
+
+    it("caps a well-collateralized whale", async () => {
+      // 50 SOL at $100 supports $2500 of credit, but the cap stops it at $1000
+      const whale = await userWithCollateral(50 * SOL);
+      await setParams({ maxSinglePositionDebtUsdc: new anchor.BN(1_000 * USDC) });
+
+      await borrowUsdc(whale, 1_000 * USDC);
+      await expectError(borrowUsdc(whale, USDC), "PositionDebtCapExceeded");
+      expect((await fetchPosition(whale.publicKey)).debtUsdc.toNumber()).to.equal(1_000 * USDC);
+    });
+
+    it("rejects a cap below the minimum borrow", async () => {
+      await expectError(setParams({ maxSinglePositionDebtUsdc: new anchor.BN(USDC - 1) }), "InvalidParameter");
+    });
+  });
+
+  describe("liquidity share cap", () => {
+    after(async () => {
+      await setParams({ maxBorrowLiquidityShareBps: 0 });
+    });
+
+    it("limits a well-collateralized borrow to its share of idle USDC", async () => {
+      const { usdcMint } = await ensureConfig();
+      const owner = await userWithCollateral(100 * SOL);
+
+      // 0.01% of the lendable treasury, far below the $5000 the collateral supports
+      await setParams({ maxBorrowLiquidityShareBps: 1 });
+      const treasury = Number((await getAccount(provider.connection, vaultPda(usdcMint))).amount);
+      const config = await fetchConfig();
+      const available = treasury - config.totalReservesUsdc.toNumber()
+        - config.totalDeleverageBuffersUsdc.toNumber() - config.totalDebitBalancesUsdc.toNumber();
+      const share = Math.floor(available / 10_000);
+      expect(share).to.be.lessThan(5_000 * USDC);
+
+      await expectError(borrowUsdc(owner, share + 1), "InsufficientProtocolLiquidity");
+      await borrowUsdc(owner, share);
+      expect((await fetchPosition(owner.publicKey)).debtUsdc.toNumber()).to.equal(share);
+    });
+
+    it("leaves owner-held USDC out of the share", async () => {
+      const { usdcMint } = await ensureConfig();
+      const owner = await userWithCollateral(100 * SOL);
+      await setParams({ maxBorrowLiquidityShareBps: 1 });
+
+      // $1000 of debit balance raises the treasury but not the share
+      await debitDeposit(await fundedKeypair(2), 1_000 * USDC);
+      const treasury = Number((await getAccount(provider.connection, vaultPda(usdcMint))).amount);
+      const config = await fetchConfig();
+      const ownerHeld = config.totalReservesUsdc.toNumber()
+        + config.totalDeleverageBuffersUsdc.toNumber() + config.totalDebitBalancesUsdc.toNumber();
+      const share = Math.floor((treasury - ownerHeld) / 10_000);
+      expect(Math.floor(treasury / 10_000)).to.be.above(share);
+
+      await expectError(borrowUsdc(owner, share + 1), "InsufficientProtocolLiquidity");
+      await borrowUsdc(owner, share);
+    });
+  });
+
+  describe("borrow index age", () => {
+    before(async () => {
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(0) });
+    });
+
+    after(async () => {
+      await setParams({ maxBorrowIndexAgeSecs: new anchor.BN(0), minAccrualIntervalSecs: new anchor.BN(60) });
+    });
+
+    it("rejects a borrow after a long idle period unless accrued first", async () => {
+      const owner = await userWithCollateral(10 * SOL);
+      await setParams({ maxBorrowIndexAgeSecs: new anchor.BN(2) });
+      await new Promise((resolve) => setTimeout(resolve, 4000));
+
+      await expectError(borrowUsdc(owner, 100 * USDC), "StaleBorrowIndex");
+
+      await borrowUsdc(owner, 100 * USDC, undefined, CLIENT_VERSION, true);
+      const position = await fetchPosition(owner.publicKey);
+      expect(position.debtUsdc.toNumber()).to.equal(100 * USDC);
+    });
+
+    it("rejects an age limit shorter than the accrual interval", async () => {
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(60) });
+      await expectError(setParams({ maxBorrowIndexAgeSecs: new anchor.BN(30) }), "InvalidParameter");
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(0) });
+    });
+  });
+});
+
+describe("debt assets", () => {
+  // $1000 collateral at the 60% liquidation threshold
+  async function assetAccount(owner: anchor.web3.Keypair, mint: anchor.web3.PublicKey) {
+    return createAccount(provider.connection, owner, mint, owner.publicKey, anchor.web3.Keypair.generate());
+  }
+
+  it("values a $1 stablecoin debt one-to-one", async () => {
+    const stable = await debtAssetWithVault(6, 1_000 * USDC);
+    const owner = await userWithCollateral(10 * SOL);
+    const destination = await assetAccount(owner, stable);
+
+    await borrowAsset(owner, stable, destination, 200 * USDC);
+
+    expect(Number((await getAccount(provider.connection, destination)).amount)).to.equal(200 * USDC);
+    const position = await fetchPosition(owner.publicKey);
+    expect(position.debtMint.toBase58()).to.equal(stable.toBase58());
+    // 600 / 200
+    expect(await returnDataU64(await refreshPosition(owner.publicKey))).to.be.closeTo(30_000, 1);
+  });
+
+  it("values an oracle-priced debt at its feed", async () => {
+    const oracle = pythFeed("sol-usd-100");
+    const priced = await debtAssetWithVault(9, 10 * SOL, oracle);
+    const owner = await userWithCollateral(10 * SOL);
+    const destination = await assetAccount(owner, priced);
+
+    // 3 units at $100 is $300 of debt
+    await borrowAsset(owner, priced, destination, 3 * SOL, oracle);
+
+    expect(await returnDataU64(await refreshPosition(owner.publicKey, undefined, oracle))).to.be.closeTo(20_000, 1);
+    // Without the feed the debt can't be valued
+    await expectError(refreshPosition(owner.publicKey), "InvalidOracle");
+    // Nor can the credit limit be exceeded in value: $300 + $300 > $500
+    await expectError(borrowAsset(owner, priced, destination, 3 * SOL, oracle), "DebtLimitExceeded");
+  });
+
+  it("keeps a position on one debt asset until it is repaid", async () => {
+    const stable = await debtAssetWithVault(6, 1_000 * USDC);
+    const owner = await userWithCollateral(10 * SOL);
+    const account = await assetAccount(owner, stable);
+    await borrowAsset(owner, stable, account, 100 * USDC);
+
+    await expectError(borrowUsdc(owner, 10 * USDC), "DebtMintMismatch");
+
+    // Repaying in full (interest included) frees the position to borrow USDC again
+    await mintTo(provider.connection, admin, stable, account, admin, USDC);
+    await repayAsset(owner, stable, account, 101 * USDC);
+    expect((await fetchPosition(owner.publicKey)).debtUsdc.toNumber()).to.equal(0);
+    await borrowUsdc(owner, 10 * USDC);
+  });
+});
+
+describe("record_debt", () => {
+  before(async () => {
+    // Accrue every call at a high rate so the position settles real interest
+    await setParams({ minAccrualIntervalSecs: new anchor.BN(0), interestRateBps: 3600 });
+  });
+
+  after(async () => {
+    await setParams({ minAccrualIntervalSecs: new anchor.BN(60), interestRateBps: 1200 });
+  });
+
+  it("adds accrued interest and new principal to the global total", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    await recordDebt(owner, 300 * USDC);
+    const totalBefore = (await fetchConfig()).totalDebtUsdc.toNumber();
+    const debtBefore = (await fetchPosition(owner.publicKey)).debtUsdc.toNumber();
+
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    await recordDebt(owner, 100 * USDC);
+
+    // Only this position settled, so the total moved exactly as its debt did
+    const totalAfter = (await fetchConfig()).totalDebtUsdc.toNumber();
+    const debtAfter = (await fetchPosition(owner.publicKey)).debtUsdc.toNumber();
+    expect(debtAfter).to.be.greaterThan(debtBefore + 100 * USDC);
+    expect(totalAfter - totalBefore).to.equal(debtAfter - debtBefore);
+  });
+});
+
+describe("client versioning", () => {
+  after(async () => {
+    await setParams({ minClientVersion: 0 });
+  });
+
+  it("rejects borrows and deposits from an under-versioned client", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    await setParams({ minClientVersion: CLIENT_VERSION });
+
+    await expectError(borrowUsdc(owner, 100 * USDC, undefined, CLIENT_VERSION - 1), "ClientVersionTooOld");
+    await expectError(depositWsol(owner, SOL, undefined, CLIENT_VERSION - 1), "ClientVersionTooOld");
+
+    await borrowUsdc(owner, 100 * USDC);
+    await depositWsol(owner, SOL);
+    const position = await fetchPosition(owner.publicKey);
+    expect(position.debtUsdc.toNumber()).to.equal(100 * USDC);
+    expect(position.collateralAmount.toNumber()).to.equal(11 * SOL);
+  });
+
+  it("rejects a minimum above the program's protocol version", async () => {
+    await expectError(setParams({ minClientVersion: CLIENT_VERSION + 1 }), "InvalidParameter");
+  });
+});
+
+// Only meaningful against a build with `anchor build -- --features verbose-state`
+describe("verbose position state", () => {
+  const itVerbose = process.env.VERBOSE_STATE ? it : it.skip;
+
+  itVerbose("logs a full position snapshot after a borrow", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    const logs = await transactionLogs(await borrowUsdc(owner, 100 * USDC));
+
+    const snapshot = logs.find((line) => line.includes("Position state: owner="));
+    expect(snapshot).to.include(owner.publicKey.toBase58());
+    expect(snapshot).to.include(`debt=${100 * USDC}`);
+    expect(logs.some((line) => line.includes("Position state: lifetime_borrows="))).to.equal(true);
+  });
+});
+
+describe("repay_usdc", () => {
+  it("repays part of the debt", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    await borrowUsdc(owner, 100 * USDC);
+
+    await repayUsdc(owner, 40 * USDC);
+
+    const position = await fetchPosition(owner.publicKey);
+    expect(position.debtUsdc.toNumber()).to.be.closeTo(60 * USDC, 1_000);
+  });
+
+  it("rejects a token program that is not SPL Token", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    await borrowUsdc(owner, 100 * USDC);
+
+    await expectError(
+      repayUsdc(owner, 10 * USDC, anchor.web3.SystemProgram.programId),
+      "InvalidProgramId"
+    );
+  });
+
+  describe("repay all", () => {
+    before(async () => {
+      // Accrue every call at a high rate so the debt moves between reading and repaying
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(0), interestRateBps: 3600 });
+    });
+
+    after(async () => {
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(60), interestRateBps: 1200 });
+    });
+
+    it("repays the debt accrued at execution, leaving no residual", async () => {
+      const owner = await userWithCollateral(10 * SOL);
+      await borrowUsdc(owner, 100 * USDC);
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      await repayAllUsdc(owner, 101 * USDC);
+
+      expect((await fetchPosition(owner.publicKey)).debtUsdc.toNumber()).to.equal(0);
+    });
+
+    it("rejects when the accrued debt exceeds the cap", async () => {
+      const owner = await userWithCollateral(10 * SOL);
+      await borrowUsdc(owner, 100 * USDC);
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+
+      // Interest has accrued past the borrowed principal
+      await expectError(repayAllUsdc(owner, 100 * USDC), "SlippageExceeded");
+    });
+  });
+});
+
+describe("deposit_and_borrow", () => {
+  it("sizes the borrow at price minus confidence", async () => {
+    const { usdcMint } = await ensureConfig();
+    const owner = await fundedKeypair(15);
+    await openPosition(owner);
+
+    // At the $100 midpoint 10 SOL supports $500; at $99.95 (minus the $0.05 band) only $499.75
+    await depositAndBorrow(owner, 10 * SOL, 1_000 * USDC);
+
+    const position = await fetchPosition(owner.publicKey);
+    expect(position.collateralAmount.toNumber()).to.equal(10 * SOL);
+    expect(position.debtUsdc.toNumber()).to.equal(499_750_000);
+    expect(position.debtUsdc.toNumber()).to.be.lessThan(500 * USDC);
+    const ata = await getAccount(provider.connection, getAssociatedTokenAddressSync(usdcMint, owner.publicKey));
+    expect(Number(ata.amount)).to.equal(499_750_000);
+  });
+
+  it("borrows only what was asked for when that is less", async () => {
+    const owner = await fundedKeypair(15);
+    await openPosition(owner);
+
+    await depositAndBorrow(owner, 10 * SOL, 100 * USDC);
+    expect((await fetchPosition(owner.publicKey)).debtUsdc.toNumber()).to.equal(100 * USDC);
+  });
+});
+
+describe("repay_with_collateral", () => {
+  before(async () => {
+    await fundReserves(1_000 * USDC);
+  });
+
+  it("deleverages a position below the borrow buffer", async () => {
+    // $1000 collateral at 60% threshold against $550 debt is 1.09, under the 1.10 buffer
+    const owner = await userWithCollateral(10 * SOL);
+    await recordDebt(owner, 550 * USDC);
+    await expectError(withdrawCollateral(owner, SOL / 100), "HealthFactorTooLow");
+
+    // Selling $5 of collateral only lifts it to 1.095, still under the buffer
+    await repayWithCollateral(owner, SOL / 20);
+
+    const position = await fetchPosition(owner.publicKey);
+    expect(position.collateralAmount.toNumber()).to.equal(10 * SOL - SOL / 20);
+    expect(position.debtUsdc.toNumber()).to.be.closeTo(545 * USDC, 1_000);
+  });
+
+  it("caps the sale at the outstanding debt", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    await recordDebt(owner, 100 * USDC);
+
+    await repayWithCollateral(owner, 5 * SOL);
+
+    const position = await fetchPosition(owner.publicKey);
+    expect(position.debtUsdc.toNumber()).to.equal(0);
+    expect(position.collateralAmount.toNumber()).to.be.closeTo(9 * SOL, SOL / 1000);
+  });
+});
+
+describe("repay_batch", () => {
+  it("repays several positions and skips one without debt", async () => {
+    const first = await userWithCollateral(10 * SOL);
+    const second = await userWithCollateral(10 * SOL);
+    const debtFree = await userWithCollateral(10 * SOL);
+    await recordDebt(first, 100 * USDC);
+    await recordDebt(second, 100 * USDC);
+    const payer = await fundedLiquidator(1_000 * USDC);
+
+    await repayBatch(payer.keypair, payer.usdcAccount, [
+      { owner: first.publicKey, amount: 40 * USDC },
+      { owner: debtFree.publicKey, amount: 40 * USDC },
+      { owner: second.publicKey, amount: 60 * USDC },
+    ]);
+
+    expect((await fetchPosition(first.publicKey)).debtUsdc.toNumber()).to.be.closeTo(60 * USDC, 1_000);
+    expect((await fetchPosition(second.publicKey)).debtUsdc.toNumber()).to.be.closeTo(40 * USDC, 1_000);
+    expect((await fetchPosition(debtFree.publicKey)).debtUsdc.toNumber()).to.equal(0);
+
+    // Only the two indebted positions were charged to the payer
+    const payerAccount = await getAccount(provider.connection, payer.usdcAccount);
+    expect(Number(payerAccount.amount)).to.equal(900 * USDC);
+  });
+
+  it("rejects a mismatched number of positions", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    const payer = await fundedLiquidator(100 * USDC);
+    await expectError(
+      program.methods
+        .repayBatch([new anchor.BN(USDC), new anchor.BN(USDC)])
+        .accounts({
+          config: configPda,
+          payerUsdcAccount: payer.usdcAccount,
+          treasuryUsdcAccount: vaultPda((await ensureConfig()).usdcMint),
+          payer: payer.keypair.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts([{ pubkey: positionPda(owner.publicKey), isWritable: true, isSigner: false }])
+        .signers([payer.keypair])
+        .rpc(),
+      "InvalidParameter"
+    );
+  });
+});
+
+describe("prepayment fee", () => {
+  before(async () => {
+    await ensureConfig();
+    // 1% on repayments within 2 seconds of the borrow; keep debt fixed meanwhile
+    await setParams({
+      prepaymentFeeBps: 100,
+      minLoanTermSecs: new anchor.BN(2),
+      minAccrualIntervalSecs: new anchor.BN(3600),
+    });
+  });
+
+  after(async () => {
+    await setParams({
+      prepaymentFeeBps: 0,
+      minLoanTermSecs: new anchor.BN(0),
+      minAccrualIntervalSecs: new anchor.BN(60),
+    });
+  });
+
+  it("charges the fee on an early payoff and sends it to reserves", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    await recordDebt(owner, 100 * USDC);
+    const payer = await fundedLiquidator(1_000 * USDC);
+    const reservesBefore = (await fetchConfig()).totalReservesUsdc.toNumber();
+
+    await repayBatch(payer.keypair, payer.usdcAccount, [{ owner: owner.publicKey, amount: 200 * USDC }]);
+
+    expect((await fetchPosition(owner.publicKey)).debtUsdc.toNumber()).to.equal(0);
+    const payerAccount = await getAccount(provider.connection, payer.usdcAccount);
+    expect(Number(payerAccount.amount)).to.equal(899 * USDC);
+    expect((await fetchConfig()).totalReservesUsdc.toNumber() - reservesBefore).to.equal(USDC);
+  });
+
+  it("charges every early repayment, so splitting the payoff saves nothing", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    const payer = await fundedLiquidator(1_000 * USDC);
+    await recordDebt(owner, 100 * USDC);
+
+    // All but one unit, then the last unit, whose 1% fee rounds up to a unit
+    await repayBatch(payer.keypair, payer.usdcAccount, [{ owner: owner.publicKey, amount: 100 * USDC - 1 }]);
+    await repayBatch(payer.keypair, payer.usdcAccount, [{ owner: owner.publicKey, amount: 1 }]);
+
+    expect((await fetchPosition(owner.publicKey)).debtUsdc.toNumber()).to.equal(0);
+    const payerAccount = await getAccount(provider.connection, payer.usdcAccount);
+    expect(Number(payerAccount.amount)).to.equal(899 * USDC - 1);
+  });
+
+  it("charges nothing once the minimum term has passed", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    await recordDebt(owner, 100 * USDC);
+    const payer = await fundedLiquidator(1_000 * USDC);
+
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+    await repayBatch(payer.keypair, payer.usdcAccount, [{ owner: owner.publicKey, amount: 200 * USDC }]);
+
+    expect((await fetchPosition(owner.publicKey)).debtUsdc.toNumber()).to.equal(0);
+    const payerAccount = await getAccount(provider.connection, payer.usdcAccount);
+    expect(Number(payerAccount.amount)).to.equal(900 * USDC);
+  });
+
+  it("moves the repayment and the fee into the treasury on a repay-all", async () => {
+    const { usdcMint } = await ensureConfig();
+    const owner = await userWithCollateral(10 * SOL);
+    await recordDebt(owner, 100 * USDC);
+    const treasuryBefore = Number((await getAccount(provider.connection, vaultPda(usdcMint))).amount);
+
+    // The helper funds the owner with the 200 USDC cap; $100 debt plus the 1% fee is charged
+    await repayAllUsdc(owner, 200 * USDC);
+
+    expect((await fetchPosition(owner.publicKey)).debtUsdc.toNumber()).to.equal(0);
+    const ownerAccount = await getAccount(provider.connection, getAssociatedTokenAddressSync(usdcMint, owner.publicKey));
+    expect(Number(ownerAccount.amount)).to.equal(99 * USDC);
+    const treasuryAfter = Number((await getAccount(provider.connection, vaultPda(usdcMint))).amount);
+    expect(treasuryAfter - treasuryBefore).to.equal(101 * USDC);
+  });
+
+  it("books the fee under prepayment fees", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    await recordDebt(owner, 100 * USDC);
+    const payer = await fundedLiquidator(1_000 * USDC);
+    const before = await getFeeBreakdown();
+
+    await repayBatch(payer.keypair, payer.usdcAccount, [{ owner: owner.publicKey, amount: 200 * USDC }]);
+
+    const after = await getFeeBreakdown();
+    expect(after.prepaymentFeesUsdc.toNumber() - before.prepaymentFeesUsdc.toNumber()).to.equal(USDC);
+    expect(after.liquidationFeesUsdc.toNumber()).to.equal(before.liquidationFeesUsdc.toNumber());
+  });
+});
+
+describe("auto-deleverage", () => {
+  it("repays from the buffer after a price drop and restores health", async () => {
+    // 10 SOL at $100 against $450 debt is 1.33; at $80 it falls to 1.07
+    const owner = await userWithCollateral(10 * SOL);
+    await borrowUsdc(owner, 450 * USDC);
+    await configureAutoDeleverage(owner, 12_000, 100 * USDC);
+
+    await expectError(triggerAutoDeleverage(owner.publicKey), "AutoDeleverageNotTriggered");
+
+    await triggerAutoDeleverage(owner.publicKey, pythFeed("sol-usd-80"));
+
+    // Debt drops to the 1.2 line: 800 * 60% / 1.2 = $400
+    const position = await fetchPosition(owner.publicKey);
+    expect(position.debtUsdc.toNumber()).to.be.closeTo(400 * USDC, 1_000);
+    expect(position.deleverageBufferUsdc.toNumber()).to.be.closeTo(50 * USDC, 1_000);
+    expect(position.lastHealthFactor.toNumber()).to.be.at.least(12_000);
+  });
+
+  it("refunds the buffer when opting out", async () => {
+    const { usdcMint } = await ensureConfig();
+    const owner = await userWithCollateral(10 * SOL);
+    await borrowUsdc(owner, 100 * USDC);
+    await configureAutoDeleverage(owner, 12_000, 100 * USDC);
+
+    await configureAutoDeleverage(owner, 0, 0);
+
+    const position = await fetchPosition(owner.publicKey);
+    expect(position.deleverageBufferUsdc.toNumber()).to.equal(0);
+    const ata = await getAccount(provider.connection, getAssociatedTokenAddressSync(usdcMint, owner.publicKey));
+    expect(Number(ata.amount)).to.equal(100 * USDC);
+  });
+
+  it("rejects a threshold at or below the liquidation line", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    await borrowUsdc(owner, 100 * USDC);
+    await expectError(configureAutoDeleverage(owner, 10_000, 0), "InvalidParameter");
+  });
+});
+
+describe("stop-loss", () => {
+  before(async () => {
+    await fundReserves(1_000 * USDC);
+  });
+
+  it("sells collateral to reserves once health drops below the stop-loss", async () => {
+    // 10 SOL at $100 against $400 debt is 1.5; at $80 it falls to 1.2
+    const owner = await userWithCollateral(10 * SOL);
+    await recordDebt(owner, 400 * USDC);
+    await configureStopLoss(owner, 13_000);
+
+    await expectError(executeStopLoss(owner.publicKey), "StopLossNotTriggered");
+
+    await executeStopLoss(owner.publicKey, pythFeed("sol-usd-80"));
+
+    // Selling x brings (800 - x) * 60% / (400 - x) back to 1.3: x = 40 / 0.7
+    const position = await fetchPosition(owner.publicKey);
+    expect(position.debtUsdc.toNumber()).to.be.closeTo(342_857_143, 5_000);
+    expect(position.lastHealthFactor.toNumber()).to.be.closeTo(13_000, 2);
+  });
+
+  it("does nothing for a position without a stop-loss", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    await recordDebt(owner, 400 * USDC);
+    await expectError(executeStopLoss(owner.publicKey, pythFeed("sol-usd-80")), "StopLossNotTriggered");
+  });
+
+  it("rejects a stop-loss at or below the liquidation line", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    await expectError(configureStopLoss(owner, 10_000), "InvalidParameter");
+  });
+});
+
+describe("enforce_buffer", () => {
+  it("flags a position that drifted into the warning band and blocks borrowing", async () => {
+    // 10 SOL against $450 debt: 1.33 at $100, 1.07 at $80 (under the 1.10 buffer, above 1.0)
+    const owner = await userWithCollateral(10 * SOL);
+    await recordDebt(owner, 450 * USDC);
+
+    await enforceBuffer(owner.publicKey);
+    expect((await fetchPosition(owner.publicKey)).bufferBreached).to.equal(false);
+
+    await enforceBuffer(owner.publicKey, pythFeed("sol-usd-80"));
+    const position = await fetchPosition(owner.publicKey);
+    expect(position.bufferBreached).to.equal(true);
+    expect(position.lastHealthFactor.toNumber()).to.be.closeTo(10_667, 2);
+
+    // Even at a price the borrow itself would pass, the flag holds until cleared
+    await expectError(borrowUsdc(owner, 10 * USDC), "BufferBreached");
+
+    await enforceBuffer(owner.publicKey);
+    expect((await fetchPosition(owner.publicKey)).bufferBreached).to.equal(false);
+    await borrowUsdc(owner, 10 * USDC);
+  });
+});
+
+describe("position event sequence", () => {
+  it("increments across deposit, borrow and repay", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    expect((await fetchPosition(owner.publicKey)).eventSeq.toNumber()).to.equal(1);
+
+    await borrowUsdc(owner, 100 * USDC);
+    expect((await fetchPosition(owner.publicKey)).eventSeq.toNumber()).to.equal(2);
+
+    let event: any;
+    const listener = program.addEventListener("DebtRepaid", (e) => {
+      event = e;
+    });
+    await repayUsdc(owner, 40 * USDC);
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await program.removeEventListener(listener);
+
+    expect((await fetchPosition(owner.publicKey)).eventSeq.toNumber()).to.equal(3);
+    expect(event.eventSeq.toNumber()).to.equal(3);
+  });
+
+  it("carries the owner's webhook id", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    const webhookId = Buffer.alloc(32, 7);
+    await setWebhookId(owner, webhookId);
+    expect(Buffer.from((await fetchPosition(owner.publicKey)).webhookId).equals(webhookId)).to.equal(true);
+
+    let event: any;
+    const listener = program.addEventListener("CollateralDeposited", (e) => {
+      event = e;
+    });
+    await depositWsol(owner, SOL);
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await program.removeEventListener(listener);
+
+    expect(Buffer.from(event.webhookId).equals(webhookId)).to.equal(true);
+  });
+
+  it("records the oracle price a deposit was valued at", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+
+    let event: any;
+    const listener = program.addEventListener("CollateralDeposited", (e) => {
+      event = e;
+    });
+    await depositWsol(owner, SOL, pythFeed("sol-usd-80"));
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await program.removeEventListener(listener);
+
+    // The credit limit follows from the emitted price: 11 SOL * $80 * 50%
+    expect(event.oraclePrice.toNumber()).to.be.closeTo(80 * USDC, USDC);
+    expect(event.creditLimit.toNumber()).to.equal(
+      Math.floor((11 * event.oraclePrice.toNumber()) / 2)
+    );
+    expect(event.oraclePublishSlot.toNumber()).to.be.greaterThan(0);
+    expect(event.oraclePublishSlot.toNumber()).to.be.at.most(await provider.connection.getSlot());
+  });
+
+  it("reports the credit left after existing debt", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    await recordDebt(owner, 300 * USDC);
+
+    let event: any;
+    const listener = program.addEventListener("CollateralDeposited", (e) => {
+      event = e;
+    });
+    await depositWsol(owner, SOL);
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await program.removeEventListener(listener);
+
+    // 11 SOL at $100 gives a $550 limit, $250 of it still free after $300 of debt
+    const position = await fetchPosition(owner.publicKey);
+    expect(event.creditLimit.toNumber()).to.be.closeTo(550 * USDC, USDC);
+    expect(event.availableCredit.toNumber()).to.equal(
+      event.creditLimit.toNumber() - position.debtUsdc.toNumber()
+    );
+    expect(event.availableCredit.toNumber()).to.be.closeTo(250 * USDC, USDC);
+  });
+
+  it("opens the position of a fresh wallet in the deposit", async () => {
+    const owner = await fundedKeypair(13);
+    expect(await provider.connection.getAccountInfo(positionPda(owner.publicKey))).to.equal(null);
+
+    await initAndDeposit(owner, 10 * SOL);
+    let position = await fetchPosition(owner.publicKey);
+    expect(position.isInitialized).to.equal(true);
+    expect(position.owner.equals(owner.publicKey)).to.equal(true);
+    expect(position.collateralAmount.toNumber()).to.equal(10 * SOL);
+    expect(position.creditLimit.toNumber()).to.be.closeTo(500 * USDC, USDC);
+
+    // An existing position is deposited into, not reset
+    await initAndDeposit(owner, SOL);
+    position = await fetchPosition(owner.publicKey);
+    expect(position.collateralAmount.toNumber()).to.equal(11 * SOL);
+    expect(position.eventSeq.toNumber()).to.equal(2);
+  });
+
+  it("emits a credit limit change for a deposit", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    const limitBefore = (await fetchPosition(owner.publicKey)).creditLimit.toNumber();
+
+    let event: any;
+    const listener = program.addEventListener("CreditLimitChanged", (e) => {
+      event = e;
+    });
+    await depositWsol(owner, SOL, pythFeed("sol-usd-80"));
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await program.removeEventListener(listener);
+
+    const position = await fetchPosition(owner.publicKey);
+    expect(event.user.equals(owner.publicKey)).to.equal(true);
+    expect(event.reason).to.equal(CREDIT_LIMIT_DEPOSIT);
+    expect(event.oldCreditLimit.toNumber()).to.equal(limitBefore);
+    expect(event.newCreditLimit.toNumber()).to.equal(position.creditLimit.toNumber());
+    expect(event.oraclePrice.toNumber()).to.be.closeTo(80 * USDC, USDC);
+  });
+});
+
+describe("supply", () => {
+  it("supplies and withdraws USDC", async () => {
+    const supplier = await fundedLiquidator(1_000 * USDC);
+    await supplyUsdc(supplier.keypair, supplier.usdcAccount, 1_000 * USDC);
+    await withdrawSupply(supplier.keypair, supplier.usdcAccount, 1_000 * USDC);
+
+    const account = await getAccount(provider.connection, supplier.usdcAccount);
+    expect(Number(account.amount)).to.equal(1_000 * USDC);
+  });
+
+  describe("reserve ratio floor", () => {
+    let borrower: anchor.web3.Keypair | undefined;
+    let recorded = 0;
+
+    after(async () => {
+      await setParams({ minReserveRatioBps: 1000 });
+      // Take the recorded debt back out of the protocol total
+      if (borrower) {
+        await repayUsdc(borrower, recorded + USDC);
+      }
+    });
+
+    it("caps a withdrawal that would dip below the floor", async () => {
+      const { usdcMint } = await ensureConfig();
+      const supplier = await fundedLiquidator(1_000 * USDC);
+      await supplyUsdc(supplier.keypair, supplier.usdcAccount, 1_000 * USDC);
+
+      // With a 100% ratio the floor is total debt plus reserves; raise debt so
+      // only $400 of the treasury sits above it
+      await setParams({ minReserveRatioBps: 10_000 });
+      const treasury = Number((await getAccount(provider.connection, vaultPda(usdcMint))).amount);
+      const config = await fetchConfig();
+      recorded = treasury - 400 * USDC - config.totalDebtUsdc.toNumber() - config.totalReservesUsdc.toNumber()
+        - config.totalDeleverageBuffersUsdc.toNumber() - config.totalDebitBalancesUsdc.toNumber();
+      borrower = await userWithCollateral(10 * SOL);
+      await recordDebt(borrower, recorded);
+
+      await withdrawSupply(supplier.keypair, supplier.usdcAccount, 1_000 * USDC);
+
+      const account = await getAccount(provider.connection, supplier.usdcAccount);
+      expect(Number(account.amount)).to.be.closeTo(400 * USDC, USDC);
+      const position = await program.account.supplierPosition.fetch(supplierPositionPda(supplier.keypair.publicKey));
+      expect(position.suppliedUsdc.toNumber()).to.be.closeTo(600 * USDC, USDC);
+
+      await expectError(
+        withdrawSupply(supplier.keypair, supplier.usdcAccount, 600 * USDC),
+        "ReserveRatioBreached"
+      );
+    });
+
+    it("keeps debit balances and deleverage buffers out of supplier withdrawals", async () => {
+      const { usdcMint } = await ensureConfig();
+      const supplier = await fundedLiquidator(1_000 * USDC);
+      await supplyUsdc(supplier.keypair, supplier.usdcAccount, 1_000 * USDC);
+
+      // A deleverage buffer and a debit balance fill all but $400 of the room above the floor
+      await setParams({ minReserveRatioBps: 10_000 });
+      const buffered = await userWithCollateral(10 * SOL);
+      await borrowUsdc(buffered, 100 * USDC);
+      await configureAutoDeleverage(buffered, 12_000, 50 * USDC);
+      const treasury = Number((await getAccount(provider.connection, vaultPda(usdcMint))).amount);
+      const config = await fetchConfig();
+      const headroom = treasury - config.totalDebtUsdc.toNumber() - config.totalReservesUsdc.toNumber()
+        - config.totalDeleverageBuffersUsdc.toNumber() - config.totalDebitBalancesUsdc.toNumber();
+      await debitDeposit(await fundedKeypair(2), headroom - 400 * USDC);
+      expect((await fetchConfig()).totalDeleverageBuffersUsdc.toNumber()).to.be.at.least(50 * USDC);
+
+      await withdrawSupply(supplier.keypair, supplier.usdcAccount, 1_000 * USDC);
+
+      const account = await getAccount(provider.connection, supplier.usdcAccount);
+      expect(Number(account.amount)).to.be.closeTo(400 * USDC, USDC);
+    });
+  });
+
+});
+
+describe("withdraw_collateral", () => {
+  it("withdraws and recomputes the credit limit from one price read", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    await borrowUsdc(owner, 200 * USDC);
+    const destination = await wrapSol(owner, 0);
+
+    await withdrawCollateral(owner, 2 * SOL, destination);
+
+    // 8 SOL at $100 with 50% LTV
+    const position = await fetchPosition(owner.publicKey);
+    expect(position.collateralAmount.toNumber()).to.equal(8 * SOL);
+    expect(position.creditLimit.toNumber()).to.equal(400 * USDC);
+    const account = await getAccount(provider.connection, destination);
+    expect(Number(account.amount)).to.equal(2 * SOL);
+  });
+
+  it("withdraws to a third-party recipient account", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    const ownAccount = await wrapSol(owner, 0);
+    const coldWallet = await fundedKeypair(1);
+    const recipient = await wrapSol(coldWallet, 0);
+
+    await withdrawCollateral(owner, 2 * SOL, ownAccount, undefined, recipient);
+
+    expect(Number((await getAccount(provider.connection, recipient)).amount)).to.equal(2 * SOL);
+    expect(Number((await getAccount(provider.connection, ownAccount)).amount)).to.equal(0);
+    expect((await fetchPosition(owner.publicKey)).collateralAmount.toNumber()).to.equal(8 * SOL);
+  });
+
+  it("rejects the vault as the recipient", async () => {
+    const { wsolMint } = await ensureConfig();
+    const owner = await userWithCollateral(10 * SOL);
+    await expectError(
+      withdrawCollateral(owner, SOL, undefined, undefined, vaultPda(wsolMint)),
+      "ReceivingAccountIsVault"
+    );
+  });
+
+  it("rejects a withdrawal that breaks the health buffer", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    await borrowUsdc(owner, 400 * USDC);
+
+    // 7 SOL left: $420 liquidation value against $400 debt is below 1.10
+    await expectError(withdrawCollateral(owner, 3 * SOL), "HealthFactorTooLow");
+  });
+
+  it("delivers the unwrapped part as native SOL", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    const destination = await wrapSol(owner, 0);
+    const lamportsBefore = await provider.connection.getBalance(owner.publicKey);
+
+    // 3 SOL out: 2 as native SOL, 1 kept wrapped
+    await withdrawAndUnwrap(owner, 3 * SOL, 2 * SOL, destination);
+
+    // The temporary account's rent comes straight back, so only the SOL is added
+    expect(await provider.connection.getBalance(owner.publicKey)).to.equal(lamportsBefore + 2 * SOL);
+    expect(Number((await getAccount(provider.connection, destination)).amount)).to.equal(SOL);
+    expect((await fetchPosition(owner.publicKey)).collateralAmount.toNumber()).to.equal(7 * SOL);
+
+    // The temporary account is gone, so it can be reused
+    await withdrawAndUnwrap(owner, SOL, SOL, destination);
+    expect((await fetchPosition(owner.publicKey)).collateralAmount.toNumber()).to.equal(6 * SOL);
+  });
+
+  it("withdraws everything once debt-free", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+
+    await withdrawCollateral(owner, 10 * SOL);
+
+    const position = await fetchPosition(owner.publicKey);
+    expect(position.collateralAmount.toNumber()).to.equal(0);
+    expect(position.creditLimit.toNumber()).to.equal(0);
+  });
+
+  describe("max_withdrawable", () => {
+    before(async () => {
+      await ensureConfig();
+      // Keep the debt fixed between the view and the withdrawal
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(3600) });
+    });
+
+    after(async () => {
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(60) });
+    });
+
+    it("returns the exact amount that keeps the health buffer", async () => {
+      const owner = await userWithCollateral(10 * SOL);
+      await borrowUsdc(owner, 400 * USDC);
+
+      // $400 debt at 1.10 needs $733.33 collateral at a 60% threshold: 7.33333334 SOL stays
+      const max = await maxWithdrawable(owner.publicKey);
+      expect(max).to.equal(2_666_666_660);
+
+      await expectError(withdrawCollateral(owner, max + 1), "HealthFactorTooLow");
+      await withdrawCollateral(owner, max);
+
+      const position = await fetchPosition(owner.publicKey);
+      expect(position.collateralAmount.toNumber()).to.equal(10 * SOL - max);
+    });
+
+    it("returns all collateral for a debt-free position", async () => {
+      const owner = await userWithCollateral(3 * SOL);
+      expect(await maxWithdrawable(owner.publicKey)).to.equal(3 * SOL);
+    });
+  });
+});
+
+describe("get_liquidation_price", () => {
+  before(async () => {
+    await ensureConfig();
+    // Keep the debt fixed between the view and the refresh
+    await setParams({ minAccrualIntervalSecs: new anchor.BN(3600) });
+  });
+
+  after(async () => {
+    await setParams({ minAccrualIntervalSecs: new anchor.BN(60) });
+  });
+
+  it("returns the price at which the health factor is exactly 1.0", async () => {
+    // $480 debt against 10 SOL at a 60% threshold: 10 * P * 60% = 480 at P = $80
+    const owner = await userWithCollateral(10 * SOL);
+    await recordDebt(owner, 480 * USDC);
+
+    const price = await getLiquidationPrice(owner.publicKey);
+    expect(price).to.equal(80 * USDC);
+
+    // Fed back through the $80 feed, the position sits exactly at 1.0
+    expect(await refreshHealthFactor(owner.publicKey, pythFeed("sol-usd-80"))).to.equal(10_000);
+  });
+
+  it("returns 0 for a position without debt", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    expect(await getLiquidationPrice(owner.publicKey)).to.equal(0);
+  });
+});
+
+describe("interest subsidy", () => {
+  before(async () => {
+    await ensureConfig();
+    // Accrue every second so a short wait produces visible interest
+    await setParams({ minAccrualIntervalSecs: new anchor.BN(0), interestRateBps: 3600 });
+    await fundReserves(1_000 * USDC);
+  });
+
+  after(async () => {
+    await setParams({ minAccrualIntervalSecs: new anchor.BN(60), interestRateBps: 1200 });
+  });
+
+  it("pays a subsidized position's interest out of reserves", async () => {
+    const subsidized = await userWithCollateral(10 * SOL);
+    const unsubsidized = await userWithCollateral(10 * SOL);
+    await borrowUsdc(subsidized, 400 * USDC);
+    await borrowUsdc(unsubsidized, 400 * USDC);
+
+    const expiry = Math.floor(Date.now() / 1000) + 3600;
+    await setSubsidy(subsidized.publicKey, 10_000, expiry);
+    const reservesBefore = (await fetchConfig()).totalReservesUsdc.toNumber();
+
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+    await refreshPosition(subsidized.publicKey);
+    await refreshPosition(unsubsidized.publicKey);
+
+    const subsidizedPosition = await fetchPosition(subsidized.publicKey);
+    const unsubsidizedPosition = await fetchPosition(unsubsidized.publicKey);
+    expect(subsidizedPosition.debtUsdc.toNumber()).to.equal(400 * USDC);
+    expect(unsubsidizedPosition.debtUsdc.toNumber()).to.be.greaterThan(400 * USDC);
+    expect((await fetchConfig()).totalReservesUsdc.toNumber()).to.be.lessThan(reservesBefore);
+  });
+
+  it("rejects a subsidy above 100%", async () => {
+    const owner = await userWithCollateral(SOL);
+    const expiry = Math.floor(Date.now() / 1000) + 3600;
+    await expectError(setSubsidy(owner.publicKey, 10_001, expiry), "InvalidPercentage");
+  });
+});
@@ -0,0 +1,732 @@
+import * as anchor from "@coral-xyz/anchor";
+import { createAccount, createMint, getAccount, mintTo } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  ensureConfig,
+  userWithCollateral,
+  recordDebt,
+  fundedLiquidator,
+  liquidate,
+  liquidateToTarget,
+  fetchPosition,
+  setParams,
+  expectError,
+  fetchConfig,
+  positionPda,
+  vaultPda,
+  provider,
+  program,
+  socializeBadDebt,
+  closeBadDebtPosition,
+  setCollateralType,
+  fetchCollateralType,
+  admin,
+  initVault,
+  openPosition,
+  wrapSol,
+  fundedKeypair,
+  pythFeed,
+  depositMultiCollateral,
+  liquidateMulti,
+  emitLiquidationWarning,
+  fundReserves,
+  repayUsdc,
+  VALUATION_MID,
+  VALUATION_ASK,
+  getFeeBreakdown,
+  withdrawCollateral,
+  refreshHealthFactor,
+  HEALTH_FACTOR_SCALE,
+} from "./helpers";
+
+const SOL = anchor.web3.LAMPORTS_PER_SOL;
+const USDC = 1_000_000;
+
+describe("liquidate", () => {
+  before(async () => {
+    await ensureConfig();
+  });
+
+  it("rejects liquidating a healthy position", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    await recordDebt(owner, 400 * USDC);
+    const liquidator = await fundedLiquidator(1_000 * USDC);
+
+    await expectError(liquidate(liquidator, owner.publicKey, 100 * USDC), "PositionHealthy");
+  });
+
+  it("reports a debt-free position as healthy", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    const liquidator = await fundedLiquidator(1_000 * USDC);
+
+    await expectError(liquidate(liquidator, owner.publicKey, 100 * USDC), "PositionHealthy");
+  });
+
+  it("rejects the collateral vault as the receiving account", async () => {
+    const { wsolMint } = await ensureConfig();
+    const owner = await userWithCollateral(10 * SOL);
+    await recordDebt(owner, 700 * USDC);
+    const liquidator = await fundedLiquidator(1_000 * USDC);
+
+    await expectError(
+      liquidate({ ...liquidator, collateralAccount: vaultPda(wsolMint) }, owner.publicKey, 100 * USDC),
+      "ReceivingAccountIsVault"
+    );
+  });
+
+  it("partially liquidates up to half the debt", async () => {
+    // $1000 collateral at 60% threshold against $700 debt
+    const owner = await userWithCollateral(10 * SOL);
+    await recordDebt(owner, 700 * USDC);
+    const liquidator = await fundedLiquidator(1_000 * USDC);
+
+    await liquidate(liquidator, owner.publicKey, 700 * USDC);
+
+    // $350 repaid, $371 (350 + 6% bonus) of collateral seized at $100
+    const position = await fetchPosition(owner.publicKey);
+    expect(position.debtUsdc.toNumber()).to.be.closeTo(350 * USDC, 1_000);
+    expect(position.collateralAmount.toNumber()).to.be.closeTo(629 * SOL / 100, SOL / 1000);
+    expect(position.liquidationCount).to.equal(1);
+  });
+
+  it("sizes down after the owner repays first", async () => {
+    const owner = await userWithCollateral(10 * SOL);
+    await recordDebt(owner, 700 * USDC);
+    const liquidator = await fundedLiquidator(1_000 * USDC);
+
+    // The owner's repay lands before the liquidation, leaving $650 (still 0.92)
+    await repayUsdc(owner, 50 * USDC);
+    await liquidate(liquidator, owner.publicKey, 350 * USDC);
+
+    // Half of the fresh $650, not of the $700 the liquidator saw
+    const liquidatorUsdc = await getAccount(provider.connection, liquidator.usdcAccount);
+    expect(1_000 * USDC - Number(liquidatorUsdc.amount)).to.be.closeTo(325 * USDC, 1_000);
+    const position = await fetchPosition(owner.publicKey);
+    expect(position.debtUsdc.toNumber()).to.be.closeTo(325 * USDC, 1_000);
+  });
+
+  it("emits the oracle price and health factor it liquidated at", async () => {
+    // $1000 collateral at 60% threshold against $700 debt is 0.857
+    const owner = await userWithCollateral(10 * SOL);
+    await recordDebt(owner, 700 * USDC);
+    const liquidator = await fundedLiquidator(1_000 * USDC);
+
+    let event: any;
+    const listener = program.addEventListener("PositionLiquidated", (e) => {
+      event = e;
+    });
+    await liquidate(liquidator, owner.publicKey, 100 * USDC);
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await program.removeEventListener(listener);
+
+    expect(event.user.toBase58()).to.equal(owner.publicKey.toBase58());
+    expect(event.oraclePrice.toNumber()).to.equal(100 * USDC);
+    expect(event.healthFactorAtLiquidation.toNumber()).to.be.closeTo(8571, 1);
+  });
+
+  describe("liquidation to a target health factor", () => {
+    // $1000 collateral at 60% threshold against $700 debt is 0.857
+    const healthOf = (position: any) =>
+      ((position.collateralAmount.toNumber() / SOL) * 100 * 6_000) / (position.debtUsdc.toNumber() / USDC);
+
+    it("repays exactly enough to reach the target", async () => {
+      const owner = await userWithCollateral(10 * SOL);
+      await recordDebt(owner, 700 * USDC);
+      const liquidator = await fundedLiquidator(1_000 * USDC);
+
+      await liquidateToTarget(liquidator, owner.publicKey, 10_500);
+
+      // (735 - 600) / (1.05 - 1.06 * 0.6) = $326.09 repaid, well under half the debt
+      const position = await fetchPosition(owner.publicKey);
+      expect(position.debtUsdc.toNumber()).to.be.closeTo(373_913_000, 1_000);
+      expect(healthOf(position)).to.be.closeTo(10_500, 1);
+    });
+
+    it("stops at the close factor when the target needs more", async () => {
+      const owner = await userWithCollateral(10 * SOL);
+      await recordDebt(owner, 700 * USDC);
+      const liquidator = await fundedLiquidator(1_000 * USDC);
+
+      await liquidateToTarget(liquidator, owner.publicKey, 20_000);
+
+      const position = await fetchPosition(owner.publicKey);
+      expect(position.debtUsdc.toNumber()).to.be.closeTo(350 * USDC, 1_000);
+      expect(healthOf(position)).to.be.below(20_000);
+    });
+
+    it("rejects a target at or below the current health factor", async () => {
+      const owner = await userWithCollateral(10 * SOL);
+      await recordDebt(owner, 700 * USDC);
+      const liquidator = await fundedLiquidator(1_000 * USDC);
+
+      await expectError(liquidateToTarget(liquidator, owner.publicKey, 8_000), "InvalidParameter");
+    });
+  });
+
+  describe("liquidation warnings", () => {
+    // $1000 collateral at 60% threshold; the default warning band is 1.0 to 1.15
+    it("warns for a position between the warning and liquidation thresholds", async () => {
+      const owner = await userWithCollateral(10 * SOL);
+      await recordDebt(owner, 550 * USDC);
+
+      let event: any;
+      const listener = program.addEventListener("LiquidationWarning", (e) => {
+        event = e;
+      });
+      await emitLiquidationWarning(owner.publicKey);
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(event.user.toBase58()).to.equal(owner.publicKey.toBase58());
+      expect(event.healthFactor.toNumber()).to.be.closeTo(10909, 1);
+      expect(event.warningThresholdBps).to.equal(11_500);
+    });
+
+    it("rejects warnings outside the band", async () => {
+      const healthy = await userWithCollateral(10 * SOL);
+      await recordDebt(healthy, 400 * USDC);
+      await expectError(emitLiquidationWarning(healthy.publicKey), "PositionNotInWarningBand");
+
+      const liquidatable = await userWithCollateral(10 * SOL);
+      await recordDebt(liquidatable, 700 * USDC);
+      await expectError(emitLiquidationWarning(liquidatable.publicKey), "PositionNotInWarningBand");
+    });
+  });
+
+  describe("health factor boundary", () => {
+    // $1000 collateral at 60% threshold supports exactly $600 of debt
+    it("treats a position at exactly 1.0 as healthy", async () => {
+      const owner = await userWithCollateral(10 * SOL);
+      await recordDebt(owner, 600 * USDC);
+      const liquidator = await fundedLiquidator(1_000 * USDC);
+
+      // Accrued interest rounds the health factor to 9999, within the 1 bps allowance
+      await expectError(liquidate(liquidator, owner.publicKey, 100 * USDC), "PositionHealthy");
+      await expectError(liquidate(liquidator, owner.publicKey, 100 * USDC), "PositionHealthy");
+    });
+
+    it("liquidates a position clearly below 1.0", async () => {
+      const owner = await userWithCollateral(10 * SOL);
+      await recordDebt(owner, 601 * USDC);
+      const liquidator = await fundedLiquidator(1_000 * USDC);
+
+      await liquidate(liquidator, owner.publicKey, 100 * USDC);
+      const position = await fetchPosition(owner.publicKey);
+      expect(position.liquidationCount).to.equal(1);
+    });
+
+    it("rejects an allowance above 100 bps", async () => {
+      await expectError(setParams({ liquidationHealthEpsilonBps: 101 }), "InvalidParameter");
+    });
+  });
+
+  describe("collateral fraction cap", () => {
+    after(async () => {
+      await setParams({ maxLiquidationCollateralFractionBps: 10_000 });
+    });
+
+    it("caps seizure below the half-debt limit", async () => {
+      // $1000 collateral against $700 debt: the debt cap alone would seize 3.71 SOL
+      const owner = await userWithCollateral(10 * SOL);
+      await recordDebt(owner, 700 * USDC);
+      const liquidator = await fundedLiquidator(1_000 * USDC);
+
+      await setParams({ maxLiquidationCollateralFractionBps: 2_000 });
+      await liquidate(liquidator, owner.publicKey, 350 * USDC);
+
+      // At most 2 SOL ($200) seized, so $200 / 1.06 = $188.68 repaid
+      const position = await fetchPosition(owner.publicKey);
+      expect(position.collateralAmount.toNumber()).to.be.closeTo(8 * SOL, SOL / 1000);
+      expect(position.debtUsdc.toNumber()).to.be.closeTo(700 * USDC - 188_679_245, 1_000);
+    });
+
+    it("rejects a zero or above-100% fraction", async () => {
+      await expectError(setParams({ maxLiquidationCollateralFractionBps: 0 }), "InvalidPercentage");
+      await expectError(setParams({ maxLiquidationCollateralFractionBps: 10_001 }), "InvalidPercentage");
+    });
+  });
+
+  describe("minimum residual collateral", () => {
+    after(async () => {
+      await setParams({ minResidualCollateral: new anchor.BN(SOL / 10) });
+    });
+
+    it("upgrades a partial liquidation that would strand dust collateral", async () => {
+      const owner = await userWithCollateral(10 * SOL);
+      await recordDebt(owner, 700 * USDC);
+      const liquidator = await fundedLiquidator(1_000 * USDC);
+
+      // A half liquidation would leave ~6.29 SOL, below the 7 SOL minimum
+      await setParams({ minResidualCollateral: new anchor.BN(7 * SOL) });
+      await liquidate(liquidator, owner.publicKey, 350 * USDC);
+
+      // Whole debt repaid, $742 (700 + 6% bonus) of collateral seized at $100
+      const position = await fetchPosition(owner.publicKey);
+      expect(position.debtUsdc.toNumber()).to.equal(0);
+      expect(position.collateralAmount.toNumber()).to.be.closeTo(258 * SOL / 100, SOL / 1000);
+    });
+
+    it("only charges for the collateral received when seizure is clamped", async () => {
+      // $100 of collateral against $300 debt: half the debt plus bonus exceeds the collateral
+      const owner = await userWithCollateral(SOL);
+      await recordDebt(owner, 300 * USDC);
+      const liquidator = await fundedLiquidator(1_000 * USDC);
+
+      // No residual minimum, so the partial path does the clamping
+      await setParams({ minResidualCollateral: new anchor.BN(0) });
+      await liquidate(liquidator, owner.publicKey, 150 * USDC);
+
+      // All $100 of collateral seized for $94.34 (100 / 1.06), not the requested $150
+      const liquidatorUsdc = await getAccount(provider.connection, liquidator.usdcAccount);
+      const repaid = 1_000 * USDC - Number(liquidatorUsdc.amount);
+      expect(repaid).to.be.closeTo(94_339_622, 1_000);
+
+      const position = await fetchPosition(owner.publicKey);
+      expect(position.collateralAmount.toNumber()).to.equal(0);
+      expect(position.debtUsdc.toNumber()).to.be.closeTo(300 * USDC - repaid, 1_000);
+    });
+  });
+
+  describe("global debt total", () => {
+    before(async () => {
+      // Accrue every call at a high rate so the position settles real interest
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(0), interestRateBps: 3600 });
+    });
+
+    after(async () => {
+      await setParams({ minAccrualIntervalSecs: new anchor.BN(60), interestRateBps: 1200 });
+    });
+
+    it("moves total debt in step with the liquidated position, interest included", async () => {
+      const owner = await userWithCollateral(10 * SOL);
+      await recordDebt(owner, 700 * USDC);
+      const liquidator = await fundedLiquidator(1_000 * USDC);
+      const totalBefore = (await fetchConfig()).totalDebtUsdc.toNumber();
+      const debtBefore = (await fetchPosition(owner.publicKey)).debtUsdc.toNumber();
+
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+      await liquidate(liquidator, owner.publicKey, 350 * USDC);
+
+      // Only this position settled, so both moved by interest minus the repayment
+      const totalAfter = (await fetchConfig()).totalDebtUsdc.toNumber();
+      const debtAfter = (await fetchPosition(owner.publicKey)).debtUsdc.toNumber();
+      expect(debtAfter).to.be.greaterThan(debtBefore - 350 * USDC);
+      expect(totalAfter - totalBefore).to.equal(debtAfter - debtBefore);
+    });
+  });
+
+  describe("minimum debt after liquidation", () => {
+    after(async () => {
+      await setParams({ minDebtAfterLiquidation: new anchor.BN(0) });
+    });
+
+    it("upgrades a partial liquidation that would leave dust debt", async () => {
+      const owner = await userWithCollateral(10 * SOL);
+      await recordDebt(owner, 700 * USDC);
+      const liquidator = await fundedLiquidator(1_000 * USDC);
+
+      // A half liquidation would leave $350 of debt, below the $400 minimum
+      await setParams({ minDebtAfterLiquidation: new anchor.BN(400 * USDC) });
+      await liquidate(liquidator, owner.publicKey, 350 * USDC);
+
+      const position = await fetchPosition(owner.publicKey);
+      expect(position.debtUsdc.toNumber()).to.equal(0);
+      expect(position.collateralAmount.toNumber()).to.be.closeTo(258 * SOL / 100, SOL / 1000);
+    });
+
+    it("leaves a partial liquidation above the minimum alone", async () => {
+      const owner = await userWithCollateral(10 * SOL);
+      await recordDebt(owner, 700 * USDC);
+      const liquidator = await fundedLiquidator(1_000 * USDC);
+
+      await setParams({ minDebtAfterLiquidation: new anchor.BN(300 * USDC) });
+      await liquidate(liquidator, owner.publicKey, 350 * USDC);
+
+      const position = await fetchPosition(owner.publicKey);
+      expect(position.debtUsdc.toNumber()).to.be.closeTo(350 * USDC, 1_000);
+    });
+  });
+
+  describe("full liquidation rebate", () => {
+    before(async () => {
+      await fundReserves(100 * USDC);
+      await setParams({ fullLiquidationRebateBps: 100 });
+    });
+
+    after(async () => {
+      await setParams({ fullLiquidationRebateBps: 0, minDebtAfterLiquidation: new anchor.BN(0) });
+    });
+
+    it("pays the rebate out of reserves when the whole debt is cleared", async () => {
+      const owner = await userWithCollateral(10 * SOL);
+      await recordDebt(owner, 700 * USDC);
+      const liquidator = await fundedLiquidator(1_000 * USDC);
+      const reservesBefore = (await fetchConfig()).totalReservesUsdc.toNumber();
+
+      // The $400 minimum upgrades the half liquidation to a full one
+      await setParams({ minDebtAfterLiquidation: new anchor.BN(400 * USDC) });
+      await liquidate(liquidator, owner.publicKey, 350 * USDC);
+      expect((await fetchPosition(owner.publicKey)).debtUsdc.toNumber()).to.equal(0);
+
+      // $700 repaid, 1% of it ($7) paid back from reserves
+      const rebate = reservesBefore - (await fetchConfig()).totalReservesUsdc.toNumber();
+      expect(rebate).to.be.closeTo(7 * USDC, 1_000);
+      const balance = Number(
+        (await getAccount(anchor.getProvider().connection, liquidator.usdcAccount)).amount
+      );
+      expect(balance).to.be.closeTo(307 * USDC, 1_000);
+    });
+
+    it("pays nothing for a partial liquidation", async () => {
+      const owner = await userWithCollateral(10 * SOL);
+      await recordDebt(owner, 700 * USDC);
+      const liquidator = await fundedLiquidator(1_000 * USDC);
+      const reservesBefore = (await fetchConfig()).totalReservesUsdc.toNumber();
+
+      await setParams({ minDebtAfterLiquidation: new anchor.BN(0) });
+      await liquidate(liquidator, owner.publicKey, 350 * USDC);
+
+      expect((await fetchConfig()).totalReservesUsdc.toNumber()).to.equal(reservesBefore);
+      const balance = Number(
+        (await getAccount(anchor.getProvider().connection, liquidator.usdcAccount)).amount
+      );
+      expect(balance).to.equal(650 * USDC);
+    });
+
+    it("rejects a rebate above 5%", async () => {
+      await expectError(setParams({ fullLiquidationRebateBps: 600 }), "InvalidPercentage");
+    });
+  });
+
+  describe("liquidation valuation mode", () => {
+    after(async () => {
+      await setParams({ liquidationValuationMode: VALUATION_MID });
+    });
+
+    it("judges eligibility at the high end of the band", async () => {
+      // $100 +/- $10 against $620 debt: 0.97 at the price, 1.06 at $110
+      const owner = await userWithCollateral(10 * SOL);
+      await recordDebt(owner, 620 * USDC);
+      const liquidator = await fundedLiquidator(1_000 * USDC);
+      const wide = pythFeed("sol-usd-100-wide");
+
+      await setParams({ liquidationValuationMode: VALUATION_ASK });
+      await expectError(liquidate(liquidator, owner.publicKey, 100 * USDC, wide), "PositionHealthy");
+
+      await setParams({ liquidationValuationMode: VALUATION_MID });
+      await liquidate(liquidator, owner.publicKey, 100 * USDC, wide);
+      expect((await fetchPosition(owner.publicKey)).debtUsdc.toNumber()).to.be.closeTo(520 * USDC, 1_000);
+    });
+  });
+
+  describe("protocol liquidation share", () => {
+    after(async () => {
+      const { wsolMint } = await ensureConfig();
+      await setCollateralType(wsolMint, { protocolLiquidationShareBps: 0 });
+    });
+
+    it("splits the bonus between the liquidator and reserves", async () => {
+      const { wsolMint } = await ensureConfig();
+      await setCollateralType(wsolMint, { protocolLiquidationShareBps: 5000 });
+
+      const owner = await userWithCollateral(10 * SOL);
+      await recordDebt(owner, 700 * USDC);
+      const liquidator = await fundedLiquidator(1_000 * USDC);
+      const reservesBefore = (await fetchCollateralType(wsolMint)).reserveCollateral.toNumber();
+
+      await liquidate(liquidator, owner.publicKey, 350 * USDC);
+
+      // $350 repaid with a $21 bonus at $100: half the bonus (0.105 SOL) goes to reserves
+      const received = Number(
+        (await getAccount(anchor.getProvider().connection, liquidator.collateralAccount)).amount
+      );
+      const reserves = (await fetchCollateralType(wsolMint)).reserveCollateral.toNumber() - reservesBefore;
+      expect(reserves).to.be.closeTo(0.105 * SOL, SOL / 10_000);
+      expect(received).to.be.closeTo(3.605 * SOL, SOL / 1000);
+
+      const position = await fetchPosition(owner.publicKey);
+      expect(10 * SOL - position.collateralAmount.toNumber()).to.equal(received + reserves);
+    });
+
+    it("books the protocol's bonus share under liquidation fees", async () => {
+      const { wsolMint } = await ensureConfig();
+      await setCollateralType(wsolMint, { protocolLiquidationShareBps: 5000 });
+
+      const owner = await userWithCollateral(10 * SOL);
+      await recordDebt(owner, 700 * USDC);
+      const liquidator = await fundedLiquidator(1_000 * USDC);
+      const before = await getFeeBreakdown();
+
+      await liquidate(liquidator, owner.publicKey, 350 * USDC);
+
+      // Half the $21 bonus, valued at the $100 seizure price
+      const after = await getFeeBreakdown();
+      expect(after.liquidationFeesUsdc.toNumber() - before.liquidationFeesUsdc.toNumber()).to.be.closeTo(10.5 * USDC, 1_000);
+      expect(after.prepaymentFeesUsdc.toNumber()).to.equal(before.prepaymentFeesUsdc.toNumber());
+    });
+
+    it("lets the protocol liquidator repay from reserves", async () => {
+      const { wsolMint } = await ensureConfig();
+      const backstop = await fundedLiquidator(USDC);
+      await setParams({ protocolLiquidator: backstop.keypair.publicKey });
+      await fundReserves(1_000 * USDC);
+
+      const owner = await userWithCollateral(10 * SOL);
+      await recordDebt(owner, 700 * USDC);
+      const usdcReservesBefore = (await fetchConfig()).totalReservesUsdc.toNumber();
+      const collateralReservesBefore = (await fetchCollateralType(wsolMint)).reserveCollateral.toNumber();
+
+      await liquidate(backstop, owner.publicKey, 350 * USDC);
+      await setParams({ protocolLiquidator: anchor.web3.PublicKey.default });
+
+      // $350 came out of reserves; all 3.71 SOL seized stays in the vault
+      const usdcReserves = (await fetchConfig()).totalReservesUsdc.toNumber();
+      expect(usdcReservesBefore - usdcReserves).to.be.closeTo(350 * USDC, 1_000);
+      const seized = (await fetchCollateralType(wsolMint)).reserveCollateral.toNumber() - collateralReservesBefore;
+      expect(seized).to.be.closeTo(3.71 * SOL, SOL / 1000);
+
+      const connection = anchor.getProvider().connection;
+      expect(Number((await getAccount(connection, backstop.usdcAccount)).amount)).to.equal(USDC);
+      expect(Number((await getAccount(connection, backstop.collateralAccount)).amount)).to.equal(0);
+      const position = await fetchPosition(owner.publicKey);
+      expect(10 * SOL - position.collateralAmount.toNumber()).to.equal(seized);
+    });
+
+    it("rejects a share that leaves liquidators too little bonus", async () => {
+      const { wsolMint } = await ensureConfig();
+      // Shares above half the bonus are rejected outright
+      await expectError(
+        setCollateralType(wsolMint, { protocolLiquidationShareBps: 7000 }),
+        "InvalidPercentage"
+      );
+      // A 3% bonus with a 50% share would leave liquidators 1.5%, below the 2% minimum
+      await setCollateralType(wsolMint, { protocolLiquidationShareBps: 5000 });
+      await expectError(setParams({ liquidationBonusBps: 300 }), "InvalidParameter");
+    });
+  });
+
+  describe("protocol-owned positions", () => {
+    const NO_OWNERS = Array(4).fill(anchor.web3.PublicKey.default);
+
+    after(async () => {
+      await setParams({ protocolOwners: NO_OWNERS, protocolLiquidator: anchor.web3.PublicKey.default });
+    });
+
+    it("reserves protocol-owned positions for the protocol liquidator", async () => {
+      const backstop = await fundedLiquidator(USDC);
+      await fundReserves(1_000 * USDC);
+      const owner = await userWithCollateral(10 * SOL);
+      await recordDebt(owner, 700 * USDC);
+      await setParams({
+        protocolOwners: [owner.publicKey, ...NO_OWNERS.slice(1)],
+        protocolLiquidator: backstop.keypair.publicKey,
+      });
+
+      const external = await fundedLiquidator(1_000 * USDC);
+      await expectError(liquidate(external, owner.publicKey, 350 * USDC), "ProtocolPositionRestricted");
+
+      await liquidate(backstop, owner.publicKey, 350 * USDC);
+      const position = await fetchPosition(owner.publicKey);
+      expect(position.debtUsdc.toNumber()).to.be.closeTo(350 * USDC, 1_000);
+    });
+  });
+
+  describe("bad debt", () => {
+    after(async () => {
+      await setParams({ minResidualCollateral: new anchor.BN(SOL / 10) });
+    });
+
+    it("socializes and force-closes an emptied position", async () => {
+      // $1000 of collateral cannot cover $1200 of debt plus the bonus
+      const owner = await userWithCollateral(10 * SOL);
+      await recordDebt(owner, 1_200 * USDC);
+      const liquidator = await fundedLiquidator(2_000 * USDC);
+
+      await setParams({ minResidualCollateral: new anchor.BN(100 * SOL) });
+      await liquidate(liquidator, owner.publicKey, 100 * USDC);
+
+      let position = await fetchPosition(owner.publicKey);
+      expect(position.collateralAmount.toNumber()).to.equal(0);
+      const residualDebt = position.debtUsdc.toNumber();
+      expect(residualDebt).to.be.greaterThan(0);
+
+      // Closing before the write-off is rejected
+      await expectError(closeBadDebtPosition(owner.publicKey), "PositionNotBadDebt");
+
+      const before = await fetchConfig();
+      await socializeBadDebt(owner.publicKey);
+      const after = await fetchConfig();
+      position = await fetchPosition(owner.publicKey);
+      const written = position.badDebtUsdc.toNumber();
+      expect(written).to.be.closeTo(residualDebt, 1_000);
+      expect(after.totalBadDebtUsdc.toNumber() - before.totalBadDebtUsdc.toNumber()).to.equal(written);
+
+      const ownerBalance = await provider.connection.getBalance(owner.publicKey);
+      await closeBadDebtPosition(owner.publicKey);
+
+      expect(await provider.connection.getAccountInfo(positionPda(owner.publicKey))).to.equal(null);
+      expect(await provider.connection.getBalance(owner.publicKey)).to.be.greaterThan(ownerBalance);
+    });
+
+    it("refuses to socialize a position that still holds collateral", async () => {
+      const owner = await userWithCollateral(10 * SOL);
+      await recordDebt(owner, 700 * USDC);
+
+      await expectError(socializeBadDebt(owner.publicKey), "PositionNotBadDebt");
+    });
+  });
+
+  describe("multi-collateral priority", () => {
+    let otherMint: anchor.web3.PublicKey;
+
+    before(async () => {
+      await ensureConfig();
+      otherMint = await createMint(provider.connection, admin, admin.publicKey, null, 9);
+      await initVault(otherMint);
+    });
+
+    after(async () => {
+      const { wsolMint, solUsdOracle } = await ensureConfig();
+      await setCollateralType(wsolMint, { oracle: solUsdOracle, liquidationPriority: 0 });
+    });
+
+    // 5 WSOL + 5 of the other collateral at $100 against $500 debt is 1.2
+    async function twoCollateralPosition(): Promise<anchor.web3.Keypair> {
+      const { wsolMint } = await ensureConfig();
+      const price = pythFeed("sol-usd-100");
+      await setCollateralType(wsolMint, { oracle: price });
+      await setCollateralType(otherMint, { oracle: price });
+
+      const owner = await fundedKeypair(10);
+      await openPosition(owner);
+      const wsol = await wrapSol(owner, 5 * SOL);
+      await depositMultiCollateral(owner, wsolMint, wsol, 5 * SOL);
+      const other = await createAccount(provider.connection, owner, otherMint, owner.publicKey);
+      await mintTo(provider.connection, admin, otherMint, other, admin, 5 * SOL);
+      await depositMultiCollateral(owner, otherMint, other, 5 * SOL);
+      await recordDebt(owner, 500 * USDC);
+      return owner;
+    }
+
+    // Both collaterals fall to $80: 800 * 60% / 500 = 0.96
+    async function liquidateAfterPriceDrop(owner: anchor.web3.Keypair): Promise<Map<string, number>> {
+      const { wsolMint } = await ensureConfig();
+      const price = pythFeed("sol-usd-80");
+      await setCollateralType(wsolMint, { oracle: price });
+      await setCollateralType(otherMint, { oracle: price });
+
+      const liquidator = await fundedLiquidator(1_000 * USDC);
+      const otherAccount = await createAccount(
+        provider.connection,
+        liquidator.keypair,
+        otherMint,
+        liquidator.keypair.publicKey
+      );
+      await liquidateMulti(
+        liquidator.keypair,
+        liquidator.usdcAccount,
+        owner.publicKey,
+        200 * USDC,
+        new Map([
+          [wsolMint.toBase58(), liquidator.collateralAccount],
+          [otherMint.toBase58(), otherAccount],
+        ])
+      );
+
+      const position = await fetchPosition(owner.publicKey);
+      return new Map(
+        position.collaterals.map((entry: any) => [entry.mint.toBase58(), entry.amount.toNumber()])
+      );
+    }
+
+    // $200 repaid plus the 6% bonus is $212, or 2.65 units at $80
+    const SEIZED = 2.65 * SOL;
+
+    it("seizes the lowest-priority collateral first", async () => {
+      const { wsolMint } = await ensureConfig();
+      await setCollateralType(otherMint, { liquidationPriority: 0 });
+      await setCollateralType(wsolMint, { liquidationPriority: 1 });
+
+      const owner = await twoCollateralPosition();
+      const amounts = await liquidateAfterPriceDrop(owner);
+
+      expect(amounts.get(otherMint.toBase58())).to.be.closeTo(5 * SOL - SEIZED, SOL / 1000);
+      expect(amounts.get(wsolMint.toBase58())).to.equal(5 * SOL);
+    });
+
+    it("follows a reversed priority", async () => {
+      const { wsolMint } = await ensureConfig();
+      await setCollateralType(otherMint, { liquidationPriority: 2 });
+      await setCollateralType(wsolMint, { liquidationPriority: 1 });
+
+      const owner = await twoCollateralPosition();
+      const amounts = await liquidateAfterPriceDrop(owner);
+
+      expect(amounts.get(wsolMint.toBase58())).to.be.closeTo(5 * SOL - SEIZED, SOL / 1000);
+      expect(amounts.get(otherMint.toBase58())).to.equal(5 * SOL);
+    });
+
+    it("seizes more of a collateral with a higher bonus override", async () => {
+      const { wsolMint } = await ensureConfig();
+      // The other collateral pays 10%, WSOL the global 6%
+      await setCollateralType(otherMint, { liquidationBonusBps: 1000 });
+
+      await setCollateralType(otherMint, { liquidationPriority: 0 });
+      await setCollateralType(wsolMint, { liquidationPriority: 1 });
+      const highBonus = await liquidateAfterPriceDrop(await twoCollateralPosition());
+      const highSeized = 5 * SOL - highBonus.get(otherMint.toBase58());
+
+      await setCollateralType(otherMint, { liquidationPriority: 2 });
+      const lowBonus = await liquidateAfterPriceDrop(await twoCollateralPosition());
+      const lowSeized = 5 * SOL - lowBonus.get(wsolMint.toBase58());
+
+      // Same $200 repaid: $220 is 2.75 units at $80 against 2.65 at 6%
+      expect(highSeized).to.be.closeTo(2.75 * SOL, SOL / 1000);
+      expect(lowSeized).to.be.closeTo(SEIZED, SOL / 1000);
+      expect(highSeized).to.be.greaterThan(lowSeized);
+
+      await setCollateralType(otherMint, { liquidationBonusBps: 0 });
+    });
+
+    it("rejects a bonus override above the maximum", async () => {
+      await expectError(setCollateralType(otherMint, { liquidationBonusBps: 2001 }), "InvalidPercentage");
+    });
+
+    it("values each collateral at its own oracle and decimals", async () => {
+      const { wsolMint } = await ensureConfig();
+      await setCollateralType(wsolMint, { oracle: pythFeed("sol-usd-100") });
+      // A 6-decimal collateral on its own $80 feed
+      const usdMint = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+      await initVault(usdMint);
+      await setCollateralType(usdMint, { oracle: pythFeed("sol-usd-80") });
+
+      const owner = await fundedKeypair(10);
+      await openPosition(owner);
+      await depositMultiCollateral(owner, wsolMint, await wrapSol(owner, 5 * SOL), 5 * SOL);
+      const source = await createAccount(provider.connection, owner, usdMint, owner.publicKey);
+      await mintTo(provider.connection, admin, usdMint, source, admin, 5_000_000);
+      await depositMultiCollateral(owner, usdMint, source, 5_000_000);
+      await recordDebt(owner, 500 * USDC);
+
+      // $500 of WSOL plus 5 units at $80: 900 * 60% / 500 = 1.08
+      const health = await refreshHealthFactor(owner.publicKey);
+      expect(health).to.be.closeTo(1.08 * HEALTH_FACTOR_SCALE, 10);
+    });
+
+    it("leaves slot collateral to the multi-collateral liquidation", async () => {
+      const { wsolMint } = await ensureConfig();
+      // A position that once held legacy WSOL keeps its collateral mint
+      const owner = await userWithCollateral(5 * SOL);
+      await withdrawCollateral(owner, 5 * SOL);
+      await setCollateralType(wsolMint, { oracle: pythFeed("sol-usd-100") });
+      await depositMultiCollateral(owner, wsolMint, await wrapSol(owner, 0), 5 * SOL);
+      await recordDebt(owner, 250 * USDC);
+
+      const liquidator = await fundedLiquidator(1_000 * USDC);
+      await expectError(
+        liquidate(liquidator, owner.publicKey, 100 * USDC, pythFeed("sol-usd-80")),
+        "SlotCollateralUnsupported"
+      );
+    });
+  });
+});
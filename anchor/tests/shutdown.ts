@@ -0,0 +1,60 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  ensureConfig,
+  userWithCollateral,
+  borrowUsdc,
+  recordDebt,
+  repayAllUsdc,
+  withdrawCollateral,
+  depositWsol,
+  fundedLiquidator,
+  liquidate,
+  fetchPosition,
+  fetchConfig,
+  setPaused,
+  setPauseFlags,
+  shutdownProtocol,
+  expectError,
+} from "./helpers";
+
+const SOL = anchor.web3.LAMPORTS_PER_SOL;
+const USDC = 1_000_000;
+
+// Shutdown is permanent for the shared config, so this file has to sort
+// after every other test file
+describe("shutdown", () => {
+  it("rejects a shutdown without the confirmation phrase", async () => {
+    await ensureConfig();
+    await expectError(shutdownProtocol("shutdown"), "ConfirmationRequired");
+    expect((await fetchConfig()).shutdown).to.equal(false);
+  });
+
+  it("lets users exit but permanently blocks new risk", async () => {
+    const borrower = await userWithCollateral(10 * SOL);
+    await borrowUsdc(borrower, 100 * USDC);
+    const underwater = await userWithCollateral(10 * SOL);
+    await recordDebt(underwater, 700 * USDC);
+    const liquidator = await fundedLiquidator(1_000 * USDC);
+
+    await shutdownProtocol();
+    expect((await fetchConfig()).shutdown).to.equal(true);
+
+    await expectError(borrowUsdc(borrower, 10 * USDC), "ProtocolPaused");
+    await expectError(depositWsol(borrower, SOL), "ProtocolPaused");
+    await expectError(liquidate(liquidator, underwater.publicKey, 350 * USDC), "ProtocolPaused");
+
+    // Repaying and withdrawing stay open
+    await repayAllUsdc(borrower, 101 * USDC);
+    await withdrawCollateral(borrower, 10 * SOL);
+    const position = await fetchPosition(borrower.publicKey);
+    expect(position.debtUsdc.toNumber()).to.equal(0);
+    expect(position.collateralAmount.toNumber()).to.equal(0);
+
+    // Neither the pause nor the pause flags can lift it, and it can't be repeated
+    await setPaused(false);
+    await setPauseFlags(0);
+    await expectError(borrowUsdc(underwater, 10 * USDC), "ProtocolPaused");
+    await expectError(shutdownProtocol(), "InvalidParameter");
+  });
+});